@@ -0,0 +1,907 @@
+/// This module handles the non-interactive commands of the CLI, e.g. `my-jira timesheet`.
+use anyhow::{anyhow, Result};
+use chrono::Datelike;
+use itertools::Itertools;
+use std::fs;
+use std::str::FromStr;
+
+use crate::dates::{format_duration, now, parse_date, today};
+use crate::db::JiraDatabase;
+use crate::export::{Format, Selection};
+use crate::import::Mapping;
+use crate::io_utils::current_user_name;
+use crate::models::{ColumnSetting, Filter, Table};
+use crate::ui::{Keymap, Theme};
+use crate::KEYMAP_PATH;
+use crate::{export, import, jira, reports};
+
+const USAGE: &str = "Usage: my-jira [command]
+
+Without a command the interactive app is started, `--no-color` turns off its colours.
+Colours are also turned off when NO_COLOR is set or the output is not a terminal.
+
+Commands:
+  tui                                     start the full-screen app, navigate with the arrow keys
+  timer start <story-id>                  start the timer for a story, e.g. 12 or WEB-12
+  timer stop [--note TEXT]                stop the timer and log the work
+  timer status                            show the running timer
+  timesheet [--user NAME] [--week DATE]   logged work per person and week
+  release-notes <version>                 Markdown release notes of a version
+  status-rules [--all-closed S] [--all-done S] [--in-progress S] [--not-started S]
+               [--no-stories S|keep]      show or change how derived epic statuses are calculated
+  purge [--all]                           remove expired items (or all) from the trash
+  trash-retention [DAYS|off]              show or change how long deleted items are kept
+  check [--repair]                        check the database for inconsistencies and fix them
+  theme [NAME]                            show or change your colour theme of the interactive app
+  page-size [ROWS|auto]                   show or change the rows of long tables per page
+  columns <epics|stories> [COLUMN[:WIDTH],...|default]
+                                          show or change the columns of a table, out of id, name,
+                                          status, progress, priority, updated and due
+  export <csv|jsonl|markdown|jira-json|jira-csv> [--output FILE] [--epic ID] [--status S]
+         [--unresolved] [--mine]          export epics and stories, to stdout without --output
+  import <FILE> [--format csv|jira-json|jira-csv] [--mapping FILE] [--map FIELD=COLUMN]...
+         [--epic NAME] [--dry-run]        import stories, from CSV with the columns mapped or from
+                                          a Jira search result or CSV export, --dry-run only shows
+                                          what would be imported
+  keys                                    show the key bindings, changed in data/keymap.json
+  help                                    show this message";
+
+/// Executes the command given on the command line.
+pub fn execute(db: &JiraDatabase, args: &[String]) -> Result<()> {
+    let Some((command, options)) = args.split_first() else {
+        return Err(anyhow!("No command given!\n\n{USAGE}"));
+    };
+
+    match command.as_str() {
+        "timer" => timer(db, options),
+        "timesheet" => timesheet(db, options),
+        "release-notes" => release_notes(db, options),
+        "status-rules" => status_rules(db, options),
+        "purge" => purge(db, options),
+        "check" => check(db, options),
+        "trash-retention" => trash_retention(db, options),
+        "theme" => theme(db, options),
+        "page-size" => page_size(db, options),
+        "columns" => columns(db, options),
+        "export" => export(db, options),
+        "import" => import(db, options),
+        "keys" => keys(KEYMAP_PATH),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(anyhow!("Unknown command '{command}'!\n\n{USAGE}")),
+    }
+}
+
+fn timer(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    match options.first().map(|option| option.as_str()) {
+        Some("start") => {
+            let input = options.get(1).ok_or_else(|| anyhow!("Missing story id!"))?;
+            let db_state = db.read_db()?;
+            let story_id = db_state
+                .resolve_id(input)
+                .ok_or_else(|| anyhow!("Invalid story id '{input}'!"))?;
+            db.start_timer(story_id, current_user_name(), now())?;
+            println!("Timer started for story {}.", db_state.display_id(story_id));
+        }
+        Some("stop") => {
+            let note = get_option(options, "--note")?.unwrap_or("").to_string();
+            match db.stop_timer(now(), note)? {
+                (story_id, Some(work_log)) => println!(
+                    "Logged {} on story {}.",
+                    format_duration(work_log.duration),
+                    db.read_db()?.display_id(story_id)
+                ),
+                (story_id, None) => {
+                    println!("Timer stopped, story {story_id} doesn't exist anymore.")
+                }
+            }
+        }
+        Some("status") | None => {
+            let db_state = db.read_db()?;
+            match &db_state.active_timer {
+                Some(timer) => println!(
+                    "Timer running for story {} since {} ({}).",
+                    db_state.display_id(timer.story_id),
+                    timer.started.format("%Y-%m-%d %H:%M"),
+                    format_duration(timer.elapsed(now()))
+                ),
+                None => println!("No timer is running."),
+            }
+        }
+        Some(subcommand) => return Err(anyhow!("Unknown timer command '{subcommand}'!")),
+    }
+    Ok(())
+}
+
+fn timesheet(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let user = get_option(options, "--user")?;
+    // any date within the week, e.g. `2026-10-19` or `today`
+    let week = get_option(options, "--week")?
+        .map(|date| parse_date(date, today()))
+        .transpose()?
+        .map(|date| date.iso_week());
+
+    let db_state = db.read_db()?;
+    print!("{}", reports::timesheet(&db_state, user, week));
+    Ok(())
+}
+
+fn release_notes(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let version = options.first().ok_or_else(|| anyhow!("Missing version!"))?;
+
+    let db_state = db.read_db()?;
+    print!("{}", reports::release_notes(&db_state, version)?);
+    Ok(())
+}
+
+fn export(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let format = options.first().ok_or_else(|| {
+        anyhow!("Missing format, use csv, jsonl, markdown, jira-json or jira-csv!")
+    })?;
+    let format = Format::from_str(format)?;
+
+    let db_state = db.read_db()?;
+    let epic = get_option(options, "--epic")?
+        .map(|input| {
+            db_state
+                .resolve_id(input)
+                .filter(|id| db_state.epics.contains_key(id))
+                .ok_or_else(|| anyhow!("Invalid epic id '{input}'!"))
+        })
+        .transpose()?;
+    let selection = Selection {
+        filter: Filter {
+            hide_done: options.iter().any(|option| option == "--unresolved"),
+            status: get_option(options, "--status")?
+                .map(str::parse)
+                .transpose()?,
+            only_mine: options.iter().any(|option| option == "--mine"),
+        },
+        user: current_user_name(),
+        epic,
+    };
+
+    let exported = export::export(&db_state, format, &selection)?;
+    match get_option(options, "--output")? {
+        Some(path) => {
+            fs::write(path, exported).map_err(|e| anyhow!("Can't write '{path}'! {e}"))?;
+            println!("Exported to {path}.");
+        }
+        None => print!("{exported}"),
+    }
+    Ok(())
+}
+
+fn import(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let path = options
+        .first()
+        .filter(|path| !path.starts_with("--"))
+        .ok_or_else(|| anyhow!("Missing file!"))?;
+    let text = fs::read_to_string(path).map_err(|e| anyhow!("Can't read '{path}'! {e}"))?;
+
+    let mut mapping = match get_option(options, "--mapping")? {
+        Some(mapping_path) => Mapping::load(mapping_path)?,
+        None => Mapping::default(),
+    };
+    // `--map` can be given several times, unlike the other options
+    for (index, _) in options.iter().enumerate().filter(|(_, o)| *o == "--map") {
+        let spec = options
+            .get(index + 1)
+            .ok_or_else(|| anyhow!("Missing value for option '--map'!"))?;
+        mapping.set(spec)?;
+    }
+
+    let db_state = db.read_db()?;
+    let default_epic = get_option(options, "--epic")?;
+    let plan = match get_option(options, "--format")?.unwrap_or("csv") {
+        "csv" => import::plan(&db_state, &text, &mapping, default_epic, today())?,
+        "jira-json" => jira::read_json(&db_state, &text, default_epic)?,
+        "jira-csv" => jira::read_csv(&db_state, &text, default_epic)?,
+        format => {
+            return Err(anyhow!(
+                "Invalid format '{format}', use csv, jira-json or jira-csv!"
+            ))
+        }
+    };
+
+    if options.iter().any(|option| option == "--dry-run") {
+        for epic in &plan.new_epics {
+            println!("new epic: {}", epic.name);
+        }
+        for new_story in &plan.stories {
+            println!(
+                "{}: story '{}' in epic '{}' ({})",
+                new_story.source, new_story.story.name, new_story.epic, new_story.story.status
+            );
+        }
+        for error in plan.errors.iter().chain(&plan.warnings) {
+            println!("{error}");
+        }
+        println!(
+            "Would import {} story(ies) and create {} epic(s), {} row(s) with errors.",
+            plan.stories.len(),
+            plan.new_epics.len(),
+            plan.errors.len()
+        );
+        return Ok(());
+    }
+
+    if !plan.errors.is_empty() {
+        return Err(anyhow!(
+            "Nothing imported, {} row(s) with errors:\n{}",
+            plan.errors.len(),
+            plan.errors.join("\n")
+        ));
+    }
+    for warning in &plan.warnings {
+        println!("{warning}");
+    }
+    let epics = plan.new_epics.len();
+    let stories = import::apply(db, plan)?.len();
+    println!("Imported {stories} story(ies) and created {epics} epic(s).");
+    Ok(())
+}
+
+/// Shows the key bindings of the keymap file, the defaults if there is none
+fn keys(keymap_path: &str) -> Result<()> {
+    let keymap = Keymap::load(keymap_path)?;
+    println!("{}", keymap.describe());
+    Ok(())
+}
+
+fn status_rules(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let mut rules = db.read_db()?.derived_status_rules;
+
+    if !options.is_empty() {
+        if let Some(status) = get_option(options, "--all-closed")? {
+            rules.all_closed = status.parse()?;
+        }
+        if let Some(status) = get_option(options, "--all-done")? {
+            rules.all_done = status.parse()?;
+        }
+        if let Some(status) = get_option(options, "--in-progress")? {
+            rules.in_progress = status.parse()?;
+        }
+        if let Some(status) = get_option(options, "--not-started")? {
+            rules.not_started = status.parse()?;
+        }
+        if let Some(status) = get_option(options, "--no-stories")? {
+            rules.no_stories = match status {
+                "keep" => None,
+                status => Some(status.parse()?),
+            };
+        }
+        db.update_derived_status_rules(rules.clone())?;
+    }
+
+    println!("Derived epic status rules:");
+    println!("  all stories closed:   {}", rules.all_closed);
+    println!("  all stories done:     {}", rules.all_done);
+    println!("  stories in progress:  {}", rules.in_progress);
+    println!("  no story started:     {}", rules.not_started);
+    println!(
+        "  no stories:           {}",
+        rules
+            .no_stories
+            .map_or("keep current status".to_string(), |status| status
+                .to_string())
+    );
+    Ok(())
+}
+
+fn purge(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let all = options.iter().any(|option| option == "--all");
+
+    if !all && db.read_db()?.trash_retention_days.is_none() {
+        println!("No trash retention configured, use --all to empty the trash.");
+        return Ok(());
+    }
+
+    let count = db.purge_trash(now(), all)?;
+    println!("Purged {count} item(s) from the trash.");
+    Ok(())
+}
+
+fn trash_retention(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let retention_days = match options.first().map(|option| option.as_str()) {
+        None => db.read_db()?.trash_retention_days,
+        Some("off") => {
+            db.update_trash_retention(None)?;
+            None
+        }
+        Some(days) => {
+            let days = days
+                .parse::<u32>()
+                .map_err(|_| anyhow!("Invalid number of days '{days}'!"))?;
+            db.update_trash_retention(Some(days))?;
+            Some(days)
+        }
+    };
+
+    match retention_days {
+        Some(days) => println!("Deleted items are kept for {days} days."),
+        None => println!("Deleted items are kept until they are restored or purged with --all."),
+    }
+    Ok(())
+}
+
+fn page_size(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let user = current_user_name();
+    let page_size = match options.first().map(|option| option.as_str()) {
+        None => db.read_db()?.settings_of(&user).page_size,
+        Some("auto") => {
+            db.update_user_settings(&user, |settings| settings.page_size = None)?;
+            None
+        }
+        Some(rows) => {
+            let rows = rows
+                .parse::<usize>()
+                .ok()
+                .filter(|&rows| rows > 0)
+                .ok_or_else(|| anyhow!("Invalid number of rows '{rows}'!"))?;
+            db.update_user_settings(&user, |settings| settings.page_size = Some(rows))?;
+            Some(rows)
+        }
+    };
+
+    match page_size {
+        Some(rows) => println!("Long tables of {user} show {rows} rows per page."),
+        None => println!("Long tables of {user} show as many rows as fit into the terminal."),
+    }
+    Ok(())
+}
+
+fn columns(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let user = current_user_name();
+    let table = options
+        .first()
+        .ok_or_else(|| anyhow!("Missing table, use epics or stories!"))?;
+    let table = Table::from_str(table)?;
+
+    match options.get(1).map(|option| option.as_str()) {
+        None => {}
+        Some("default") => db.update_user_settings(&user, |settings| {
+            settings.update_table(table, |settings| settings.columns.clear())
+        })?,
+        Some(columns) => {
+            let columns = columns
+                .split(',')
+                .map(ColumnSetting::from_str)
+                .collect::<Result<Vec<_>>>()?;
+            if columns
+                .iter()
+                .map(|setting| setting.column)
+                .unique()
+                .count()
+                < columns.len()
+            {
+                return Err(anyhow!("Every column can only be shown once!"));
+            }
+            db.update_user_settings(&user, |settings| {
+                settings.update_table(table, |settings| settings.columns = columns)
+            })?;
+        }
+    }
+
+    let columns = db.read_db()?.settings_of(&user).table(table).columns;
+    println!(
+        "The {} table of {user} shows: {}",
+        options[0].to_lowercase(),
+        columns
+            .iter()
+            .map(|setting| match setting.width {
+                Some(width) => format!("{}:{width}", setting.column.name()),
+                None => setting.column.name().to_owned(),
+            })
+            .join(", ")
+    );
+    Ok(())
+}
+
+fn theme(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let user = current_user_name();
+    let name = match options.first() {
+        None => db
+            .read_db()?
+            .settings_of(&user)
+            .theme
+            .unwrap_or_else(|| Theme::default().name.to_owned()),
+        Some(name) => {
+            let theme = Theme::by_name(name).ok_or_else(|| {
+                anyhow!(
+                    "Unknown theme '{name}'! Available themes: {}",
+                    Theme::names().join(", ")
+                )
+            })?;
+            db.update_user_settings(&user, |settings| {
+                settings.theme = Some(theme.name.to_owned())
+            })?;
+            theme.name.to_owned()
+        }
+    };
+
+    println!(
+        "Theme of {user}: {name} (available: {})",
+        Theme::names().join(", ")
+    );
+    Ok(())
+}
+
+fn check(db: &JiraDatabase, options: &[String]) -> Result<()> {
+    let repair = options.iter().any(|option| option == "--repair");
+
+    let problems = if repair { db.repair()? } else { db.check()? };
+    if problems.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+
+    for problem in &problems {
+        println!("- {problem}");
+    }
+    match repair {
+        true => {
+            println!("Repaired {} problem(s).", problems.len());
+            Ok(())
+        }
+        false => Err(anyhow!(
+            "Found {} problem(s), run `my-jira check --repair` to fix them.",
+            problems.len()
+        )),
+    }
+}
+
+/// Returns the value of an option like `--user alice`, `None` if the option is not given.
+fn get_option<'a>(options: &'a [String], name: &str) -> Result<Option<&'a str>> {
+    match options.iter().position(|option| option == name) {
+        None => Ok(None),
+        Some(index) => options
+            .get(index + 1)
+            .map(|value| Some(value.as_str()))
+            .ok_or_else(|| anyhow!("Missing value for option '{name}'!")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+    use crate::models::{Epic, Status, Story, TableSettings, Version};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn execute_should_fail_with_unknown_command() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+
+        assert_eq!(execute(&db, &args(&["foo"])).is_err(), true);
+        assert_eq!(execute(&db, &args(&[])).is_err(), true);
+    }
+
+    #[test]
+    fn execute_should_run_timesheet() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+
+        assert_eq!(execute(&db, &args(&["timesheet"])).is_ok(), true);
+        assert_eq!(
+            execute(&db, &args(&["timesheet", "--week", "2026-10-19"])).is_ok(),
+            true
+        );
+        assert_eq!(
+            execute(&db, &args(&["timesheet", "--week", "someday"])).is_err(),
+            true
+        );
+        assert_eq!(execute(&db, &args(&["timesheet", "--user"])).is_err(), true);
+    }
+
+    #[test]
+    fn execute_should_start_and_stop_timer() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert_eq!(execute(&db, &args(&["timer", "stop"])).is_err(), true);
+        assert_eq!(execute(&db, &args(&["timer", "start"])).is_err(), true);
+        assert_eq!(
+            execute(&db, &args(&["timer", "start", "999"])).is_err(),
+            true
+        );
+        assert_eq!(
+            execute(&db, &args(&["timer", "start", &story_id.to_string()])).is_ok(),
+            true
+        );
+        assert_eq!(execute(&db, &args(&["timer"])).is_ok(), true);
+        assert_eq!(
+            execute(&db, &args(&["timer", "stop", "--note", "review"])).is_ok(),
+            true
+        );
+
+        let db_state = db.read_db().unwrap();
+        let story = db_state.stories.get(&story_id).unwrap();
+        assert_eq!(db_state.active_timer, None);
+        assert_eq!(story.work_logs[0].note, "review".to_owned());
+    }
+
+    #[test]
+    fn execute_should_run_release_notes() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        db.create_version(Version::new("1.0".to_owned(), None))
+            .unwrap();
+
+        assert_eq!(execute(&db, &args(&["release-notes"])).is_err(), true);
+        assert_eq!(
+            execute(&db, &args(&["release-notes", "2.0"])).is_err(),
+            true
+        );
+        assert_eq!(execute(&db, &args(&["release-notes", "1.0"])).is_ok(), true);
+    }
+
+    #[test]
+    fn execute_should_update_status_rules() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+
+        assert_eq!(execute(&db, &args(&["status-rules"])).is_ok(), true);
+        assert_eq!(
+            execute(
+                &db,
+                &args(&[
+                    "status-rules",
+                    "--all-done",
+                    "closed",
+                    "--no-stories",
+                    "open"
+                ])
+            )
+            .is_ok(),
+            true
+        );
+        assert_eq!(
+            execute(&db, &args(&["status-rules", "--all-done", "done"])).is_err(),
+            true
+        );
+
+        let rules = db.read_db().unwrap().derived_status_rules;
+        assert_eq!(rules.all_done, Status::Closed);
+        assert_eq!(rules.no_stories, Some(Status::Open));
+
+        assert_eq!(
+            execute(&db, &args(&["status-rules", "--no-stories", "keep"])).is_ok(),
+            true
+        );
+        assert_eq!(db.read_db().unwrap().derived_status_rules.no_stories, None);
+    }
+
+    #[test]
+    fn execute_should_purge_trash() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        db.delete_epic(epic_id).unwrap();
+
+        // items are kept without a retention, unless all items are purged
+        assert_eq!(execute(&db, &args(&["purge"])).is_ok(), true);
+        assert_eq!(db.read_db().unwrap().trash.len(), 1);
+
+        assert_eq!(
+            execute(&db, &args(&["trash-retention", "x"])).is_err(),
+            true
+        );
+        assert_eq!(
+            execute(&db, &args(&["trash-retention", "30"])).is_ok(),
+            true
+        );
+        assert_eq!(db.read_db().unwrap().trash_retention_days, Some(30));
+        assert_eq!(execute(&db, &args(&["purge"])).is_ok(), true);
+        assert_eq!(db.read_db().unwrap().trash.len(), 1);
+
+        assert_eq!(execute(&db, &args(&["purge", "--all"])).is_ok(), true);
+        assert_eq!(db.read_db().unwrap().trash.len(), 0);
+
+        assert_eq!(
+            execute(&db, &args(&["trash-retention", "off"])).is_ok(),
+            true
+        );
+        assert_eq!(db.read_db().unwrap().trash_retention_days, None);
+    }
+
+    #[test]
+    fn execute_should_change_theme() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+
+        assert_eq!(execute(&db, &args(&["theme"])).is_ok(), true);
+        assert_eq!(execute(&db, &args(&["theme", "sepia"])).is_err(), true);
+        assert_eq!(
+            execute(&db, &args(&["theme", "High-Contrast"])).is_ok(),
+            true
+        );
+        assert_eq!(
+            db.read_db()
+                .unwrap()
+                .settings_of(&current_user_name())
+                .theme,
+            Some("high-contrast".to_owned())
+        );
+    }
+
+    #[test]
+    fn execute_should_list_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let keymap_path = dir.path().join("keymap.json");
+        let keymap_path = keymap_path.to_str().unwrap();
+
+        // the default keymap without a file
+        assert_eq!(keys(keymap_path).is_ok(), true);
+        std::fs::write(keymap_path, r#"{"previous": ["p", "back"]}"#).unwrap();
+        assert_eq!(keys(keymap_path).is_ok(), true);
+        std::fs::write(keymap_path, r#"{"previous": ["1"]}"#).unwrap();
+        assert_eq!(keys(keymap_path).is_err(), true);
+    }
+
+    #[test]
+    fn execute_should_change_page_size() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        let page_size = || {
+            db.read_db()
+                .unwrap()
+                .settings_of(&current_user_name())
+                .page_size
+        };
+
+        assert_eq!(execute(&db, &args(&["page-size", "25"])).is_ok(), true);
+        assert_eq!(page_size(), Some(25));
+        assert_eq!(execute(&db, &args(&["page-size"])).is_ok(), true);
+        assert_eq!(execute(&db, &args(&["page-size", "0"])).is_err(), true);
+        assert_eq!(execute(&db, &args(&["page-size", "many"])).is_err(), true);
+        assert_eq!(page_size(), Some(25));
+        assert_eq!(execute(&db, &args(&["page-size", "auto"])).is_ok(), true);
+        assert_eq!(page_size(), None);
+    }
+
+    #[test]
+    fn execute_should_change_columns() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        let columns = || {
+            db.read_db()
+                .unwrap()
+                .settings_of(&current_user_name())
+                .table(Table::Stories)
+                .columns
+        };
+
+        assert_eq!(
+            execute(&db, &args(&["columns", "stories", "id,name:30,priority"])).is_ok(),
+            true
+        );
+        assert_eq!(
+            columns(),
+            vec![
+                "id".parse().unwrap(),
+                "name:30".parse().unwrap(),
+                "priority".parse().unwrap()
+            ]
+        );
+        assert_eq!(execute(&db, &args(&["columns", "stories"])).is_ok(), true);
+        assert_eq!(execute(&db, &args(&["columns"])).is_err(), true);
+        assert_eq!(execute(&db, &args(&["columns", "tasks"])).is_err(), true);
+        assert_eq!(
+            execute(&db, &args(&["columns", "stories", "id,owner"])).is_err(),
+            true
+        );
+        assert_eq!(
+            execute(&db, &args(&["columns", "stories", "id,name:0"])).is_err(),
+            true
+        );
+        assert_eq!(
+            execute(&db, &args(&["columns", "stories", "id,id"])).is_err(),
+            true
+        );
+        assert_eq!(columns().len(), 3);
+
+        assert_eq!(
+            execute(&db, &args(&["columns", "stories", "default"])).is_ok(),
+            true
+        );
+        assert_eq!(columns(), TableSettings::default_columns(Table::Stories));
+    }
+
+    #[test]
+    fn execute_should_export_to_a_file() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        let epic_id = db
+            .create_epic(Epic::new("Checkout".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("Pay".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::Closed).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.md");
+        let path = path.to_str().unwrap();
+
+        assert_eq!(
+            execute(&db, &args(&["export", "markdown", "--output", path])).is_ok(),
+            true
+        );
+        let exported = fs::read_to_string(path).unwrap();
+        assert_eq!(exported.contains("## 1 Checkout"), true);
+        assert_eq!(exported.contains("- [x] STORY 2 Pay (CLOSED)"), true);
+
+        assert_eq!(
+            execute(
+                &db,
+                &args(&["export", "csv", "--unresolved", "--output", path])
+            )
+            .is_ok(),
+            true
+        );
+        assert_eq!(fs::read_to_string(path).unwrap().contains("Pay"), false);
+
+        assert_eq!(execute(&db, &args(&["export"])).is_err(), true);
+        assert_eq!(execute(&db, &args(&["export", "xml"])).is_err(), true);
+        assert_eq!(
+            execute(&db, &args(&["export", "csv", "--epic", "2"])).is_err(),
+            true
+        );
+        assert_eq!(
+            execute(&db, &args(&["export", "csv", "--status", "done"])).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn execute_should_check_and_repair() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        assert_eq!(execute(&db, &args(&["check"])).is_ok(), true);
+
+        let mut db_state = db.read_db().unwrap();
+        db_state.last_item_id = 0;
+        db.database.write_db(&db_state).unwrap();
+
+        assert_eq!(execute(&db, &args(&["check"])).is_err(), true);
+        assert_eq!(execute(&db, &args(&["check", "--repair"])).is_ok(), true);
+        assert_eq!(execute(&db, &args(&["check"])).is_ok(), true);
+    }
+
+    #[test]
+    fn execute_should_import_from_a_file() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        db.create_epic(Epic::new("Checkout".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backlog.csv");
+        let path = path.to_str().unwrap();
+        fs::write(path, "Summary,Epic\nPay,Checkout\nSearch,Search\n").unwrap();
+
+        // a dry run doesn't change anything
+        assert_eq!(
+            execute(
+                &db,
+                &args(&["import", path, "--map", "name=Summary", "--dry-run"])
+            )
+            .is_ok(),
+            true
+        );
+        assert_eq!(db.read_db().unwrap().stories.len(), 0);
+
+        // the name isn't mapped
+        assert_eq!(execute(&db, &args(&["import", path])).is_err(), true);
+
+        assert_eq!(
+            execute(&db, &args(&["import", path, "--map", "name=Summary"])).is_ok(),
+            true
+        );
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics.len(), 2);
+        assert_eq!(db_state.epics[&1].stories, vec![3]);
+        assert_eq!(db_state.stories[&4].name, "Search");
+
+        // rows with errors abort the import
+        fs::write(
+            path,
+            "name,epic,status\nShip,Checkout,\nMail,Checkout,doing\n",
+        )
+        .unwrap();
+        assert_eq!(execute(&db, &args(&["import", path])).is_err(), true);
+        assert_eq!(db.read_db().unwrap().stories.len(), 2);
+        assert_eq!(execute(&db, &args(&["import"])).is_err(), true);
+    }
+
+    #[test]
+    fn execute_should_import_and_export_jira_formats() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        let fixture = "tests/fixtures/jira-search.json";
+
+        // issues without an epic need one
+        assert_eq!(
+            execute(&db, &args(&["import", fixture, "--format", "jira-json"])).is_err(),
+            true
+        );
+        assert_eq!(
+            execute(&db, &args(&["import", fixture, "--format", "xml"])).is_err(),
+            true
+        );
+        assert_eq!(
+            execute(
+                &db,
+                &args(&[
+                    "import",
+                    fixture,
+                    "--format",
+                    "jira-json",
+                    "--epic",
+                    "Chores"
+                ])
+            )
+            .is_ok(),
+            true
+        );
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics.len(), 2);
+        assert_eq!(db_state.stories.len(), 4);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.csv");
+        let path = path.to_str().unwrap();
+        assert_eq!(
+            execute(&db, &args(&["export", "jira-csv", "--output", path])).is_ok(),
+            true
+        );
+        let exported = fs::read_to_string(path).unwrap();
+        assert_eq!(
+            exported.starts_with("Summary,Issue key,Issue id,Parent id,"),
+            true
+        );
+        assert_eq!(
+            exported.contains("Write card tests,5,5,1,Task,To Do,To Do,bob"),
+            true
+        );
+    }
+
+    #[test]
+    fn get_option_should_work() {
+        let options = args(&["--user", "alice", "--week"]);
+
+        assert_eq!(get_option(&options, "--user").unwrap(), Some("alice"));
+        assert_eq!(get_option(&options, "--foo").unwrap(), None);
+        assert_eq!(get_option(&options, "--week").is_err(), true);
+    }
+}
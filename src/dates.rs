@@ -0,0 +1,218 @@
+/// This module contains the date and time helpers used for due dates and time tracking.
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime, Timelike, Weekday};
+
+use crate::models::Minutes;
+
+const MINUTES_PER_HOUR: Minutes = 60;
+const HOURS_PER_DAY: Minutes = 8;
+
+/// The current date in the local time zone.
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// The current date and time in the local time zone, without sub-second precision.
+pub fn now() -> NaiveDateTime {
+    let now = Local::now().naive_local();
+    now.with_nanosecond(0).unwrap_or(now)
+}
+
+/// Parses a due date as entered by the user, relative to `today`.
+///
+/// Accepted formats:
+/// - `2026-11-01`
+/// - `today`, `tomorrow`
+/// - `+3d`, `+2w` (days or weeks from today)
+/// - `friday`, `next friday` (the next such weekday after today)
+pub fn parse_date(input: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let input = input.trim().to_lowercase();
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    match input.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" => return add_days(today, 1),
+        _ => {}
+    }
+
+    if let Some(offset) = input.strip_prefix('+') {
+        let invalid = || anyhow!("Invalid date offset '{input}'!");
+        let (amount, days_per_unit) = match (offset.strip_suffix('d'), offset.strip_suffix('w')) {
+            (Some(amount), _) => (amount, 1),
+            (_, Some(amount)) => (amount, 7),
+            _ => return Err(invalid()),
+        };
+        let days = amount
+            .parse::<u64>()
+            .ok()
+            .and_then(|amount| amount.checked_mul(days_per_unit))
+            .ok_or_else(invalid)?;
+        return add_days(today, days);
+    }
+
+    let weekday = input.strip_prefix("next ").unwrap_or(&input);
+    let weekday = weekday
+        .parse::<Weekday>()
+        .map_err(|_| anyhow!("Invalid date '{input}'!"))?;
+
+    // always a date in the future, so "friday" on a friday is a week from now
+    let days_ahead =
+        (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    let days_ahead = if days_ahead == 0 { 7 } else { days_ahead };
+    add_days(today, days_ahead as u64)
+}
+
+/// Parses a duration like `1d 2h 30m`, `1h30m` or `45m`. A day is a working day of 8 hours.
+pub fn parse_duration(input: &str) -> Result<Minutes> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err(anyhow!("Empty duration!"));
+    }
+
+    let mut total: Minutes = 0;
+    let mut amount = String::new();
+    for c in input.chars().filter(|c| !c.is_whitespace()) {
+        if c.is_ascii_digit() {
+            amount.push(c);
+            continue;
+        }
+        let value = amount
+            .parse::<Minutes>()
+            .map_err(|_| anyhow!("Invalid duration '{input}'!"))?;
+        let factor = match c {
+            'd' => HOURS_PER_DAY * MINUTES_PER_HOUR,
+            'h' => MINUTES_PER_HOUR,
+            'm' => 1,
+            _ => return Err(anyhow!("Invalid duration '{input}'!")),
+        };
+        total = value
+            .checked_mul(factor)
+            .and_then(|minutes| total.checked_add(minutes))
+            .ok_or_else(|| anyhow!("Invalid duration '{input}', too long!"))?;
+        amount.clear();
+    }
+
+    // a trailing number without unit is not accepted
+    if !amount.is_empty() {
+        return Err(anyhow!("Invalid duration '{input}', missing unit!"));
+    }
+
+    Ok(total)
+}
+
+/// Adds up durations, a total too large for `Minutes` stays at the maximum
+pub fn total_duration(durations: impl IntoIterator<Item = Minutes>) -> Minutes {
+    durations.into_iter().fold(0, Minutes::saturating_add)
+}
+
+/// Formats a duration in hours and minutes, e.g. `2h 30m`.
+pub fn format_duration(minutes: Minutes) -> String {
+    let hours = minutes / MINUTES_PER_HOUR;
+    let minutes = minutes % MINUTES_PER_HOUR;
+
+    match (hours, minutes) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
+
+fn add_days(date: NaiveDate, days: u64) -> Result<NaiveDate> {
+    date.checked_add_days(Days::new(days))
+        .ok_or_else(|| anyhow!("Date out of range!"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parse_date_should_accept_iso_dates() {
+        let today = date(2026, 10, 19);
+        assert_eq!(parse_date("2026-11-01", today).unwrap(), date(2026, 11, 1));
+    }
+
+    #[test]
+    fn parse_date_should_accept_relative_dates() {
+        // a monday
+        let today = date(2026, 10, 19);
+
+        assert_eq!(parse_date("today", today).unwrap(), today);
+        assert_eq!(parse_date("Tomorrow", today).unwrap(), date(2026, 10, 20));
+        assert_eq!(parse_date("+3d", today).unwrap(), date(2026, 10, 22));
+        assert_eq!(parse_date("+2w", today).unwrap(), date(2026, 11, 2));
+        assert_eq!(
+            parse_date("next friday", today).unwrap(),
+            date(2026, 10, 23)
+        );
+        assert_eq!(parse_date("friday", today).unwrap(), date(2026, 10, 23));
+        assert_eq!(
+            parse_date("next monday", today).unwrap(),
+            date(2026, 10, 26)
+        );
+    }
+
+    #[test]
+    fn parse_date_should_fail_with_invalid_input() {
+        let today = date(2026, 10, 19);
+
+        assert_eq!(parse_date("", today).is_err(), true);
+        assert_eq!(parse_date("2026-13-01", today).is_err(), true);
+        assert_eq!(parse_date("+3x", today).is_err(), true);
+        assert_eq!(parse_date("+d", today).is_err(), true);
+        assert_eq!(parse_date("next week", today).is_err(), true);
+    }
+
+    #[test]
+    fn parse_date_should_fail_with_non_ascii_and_huge_offsets() {
+        let today = date(2026, 10, 19);
+
+        assert_eq!(parse_date("+ü", today).is_err(), true);
+        assert_eq!(parse_date("+3é", today).is_err(), true);
+        assert_eq!(parse_date("+é", today).is_err(), true);
+        assert_eq!(parse_date("+18446744073709551615w", today).is_err(), true);
+        assert_eq!(parse_date("+18446744073709551615d", today).is_err(), true);
+        assert_eq!(parse_date("+99999999999w", today).is_err(), true);
+    }
+
+    #[test]
+    fn parse_duration_should_work() {
+        assert_eq!(parse_duration("45m").unwrap(), 45);
+        assert_eq!(parse_duration("2h").unwrap(), 120);
+        assert_eq!(parse_duration("1h30m").unwrap(), 90);
+        assert_eq!(parse_duration("1d 2h 30m").unwrap(), 630);
+    }
+
+    #[test]
+    fn parse_duration_should_fail_with_invalid_input() {
+        assert_eq!(parse_duration("").is_err(), true);
+        assert_eq!(parse_duration("90").is_err(), true);
+        assert_eq!(parse_duration("h").is_err(), true);
+        assert_eq!(parse_duration("2x").is_err(), true);
+        assert_eq!(parse_duration("9000000d").is_err(), true);
+        assert_eq!(parse_duration("4294967295m 1m").is_err(), true);
+        assert_eq!(parse_duration("99999999999m").is_err(), true);
+    }
+
+    #[test]
+    fn total_duration_should_saturate() {
+        assert_eq!(total_duration([30, 45]), 75);
+        assert_eq!(total_duration([Minutes::MAX, 1]), Minutes::MAX);
+        assert_eq!(total_duration([]), 0);
+    }
+
+    #[test]
+    fn format_duration_should_work() {
+        assert_eq!(format_duration(0), "0m");
+        assert_eq!(format_duration(45), "45m");
+        assert_eq!(format_duration(120), "2h");
+        assert_eq!(format_duration(630), "10h 30m");
+    }
+}
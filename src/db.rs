@@ -1,9 +1,17 @@
 use anyhow::anyhow;
 /// This module handles all database reading/writing
 use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime};
 use std::fs::{File, OpenOptions};
 
-use crate::models::{DBState, Epic, Status, Story};
+use crate::dates::now;
+use crate::integrity::{self, Problem};
+use crate::models::{
+    DBState, DeletedItem, DerivedStatusRules, Epic, Minutes, Project, RankMove, Status, Story,
+    StoryType, Timer, TrashedItem, UserSettings, Version, WorkLog,
+};
+use itertools::Itertools;
+use std::collections::HashMap;
 use std::io::BufReader;
 
 pub struct JiraDatabase {
@@ -22,42 +30,104 @@ impl JiraDatabase {
         db.as_ref().read_db()
     }
 
-    pub fn create_epic(&self, epic: Epic) -> Result<u32> {
+    /// Writes the state, the epics and stories which were created or changed since it
+    /// was read get the current time as their last update
+    fn write_db(&self, db_state: &mut DBState) -> Result<()> {
+        let old_state = self.read_db()?;
+        let updated_at = Some(now());
+
+        for (id, epic) in &mut db_state.epics {
+            let old_epic = old_state.epics.get(id);
+            if old_epic.map(|old_epic| Epic {
+                updated_at: epic.updated_at,
+                ..old_epic.clone()
+            }) != Some(epic.clone())
+            {
+                epic.updated_at = updated_at;
+            }
+        }
+        for (id, story) in &mut db_state.stories {
+            let old_story = old_state.stories.get(id);
+            if old_story.map(|old_story| Story {
+                updated_at: story.updated_at,
+                ..old_story.clone()
+            }) != Some(story.clone())
+            {
+                story.updated_at = updated_at;
+            }
+        }
+
+        self.database.write_db(db_state)
+    }
+
+    /// Checks the database for inconsistencies, see `integrity::check`.
+    pub fn check(&self) -> Result<Vec<Problem>> {
+        Ok(integrity::check(&self.read_db()?))
+    }
+
+    /// Repairs all inconsistencies and returns the problems which were fixed.
+    pub fn repair(&self) -> Result<Vec<Problem>> {
+        let mut db_state = self.read_db()?;
+
+        let problems = integrity::repair(&mut db_state);
+        if !problems.is_empty() {
+            self.write_db(&mut db_state)?;
+        }
+        Ok(problems)
+    }
+
+    pub fn create_project(&self, project: Project) -> Result<()> {
         let mut db_state = self.read_db()?;
-        let last_item_id = &mut db_state.last_item_id;
-        *last_item_id += 1;
-        let last_item_id = *last_item_id;
-        db_state.epics.insert(last_item_id, epic);
 
-        self.database.write_db(&db_state)?;
+        // keys are short, upper case and start with a letter, e.g. `WEB`
+        let key = &project.key;
+        let is_valid_key = (2..=5).contains(&key.len())
+            && key.starts_with(|c: char| c.is_ascii_uppercase())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        if !is_valid_key {
+            return Err(anyhow!(
+                "Invalid project key '{key}', use 2 to 5 letters or digits!"
+            ));
+        }
+        if db_state.projects.iter().any(|other| other.key == *key) {
+            return Err(anyhow!("Project '{key}' already exists!"));
+        }
+
+        db_state.projects.push(project);
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn create_epic(&self, epic: Epic) -> Result<u32> {
+        let mut db_state = self.read_db()?;
+        let epic_id = db_state.insert_epic(epic)?;
 
-        Ok(last_item_id)
+        self.write_db(&mut db_state)?;
+        Ok(epic_id)
     }
 
     pub fn create_story(&self, story: Story, epic_id: u32) -> Result<u32> {
         let mut db_state = self.read_db()?;
-        let last_item_id = &mut db_state.last_item_id;
-
-        // find the referenced epic
-        match db_state.epics.get_mut(&epic_id) {
-            None => Err(anyhow!("Epic not found!")),
-            Some(epic) => {
-                // determine next id
-                *last_item_id += 1;
-                let last_item_id = *last_item_id;
+        let story_id = db_state.insert_story(story, epic_id)?;
+        db_state.refresh_derived_statuses();
 
-                // add story reference to epic
-                epic.stories.push(last_item_id);
+        self.write_db(&mut db_state)?;
+        Ok(story_id)
+    }
 
-                // insert new story
-                db_state.stories.insert(last_item_id, story);
+    /// Applies several changes to the state and writes it once, nothing is written
+    /// when one of the changes fails
+    pub fn apply_changes<T>(&self, change: impl FnOnce(&mut DBState) -> Result<T>) -> Result<T> {
+        let mut db_state = self.read_db()?;
+        let result = change(&mut db_state)?;
 
-                self.database.write_db(&db_state)?;
-                Ok(last_item_id)
-            }
-        }
+        self.write_db(&mut db_state)?;
+        Ok(result)
     }
 
+    /// Moves the epic and all its stories to the trash.
     pub fn delete_epic(&self, epic_id: u32) -> Result<()> {
         let mut db_state = self.read_db()?;
         // search for epic
@@ -67,23 +137,36 @@ impl JiraDatabase {
             .ok_or(anyhow!("Epic not found!"))?;
 
         // remove all related stories
+        let mut stories = HashMap::new();
         for story_id in &epic.stories {
-            let _story = db_state.stories.remove(story_id);
             // BUGFIX: if story is not found, that doesn't matter, should be deleted anyway
-            //.ok_or(anyhow!("Story not found!"))?;
+            if let Some(mut story) = db_state.stories.remove(story_id) {
+                if let Some(work_log) = stop_timer_of(&mut db_state.active_timer, *story_id) {
+                    story.add_work_log(work_log);
+                }
+                stories.insert(*story_id, story);
+            }
         }
+        db_state.backlog.retain(|id| !epic.stories.contains(id));
 
         // remove epic
-        let _epic = db_state
+        let epic = db_state
             .epics
             .remove(&epic_id)
             .ok_or(anyhow!("Epic not found, but was found earlier!"))?;
 
+        db_state.trash.push(DeletedItem {
+            id: epic_id,
+            deleted_at: now(),
+            item: TrashedItem::Epic { epic, stories },
+        });
+
         // update database
-        self.database.write_db(&db_state)?;
+        self.write_db(&mut db_state)?;
         Ok(())
     }
 
+    /// Moves the story to the trash.
     pub fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
         let mut db_state = self.read_db()?;
         // search for epic
@@ -92,15 +175,158 @@ impl JiraDatabase {
             .get_mut(&epic_id)
             .ok_or(anyhow!("Epic not found!"))?;
         // delete story if found
-        let _story = db_state
+        let mut story = db_state
             .stories
             .remove(&story_id)
             .ok_or(anyhow!("Story not found!"))?;
+        if let Some(work_log) = stop_timer_of(&mut db_state.active_timer, story_id) {
+            story.add_work_log(work_log);
+        }
         // delete story references in the epic
         epic.stories.retain(|value| *value != story_id);
+        db_state.backlog.retain(|value| *value != story_id);
+        db_state.trash.push(DeletedItem {
+            id: story_id,
+            deleted_at: now(),
+            item: TrashedItem::Story { epic_id, story },
+        });
+        db_state.refresh_derived_statuses();
 
         // update database
-        self.database.write_db(&db_state)?;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    /// Moves a story from its epic to another epic.
+    pub fn move_story_to_epic(&self, story_id: u32, epic_id: u32) -> Result<()> {
+        let mut db_state = self.read_db()?;
+
+        if !db_state.stories.contains_key(&story_id) {
+            return Err(anyhow!("Story not found!"));
+        }
+        let epic = db_state
+            .epics
+            .get(&epic_id)
+            .ok_or(anyhow!("Epic not found!"))?;
+        if epic.archived {
+            return Err(anyhow!("Epic is archived, unarchive it first!"));
+        }
+
+        // a story moved to another project gets a key of the new project, e.g. `WEB-5` becomes `API-13`
+        let project = epic.project.clone();
+        let old_project = db_state
+            .epic_id_of_story(story_id)
+            .and_then(|old_epic_id| db_state.epics.get(&old_epic_id))
+            .and_then(|old_epic| old_epic.project.clone());
+        if project != old_project {
+            let key = project
+                .as_deref()
+                .and_then(|project| db_state.next_project_key(project));
+            if let Some(story) = db_state.stories.get_mut(&story_id) {
+                story.key = key;
+            }
+        }
+
+        for epic in db_state.epics.values_mut() {
+            epic.stories.retain(|value| *value != story_id);
+        }
+        if let Some(epic) = db_state.epics.get_mut(&epic_id) {
+            epic.stories.push(story_id);
+        }
+        db_state.refresh_derived_statuses();
+
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    /// Restores an epic with its stories or a single story from the trash.
+    pub fn restore_item(&self, id: u32) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        let index = db_state
+            .trash
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or(anyhow!("Item not found in trash!"))?;
+
+        match &db_state.trash[index].item {
+            TrashedItem::Epic { epic, stories } => {
+                let (epic, stories) = (epic.clone(), stories.clone());
+                // restored stories are ranked at the bottom of the backlog
+                db_state.backlog.extend(stories.keys().copied().sorted());
+                db_state.stories.extend(stories);
+                db_state.epics.insert(id, epic);
+            }
+            TrashedItem::Story { epic_id, story } => {
+                let (epic_id, story) = (*epic_id, story.clone());
+                let epic = db_state.epics.get_mut(&epic_id).ok_or(anyhow!(
+                    "Epic of the story is deleted, restore the epic first!"
+                ))?;
+                epic.stories.push(id);
+                db_state.stories.insert(id, story);
+                db_state.backlog.push(id);
+            }
+        }
+        db_state.trash.remove(index);
+        db_state.refresh_derived_statuses();
+
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    /// Removes items from the trash for good, either all or those older than the retention period.
+    /// Returns the number of purged items.
+    pub fn purge_trash(&self, now: NaiveDateTime, all: bool) -> Result<usize> {
+        let mut db_state = self.read_db()?;
+        let retention_days = db_state.trash_retention_days;
+
+        let count = db_state.trash.len();
+        db_state.trash.retain(|item| match (all, retention_days) {
+            (true, _) => false,
+            (false, Some(days)) => !item.is_expired(days, now),
+            (false, None) => true,
+        });
+        let count = count - db_state.trash.len();
+
+        self.write_db(&mut db_state)?;
+        Ok(count)
+    }
+
+    pub fn update_trash_retention(&self, retention_days: Option<u32>) -> Result<()> {
+        let mut db_state = self.read_db()?;
+
+        db_state.trash_retention_days = retention_days;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    /// Changes the preferences of a user, e.g. the colour theme.
+    pub fn update_user_settings(
+        &self,
+        user: &str,
+        update: impl FnOnce(&mut UserSettings),
+    ) -> Result<()> {
+        let mut db_state = self.read_db()?;
+
+        update(db_state.user_settings.entry(user.to_owned()).or_default());
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    /// Archives or unarchives an epic, only resolved or closed epics can be archived.
+    pub fn set_epic_archived(&self, epic_id: u32, archived: bool) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        // search for epic
+        let epic = db_state
+            .epics
+            .get_mut(&epic_id)
+            .ok_or(anyhow!("Epic not found!"))?;
+
+        if archived && epic.status.is_unresolved() {
+            return Err(anyhow!("Only resolved or closed epics can be archived!"));
+        }
+
+        epic.archived = archived;
+        self.write_db(&mut db_state)?;
         Ok(())
     }
 
@@ -112,8 +338,38 @@ impl JiraDatabase {
             .get_mut(&epic_id)
             .ok_or(anyhow!("Epic not found!"))?;
 
+        if epic.derived_status {
+            return Err(anyhow!(
+                "Epic status is derived from its stories, turn off derived status first!"
+            ));
+        }
+
         epic.status = status;
-        self.database.write_db(&db_state)?;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    /// Turns the derived status mode of an epic on or off, the status is recalculated right away.
+    pub fn set_epic_derived_status(&self, epic_id: u32, derived_status: bool) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        // search for epic
+        let epic = db_state
+            .epics
+            .get_mut(&epic_id)
+            .ok_or(anyhow!("Epic not found!"))?;
+
+        epic.derived_status = derived_status;
+        db_state.refresh_derived_statuses();
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn update_derived_status_rules(&self, rules: DerivedStatusRules) -> Result<()> {
+        let mut db_state = self.read_db()?;
+
+        db_state.derived_status_rules = rules;
+        db_state.refresh_derived_statuses();
+        self.write_db(&mut db_state)?;
         Ok(())
     }
 
@@ -126,9 +382,265 @@ impl JiraDatabase {
             .ok_or(anyhow!("Story not found!"))?;
 
         story.status = status;
-        self.database.write_db(&db_state)?;
+        db_state.refresh_derived_statuses();
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn update_epic_due_date(&self, epic_id: u32, due_date: Option<NaiveDate>) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        // search for epic
+        let epic = db_state
+            .epics
+            .get_mut(&epic_id)
+            .ok_or(anyhow!("Epic not found!"))?;
+
+        epic.due_date = due_date;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn update_story_due_date(&self, story_id: u32, due_date: Option<NaiveDate>) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        // search for story
+        let story = db_state
+            .stories
+            .get_mut(&story_id)
+            .ok_or(anyhow!("Story not found!"))?;
+
+        story.due_date = due_date;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn update_story_estimate(
+        &self,
+        story_id: u32,
+        original_estimate: Option<Minutes>,
+        remaining_estimate: Option<Minutes>,
+    ) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        // search for story
+        let story = db_state
+            .stories
+            .get_mut(&story_id)
+            .ok_or(anyhow!("Story not found!"))?;
+
+        story.original_estimate = original_estimate;
+        story.remaining_estimate = remaining_estimate;
+        self.write_db(&mut db_state)?;
         Ok(())
     }
+
+    pub fn log_work(&self, story_id: u32, work_log: WorkLog) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        // search for story
+        let story = db_state
+            .stories
+            .get_mut(&story_id)
+            .ok_or(anyhow!("Story not found!"))?;
+
+        story.add_work_log(work_log);
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn start_timer(&self, story_id: u32, author: String, started: NaiveDateTime) -> Result<()> {
+        let mut db_state = self.read_db()?;
+
+        if let Some(timer) = &db_state.active_timer {
+            return Err(anyhow!(
+                "A timer is already running for story {}!",
+                timer.story_id
+            ));
+        }
+        if !db_state.stories.contains_key(&story_id) {
+            return Err(anyhow!("Story not found!"));
+        }
+
+        db_state.active_timer = Some(Timer {
+            story_id,
+            author,
+            started,
+        });
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    /// Stops the running timer and logs the elapsed time against its story. A timer of a
+    /// story which doesn't exist anymore is stopped without logging anything.
+    pub fn stop_timer(
+        &self,
+        stopped: NaiveDateTime,
+        note: String,
+    ) -> Result<(u32, Option<WorkLog>)> {
+        let mut db_state = self.read_db()?;
+        let timer = db_state
+            .active_timer
+            .take()
+            .ok_or(anyhow!("No timer is running!"))?;
+
+        let work_log = WorkLog {
+            duration: timer.elapsed(stopped),
+            author: timer.author,
+            started: timer.started,
+            note,
+        };
+        let work_log = match db_state.stories.get_mut(&timer.story_id) {
+            Some(story) => {
+                story.add_work_log(work_log.clone());
+                Some(work_log)
+            }
+            None => None,
+        };
+
+        self.write_db(&mut db_state)?;
+        Ok((timer.story_id, work_log))
+    }
+
+    pub fn update_story_type(&self, story_id: u32, story_type: StoryType) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        // search for story
+        let story = db_state
+            .stories
+            .get_mut(&story_id)
+            .ok_or(anyhow!("Story not found!"))?;
+
+        story.story_type = story_type;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn update_story_assignee(&self, story_id: u32, assignee: Option<String>) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        let story = db_state
+            .stories
+            .get_mut(&story_id)
+            .ok_or(anyhow!("Story not found!"))?;
+
+        story.assignee = assignee;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn create_version(&self, version: Version) -> Result<()> {
+        let mut db_state = self.read_db()?;
+
+        if version.name.trim().is_empty() {
+            return Err(anyhow!("Version name must not be empty!"));
+        }
+        if db_state
+            .versions
+            .iter()
+            .any(|other| other.name == version.name)
+        {
+            return Err(anyhow!("Version '{}' already exists!", version.name));
+        }
+
+        db_state.versions.push(version);
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn release_version(&self, name: &str) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        // search for version
+        let version = db_state
+            .versions
+            .iter_mut()
+            .find(|version| version.name == name)
+            .ok_or(anyhow!("Version not found!"))?;
+
+        version.released = true;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn update_story_fix_version(
+        &self,
+        story_id: u32,
+        fix_version: Option<String>,
+    ) -> Result<()> {
+        let mut db_state = self.read_db()?;
+
+        if let Some(name) = &fix_version {
+            if !db_state
+                .versions
+                .iter()
+                .any(|version| version.name == *name)
+            {
+                return Err(anyhow!("Version not found!"));
+            }
+        }
+
+        // search for story
+        let story = db_state
+            .stories
+            .get_mut(&story_id)
+            .ok_or(anyhow!("Story not found!"))?;
+
+        story.fix_version = fix_version;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+
+    pub fn move_story_in_backlog(&self, story_id: u32, direction: RankMove) -> Result<()> {
+        let mut db_state = self.read_db()?;
+        let mut ranked = db_state.ranked_story_ids();
+
+        let position = ranked
+            .iter()
+            .position(|id| *id == story_id)
+            .ok_or(anyhow!("Story not found!"))?;
+
+        // resolved stories are not shown in the backlog, so moving up or down swaps
+        // the story with its nearest unresolved neighbour
+        let is_unresolved = |id: &u32| {
+            db_state
+                .stories
+                .get(id)
+                .is_some_and(|story| story.status.is_unresolved())
+        };
+
+        match direction {
+            RankMove::Up => {
+                if let Some(other) = ranked[..position].iter().rposition(is_unresolved) {
+                    ranked.swap(position, other);
+                }
+            }
+            RankMove::Down => {
+                if let Some(other) = ranked[position + 1..].iter().position(is_unresolved) {
+                    ranked.swap(position, position + 1 + other);
+                }
+            }
+            RankMove::Top => {
+                let id = ranked.remove(position);
+                ranked.insert(0, id);
+            }
+            RankMove::Bottom => {
+                let id = ranked.remove(position);
+                ranked.push(id);
+            }
+        }
+
+        db_state.backlog = ranked;
+        self.write_db(&mut db_state)?;
+        Ok(())
+    }
+}
+
+/// Stops the timer if it runs for the story, returns the work it logs
+fn stop_timer_of(active_timer: &mut Option<Timer>, story_id: u32) -> Option<WorkLog> {
+    if active_timer.as_ref()?.story_id != story_id {
+        return None;
+    }
+    let timer = active_timer.take()?;
+    Some(WorkLog {
+        duration: timer.elapsed(now()),
+        author: timer.author,
+        started: timer.started,
+        note: String::new(),
+    })
 }
 
 pub trait Database {
@@ -180,6 +692,7 @@ pub mod test_utils {
                     last_item_id: 0,
                     epics: HashMap::new(),
                     stories: HashMap::new(),
+                    ..Default::default()
                 }),
             }
         }
@@ -282,12 +795,27 @@ mod tests {
                 name: "epic 1".to_owned(),
                 description: "epic 1".to_owned(),
                 status: Status::Open,
+                due_date: None,
+                original_estimate: None,
+                remaining_estimate: None,
+                work_logs: vec![],
+                key: None,
+                story_type: StoryType::Story,
+                fix_version: None,
+                assignee: None,
+                updated_at: None,
             };
             let epic: Epic = Epic {
                 name: "epic 1".to_owned(),
                 description: "epic 1".to_owned(),
                 status: Status::Open,
                 stories: vec![2],
+                due_date: None,
+                project: None,
+                key: None,
+                derived_status: false,
+                archived: false,
+                updated_at: None,
             };
 
             let mut stories = HashMap::new();
@@ -300,6 +828,7 @@ mod tests {
                 last_item_id: 2,
                 epics,
                 stories,
+                ..Default::default()
             };
 
             let write_result = db.write_db(&state);
@@ -332,7 +861,83 @@ mod tests {
 
             assert_eq!(id, expected_id);
             assert_eq!(db_state.last_item_id, expected_id);
-            assert_eq!(db_state.epics.get(&id), Some(&epic));
+            // new epics are stamped with the time they were created
+            let created = &db_state.epics[&id];
+            assert_eq!(created.updated_at.is_some(), true);
+            assert_eq!(
+                created,
+                &Epic {
+                    updated_at: created.updated_at,
+                    ..epic
+                }
+            );
+        }
+
+        #[test]
+        fn create_project_should_validate_key() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+
+            let result = db.create_project(Project::new("web".to_owned(), "Website".to_owned()));
+            assert_eq!(result.is_ok(), true);
+
+            // duplicate
+            let result = db.create_project(Project::new("WEB".to_owned(), "".to_owned()));
+            assert_eq!(result.is_err(), true);
+
+            for invalid_key in ["", "W", "TOOLONG", "1AB", "W-B"] {
+                let result = db.create_project(Project::new(invalid_key.to_owned(), "".to_owned()));
+                assert_eq!(result.is_err(), true);
+            }
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.projects.len(), 1);
+            assert_eq!(db_state.projects[0].key, "WEB".to_owned());
+        }
+
+        #[test]
+        fn create_epic_should_error_if_invalid_project() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let mut epic = Epic::new("".to_owned(), "".to_owned());
+            epic.project = Some("WEB".to_owned());
+
+            let result = db.create_epic(epic);
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn create_epic_and_story_should_assign_project_keys() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            db.create_project(Project::new("WEB".to_owned(), "".to_owned()))
+                .unwrap();
+            db.create_project(Project::new("APP".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let mut epic = Epic::new("".to_owned(), "".to_owned());
+            epic.project = Some("WEB".to_owned());
+            let web_epic_id = db.create_epic(epic).unwrap();
+
+            let mut epic = Epic::new("".to_owned(), "".to_owned());
+            epic.project = Some("APP".to_owned());
+            let app_epic_id = db.create_epic(epic).unwrap();
+
+            let web_story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), web_epic_id)
+                .unwrap();
+            let app_story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), app_epic_id)
+                .unwrap();
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.display_id(web_epic_id), "WEB-1".to_owned());
+            assert_eq!(db_state.display_id(app_epic_id), "APP-1".to_owned());
+            assert_eq!(db_state.display_id(web_story_id), "WEB-2".to_owned());
+            assert_eq!(db_state.display_id(app_story_id), "APP-2".to_owned());
         }
 
         #[test]
@@ -375,7 +980,15 @@ mod tests {
                 db_state.epics.get(&epic_id).unwrap().stories.contains(&id),
                 true
             );
-            assert_eq!(db_state.stories.get(&id), Some(&story));
+            let created = &db_state.stories[&id];
+            assert_eq!(created.updated_at.is_some(), true);
+            assert_eq!(
+                created,
+                &Story {
+                    updated_at: created.updated_at,
+                    ..story
+                }
+            );
         }
 
         #[test]
@@ -581,6 +1194,803 @@ mod tests {
             );
         }
 
+        #[test]
+        fn updates_should_only_stamp_changed_items() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let long_ago = NaiveDate::from_ymd_opt(2020, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0);
+            let mut db_state = db.read_db().unwrap();
+            db_state.epics.get_mut(&epic_id).unwrap().updated_at = long_ago;
+            db_state.stories.get_mut(&story_id).unwrap().updated_at = long_ago;
+            db.database.write_db(&db_state).unwrap();
+
+            db.update_story_status(story_id, Status::Closed).unwrap();
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.epics[&epic_id].updated_at, long_ago);
+            assert_eq!(db_state.stories[&story_id].updated_at > long_ago, true);
+        }
+
+        #[test]
+        fn update_epic_due_date_should_error_if_invalid_epic_id() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+
+            let non_existent_epic_id = 999;
+
+            let result = db.update_epic_due_date(non_existent_epic_id, None);
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn update_epic_due_date_should_work() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let due_date = NaiveDate::from_ymd_opt(2026, 11, 1);
+
+            let result = db.update_epic_due_date(epic_id, due_date);
+            assert_eq!(result.is_ok(), true);
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.epics.get(&epic_id).unwrap().due_date, due_date);
+        }
+
+        #[test]
+        fn update_story_due_date_should_error_if_invalid_story_id() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+
+            let non_existent_story_id = 999;
+
+            let result = db.update_story_due_date(non_existent_story_id, None);
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn update_story_due_date_should_work() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let due_date = NaiveDate::from_ymd_opt(2026, 11, 1);
+
+            db.update_story_due_date(story_id, due_date).unwrap();
+            db.update_story_due_date(story_id, None).unwrap();
+            db.update_story_due_date(story_id, due_date).unwrap();
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.stories.get(&story_id).unwrap().due_date, due_date);
+        }
+
+        #[test]
+        fn update_story_estimate_should_error_if_invalid_story_id() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+
+            let non_existent_story_id = 999;
+
+            let result = db.update_story_estimate(non_existent_story_id, Some(60), Some(60));
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn log_work_should_error_if_invalid_story_id() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+
+            let non_existent_story_id = 999;
+            let work_log = WorkLog {
+                author: "".to_owned(),
+                started: NaiveDate::from_ymd_opt(2026, 10, 19)
+                    .unwrap()
+                    .and_hms_opt(9, 0, 0)
+                    .unwrap(),
+                duration: 30,
+                note: "".to_owned(),
+            };
+
+            let result = db.log_work(non_existent_story_id, work_log);
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn log_work_should_reduce_remaining_estimate() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let work_log = WorkLog {
+                author: "alice".to_owned(),
+                started: NaiveDate::from_ymd_opt(2026, 10, 19)
+                    .unwrap()
+                    .and_hms_opt(9, 0, 0)
+                    .unwrap(),
+                duration: 90,
+                note: "first draft".to_owned(),
+            };
+
+            db.update_story_estimate(story_id, Some(120), Some(120))
+                .unwrap();
+
+            let result = db.log_work(story_id, work_log.clone());
+            assert_eq!(result.is_ok(), true);
+
+            let result = db.log_work(story_id, work_log.clone());
+            assert_eq!(result.is_ok(), true);
+
+            let db_state = db.read_db().unwrap();
+            let story = db_state.stories.get(&story_id).unwrap();
+
+            assert_eq!(story.work_logs, vec![work_log.clone(), work_log]);
+            assert_eq!(story.time_spent(), 180);
+            assert_eq!(story.original_estimate, Some(120));
+            assert_eq!(story.remaining_estimate, Some(0));
+        }
+
+        #[test]
+        fn start_timer_should_error_if_invalid_story_id() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+
+            let non_existent_story_id = 999;
+            let started = NaiveDate::from_ymd_opt(2026, 10, 19)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap();
+
+            let result = db.start_timer(non_existent_story_id, "alice".to_owned(), started);
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn stop_timer_should_error_if_no_timer_is_running() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let stopped = NaiveDate::from_ymd_opt(2026, 10, 19)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap();
+
+            let result = db.stop_timer(stopped, "".to_owned());
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn timer_should_log_work_when_stopped() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let started = NaiveDate::from_ymd_opt(2026, 10, 19)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap();
+            let stopped = started + chrono::Duration::minutes(95);
+
+            let result = db.start_timer(story_id, "alice".to_owned(), started);
+            assert_eq!(result.is_ok(), true);
+
+            // only one timer can run at a time
+            let result = db.start_timer(story_id, "alice".to_owned(), started);
+            assert_eq!(result.is_err(), true);
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.active_timer.unwrap().story_id, story_id);
+
+            let (stopped_story_id, work_log) = db.stop_timer(stopped, "done".to_owned()).unwrap();
+            assert_eq!(stopped_story_id, story_id);
+            let work_log = work_log.unwrap();
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.active_timer, None);
+            assert_eq!(
+                db_state.stories.get(&story_id).unwrap().work_logs,
+                vec![WorkLog {
+                    author: "alice".to_owned(),
+                    started,
+                    duration: 95,
+                    note: "done".to_owned(),
+                }]
+            );
+            assert_eq!(work_log.duration, 95);
+        }
+
+        #[test]
+        fn delete_should_stop_the_timer_of_the_deleted_story() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let other_story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            db.start_timer(story_id, "alice".to_owned(), now()).unwrap();
+            db.delete_story(epic_id, other_story_id).unwrap();
+            assert_eq!(db.read_db().unwrap().active_timer.is_some(), true);
+
+            // the work is kept with the story in the trash
+            db.delete_story(epic_id, story_id).unwrap();
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.active_timer, None);
+            match &db_state.trash.last().unwrap().item {
+                TrashedItem::Story { story, .. } => assert_eq!(story.work_logs.len(), 1),
+                item => panic!("unexpected item {item:?}"),
+            }
+
+            // a new timer can be started
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.start_timer(story_id, "alice".to_owned(), now()).unwrap();
+            db.delete_epic(epic_id).unwrap();
+            assert_eq!(db.read_db().unwrap().active_timer, None);
+        }
+
+        #[test]
+        fn stop_timer_should_drop_a_timer_of_a_missing_story() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let started = NaiveDate::from_ymd_opt(2026, 10, 19)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap();
+            let mut db_state = db.read_db().unwrap();
+            db_state.active_timer = Some(Timer {
+                story_id: 99,
+                author: "alice".to_owned(),
+                started,
+            });
+            db.database.write_db(&db_state).unwrap();
+
+            let (story_id, work_log) = db.stop_timer(started, "".to_owned()).unwrap();
+
+            assert_eq!(story_id, 99);
+            assert_eq!(work_log, None);
+            assert_eq!(db.read_db().unwrap().active_timer, None);
+        }
+
+        #[test]
+        fn create_version_should_error_if_name_is_invalid() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+
+            let result = db.create_version(Version::new("".to_owned(), None));
+            assert_eq!(result.is_err(), true);
+
+            let result = db.create_version(Version::new("1.0".to_owned(), None));
+            assert_eq!(result.is_ok(), true);
+
+            let result = db.create_version(Version::new("1.0".to_owned(), None));
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn release_version_should_work() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            db.create_version(Version::new("1.0".to_owned(), None))
+                .unwrap();
+
+            let result = db.release_version("2.0");
+            assert_eq!(result.is_err(), true);
+
+            let result = db.release_version("1.0");
+            assert_eq!(result.is_ok(), true);
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.versions[0].released, true);
+        }
+
+        #[test]
+        fn update_story_fix_version_should_error_if_invalid_version() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let result = db.update_story_fix_version(story_id, Some("1.0".to_owned()));
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn update_story_fix_version_and_type_should_work() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.create_version(Version::new("1.0".to_owned(), None))
+                .unwrap();
+
+            let result = db.update_story_fix_version(story_id, Some("1.0".to_owned()));
+            assert_eq!(result.is_ok(), true);
+
+            let result = db.update_story_type(story_id, StoryType::Bug);
+            assert_eq!(result.is_ok(), true);
+
+            let db_state = db.read_db().unwrap();
+            let story = db_state.stories.get(&story_id).unwrap();
+            assert_eq!(story.fix_version, Some("1.0".to_owned()));
+            assert_eq!(story.story_type, StoryType::Bug);
+        }
+
+        #[test]
+        fn derived_epic_status_should_follow_story_changes() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.set_epic_derived_status(epic_id, true).unwrap();
+
+            let story_1 = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let story_2 = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let status = || {
+                db.read_db()
+                    .unwrap()
+                    .epics
+                    .get(&epic_id)
+                    .unwrap()
+                    .status
+                    .clone()
+            };
+            assert_eq!(status(), Status::Open);
+
+            db.update_story_status(story_1, Status::InProgress).unwrap();
+            assert_eq!(status(), Status::InProgress);
+
+            db.update_story_status(story_1, Status::Resolved).unwrap();
+            assert_eq!(status(), Status::InProgress);
+
+            db.delete_story(epic_id, story_2).unwrap();
+            assert_eq!(status(), Status::Resolved);
+
+            db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            assert_eq!(status(), Status::InProgress);
+        }
+
+        #[test]
+        fn update_epic_status_should_error_for_derived_epic() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.set_epic_derived_status(epic_id, true).unwrap();
+
+            assert_eq!(
+                db.update_epic_status(epic_id, Status::Closed).is_err(),
+                true
+            );
+
+            db.set_epic_derived_status(epic_id, false).unwrap();
+            assert_eq!(db.update_epic_status(epic_id, Status::Closed).is_ok(), true);
+            assert_eq!(db.set_epic_derived_status(999, true).is_err(), true);
+        }
+
+        #[test]
+        fn update_derived_status_rules_should_recalculate_epics() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.set_epic_derived_status(epic_id, true).unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.update_story_status(story_id, Status::Resolved).unwrap();
+
+            let rules = DerivedStatusRules {
+                all_done: Status::Closed,
+                ..Default::default()
+            };
+            db.update_derived_status_rules(rules.clone()).unwrap();
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.derived_status_rules, rules);
+            assert_eq!(db_state.epics.get(&epic_id).unwrap().status, Status::Closed);
+        }
+
+        #[test]
+        fn delete_should_move_items_to_trash_and_restore_should_bring_them_back() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_1 = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let story_2 = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            db.delete_story(epic_id, story_2).unwrap();
+            db.delete_epic(epic_id).unwrap();
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.epics.len(), 0);
+            assert_eq!(db_state.stories.len(), 0);
+            assert_eq!(db_state.backlog.is_empty(), true);
+            assert_eq!(
+                db_state
+                    .trash
+                    .iter()
+                    .map(|item| item.id)
+                    .collect::<Vec<_>>(),
+                vec![story_2, epic_id]
+            );
+
+            // the story can't be restored without its epic
+            assert_eq!(db.restore_item(story_2).is_err(), true);
+
+            db.restore_item(epic_id).unwrap();
+            db.restore_item(story_2).unwrap();
+            assert_eq!(db.restore_item(999).is_err(), true);
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.trash.len(), 0);
+            assert_eq!(
+                db_state.epics.get(&epic_id).unwrap().stories,
+                vec![story_1, story_2]
+            );
+            assert_eq!(db_state.stories.len(), 2);
+            assert_eq!(db_state.backlog, vec![story_1, story_2]);
+        }
+
+        #[test]
+        fn purge_trash_should_remove_expired_items() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_1 = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let epic_2 = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.delete_epic(epic_1).unwrap();
+            db.delete_epic(epic_2).unwrap();
+
+            // the first epic was deleted a week ago
+            let mut db_state = db.read_db().unwrap();
+            let now = db_state.trash[1].deleted_at;
+            db_state.trash[0].deleted_at = now - chrono::Duration::days(7);
+            db.database.write_db(&db_state).unwrap();
+
+            // without a retention nothing expires
+            assert_eq!(db.purge_trash(now, false).unwrap(), 0);
+
+            db.update_trash_retention(Some(7)).unwrap();
+            assert_eq!(db.purge_trash(now, false).unwrap(), 1);
+            assert_eq!(db.read_db().unwrap().trash[0].id, epic_2);
+
+            assert_eq!(db.purge_trash(now, true).unwrap(), 1);
+            assert_eq!(db.read_db().unwrap().trash.len(), 0);
+        }
+
+        #[test]
+        fn move_story_to_epic_should_work() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_1 = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let epic_2 = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_1)
+                .unwrap();
+
+            assert_eq!(db.move_story_to_epic(story_id, 999).is_err(), true);
+            assert_eq!(db.move_story_to_epic(999, epic_2).is_err(), true);
+
+            db.move_story_to_epic(story_id, epic_2).unwrap();
+            let db_state = db.read_db().unwrap();
+            assert_eq!(
+                db_state.epics.get(&epic_1).unwrap().stories.is_empty(),
+                true
+            );
+            assert_eq!(db_state.epics.get(&epic_2).unwrap().stories, vec![story_id]);
+            assert_eq!(db_state.backlog, vec![story_id]);
+        }
+
+        #[test]
+        fn move_story_to_epic_should_give_a_key_of_the_new_project() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            db.create_project(Project::new("WEB".to_owned(), "".to_owned()))
+                .unwrap();
+            db.create_project(Project::new("API".to_owned(), "".to_owned()))
+                .unwrap();
+            let mut web_epic = Epic::new("".to_owned(), "".to_owned());
+            web_epic.project = Some("WEB".to_owned());
+            let web_epic = db.create_epic(web_epic).unwrap();
+            let mut api_epic = Epic::new("".to_owned(), "".to_owned());
+            api_epic.project = Some("API".to_owned());
+            let api_epic = db.create_epic(api_epic).unwrap();
+            let mut other_web_epic = Epic::new("".to_owned(), "".to_owned());
+            other_web_epic.project = Some("WEB".to_owned());
+            let other_web_epic = db.create_epic(other_web_epic).unwrap();
+            let plain_epic = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), web_epic)
+                .unwrap();
+            let key = |db: &JiraDatabase| db.read_db().unwrap().stories[&story_id].key.clone();
+            assert_eq!(key(&db), Some("WEB-3".to_owned()));
+
+            // the key stays within the project
+            db.move_story_to_epic(story_id, other_web_epic).unwrap();
+            assert_eq!(key(&db), Some("WEB-3".to_owned()));
+
+            db.move_story_to_epic(story_id, api_epic).unwrap();
+            assert_eq!(key(&db), Some("API-2".to_owned()));
+            let db_state = db.read_db().unwrap();
+            let api = db_state.projects.iter().find(|p| p.key == "API").unwrap();
+            assert_eq!(api.last_item_id, 2);
+
+            db.move_story_to_epic(story_id, plain_epic).unwrap();
+            assert_eq!(key(&db), None);
+        }
+
+        #[test]
+        fn move_story_to_epic_should_reject_archived_epics() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_1 = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let archived = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.update_epic_status(archived, Status::Closed).unwrap();
+            db.set_epic_archived(archived, true).unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_1)
+                .unwrap();
+
+            assert_eq!(db.move_story_to_epic(story_id, archived).is_err(), true);
+            let db_state = db.read_db().unwrap();
+            assert_eq!(db_state.epics[&epic_1].stories, vec![story_id]);
+            assert_eq!(db_state.epics[&archived].stories.is_empty(), true);
+        }
+
+        #[test]
+        fn update_user_settings_should_only_change_settings_of_user() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+
+            db.update_user_settings("alice", |settings| {
+                settings.theme = Some("light".to_owned())
+            })
+            .unwrap();
+
+            let db_state = db.read_db().unwrap();
+            assert_eq!(
+                db_state.settings_of("alice").theme,
+                Some("light".to_owned())
+            );
+            assert_eq!(db_state.settings_of("bob"), UserSettings::default());
+        }
+
+        #[test]
+        fn set_epic_archived_should_only_archive_finished_epics() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+
+            assert_eq!(db.set_epic_archived(epic_id, true).is_err(), true);
+            assert_eq!(db.set_epic_archived(999, true).is_err(), true);
+
+            db.update_epic_status(epic_id, Status::Closed).unwrap();
+            db.set_epic_archived(epic_id, true).unwrap();
+            assert_eq!(db.read_db().unwrap().epics[&epic_id].archived, true);
+
+            db.set_epic_archived(epic_id, false).unwrap();
+            assert_eq!(db.read_db().unwrap().epics[&epic_id].archived, false);
+        }
+
+        #[test]
+        fn repair_should_fix_inconsistent_db() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            assert_eq!(db.check().unwrap(), vec![]);
+            assert_eq!(db.repair().unwrap(), vec![]);
+
+            let mut db_state = db.read_db().unwrap();
+            db_state.epics.get_mut(&epic_id).unwrap().stories.push(99);
+            db.database.write_db(&db_state).unwrap();
+
+            assert_eq!(db.check().unwrap().len(), 1);
+            assert_eq!(db.repair().unwrap().len(), 1);
+            assert_eq!(db.check().unwrap(), vec![]);
+        }
+
+        #[test]
+        fn create_story_should_rank_story_at_bottom_of_backlog() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id_1 = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let story_id_2 = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let db_state = db.read_db().unwrap();
+
+            assert_eq!(db_state.backlog, vec![story_id_1, story_id_2]);
+        }
+
+        #[test]
+        fn delete_story_should_remove_story_from_backlog() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            db.delete_story(epic_id, story_id).unwrap();
+
+            let db_state = db.read_db().unwrap();
+
+            assert_eq!(db_state.backlog.is_empty(), true);
+        }
+
+        #[test]
+        fn move_story_in_backlog_should_error_if_invalid_story_id() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+
+            let non_existent_story_id = 999;
+
+            let result = db.move_story_in_backlog(non_existent_story_id, RankMove::Up);
+            assert_eq!(result.is_err(), true);
+        }
+
+        #[test]
+        fn move_story_in_backlog_should_work() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let ids: Vec<u32> = (0..4)
+                .map(|_| {
+                    db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                        .unwrap()
+                })
+                .collect();
+            let (a, b, c, d) = (ids[0], ids[1], ids[2], ids[3]);
+
+            db.move_story_in_backlog(c, RankMove::Up).unwrap();
+            assert_eq!(db.read_db().unwrap().backlog, vec![a, c, b, d]);
+
+            db.move_story_in_backlog(a, RankMove::Down).unwrap();
+            assert_eq!(db.read_db().unwrap().backlog, vec![c, a, b, d]);
+
+            db.move_story_in_backlog(d, RankMove::Top).unwrap();
+            assert_eq!(db.read_db().unwrap().backlog, vec![d, c, a, b]);
+
+            db.move_story_in_backlog(d, RankMove::Bottom).unwrap();
+            assert_eq!(db.read_db().unwrap().backlog, vec![c, a, b, d]);
+
+            // moving the first story up is a no-op
+            db.move_story_in_backlog(c, RankMove::Up).unwrap();
+            assert_eq!(db.read_db().unwrap().backlog, vec![c, a, b, d]);
+        }
+
+        #[test]
+        fn move_story_in_backlog_should_skip_resolved_stories() {
+            let db = JiraDatabase {
+                database: Box::new(MockDB::new()),
+            };
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let ids: Vec<u32> = (0..3)
+                .map(|_| {
+                    db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                        .unwrap()
+                })
+                .collect();
+            let (a, b, c) = (ids[0], ids[1], ids[2]);
+
+            db.update_story_status(b, Status::Resolved).unwrap();
+            db.move_story_in_backlog(c, RankMove::Up).unwrap();
+
+            assert_eq!(db.read_db().unwrap().backlog, vec![c, b, a]);
+        }
+
+        // the tests of the database module are grouped like the module itself
+        #[allow(clippy::module_inception)]
         mod database {
             use std::collections::HashMap;
             use std::io::Write;
@@ -654,12 +2064,27 @@ mod tests {
                     name: "epic 1".to_owned(),
                     description: "epic 1".to_owned(),
                     status: Status::Open,
+                    due_date: None,
+                    original_estimate: None,
+                    remaining_estimate: None,
+                    work_logs: vec![],
+                    key: None,
+                    story_type: StoryType::Story,
+                    fix_version: None,
+                    assignee: None,
+                    updated_at: None,
                 };
                 let epic = Epic {
                     name: "epic 1".to_owned(),
                     description: "epic 1".to_owned(),
                     status: Status::Open,
                     stories: vec![2],
+                    due_date: None,
+                    project: None,
+                    key: None,
+                    derived_status: false,
+                    archived: false,
+                    updated_at: None,
                 };
 
                 let mut stories = HashMap::new();
@@ -672,6 +2097,7 @@ mod tests {
                     last_item_id: 2,
                     epics,
                     stories,
+                    ..Default::default()
                 };
 
                 let write_result = db.write_db(&state);
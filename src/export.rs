@@ -0,0 +1,461 @@
+/// This module exports the epics and stories, e.g. for spreadsheets or other tools.
+use std::fmt::Write;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::jira;
+use crate::models::{DBState, Epic, Filter, Story, ID};
+
+/// The formats the epics and stories can be exported as
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    /// one row per epic or story, the stories refer to their epic
+    Csv,
+    /// one JSON object per epic or story with all its fields
+    JsonLines,
+    /// one section per epic with a task list of its stories
+    Markdown,
+    /// like a response of the search endpoint of Jira's REST API
+    JiraJson,
+    /// like Jira's CSV export
+    JiraCsv,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "jsonl" | "json-lines" => Ok(Format::JsonLines),
+            "md" | "markdown" => Ok(Format::Markdown),
+            "jira-json" => Ok(Format::JiraJson),
+            "jira-csv" => Ok(Format::JiraCsv),
+            _ => Err(anyhow!(
+                "Invalid format '{input}', use csv, jsonl, markdown, jira-json or jira-csv!"
+            )),
+        }
+    }
+}
+
+/// Which epics and stories are exported
+#[derive(Debug, Default)]
+pub struct Selection {
+    /// the same filter as in the tables of the interactive app
+    pub filter: Filter,
+    /// the user the `only_mine` filter is about
+    pub user: String,
+    /// only this epic and its stories
+    pub epic: Option<ID>,
+}
+
+/// The epics with their stories which are exported, sorted by id. The filter applies to the
+/// stories, epics are exported if they match it themselves or one of their stories does.
+/// Stories which don't belong to an epic are listed under `None`.
+fn select(db_state: &DBState, selection: &Selection) -> Vec<(Option<ID>, Vec<ID>)> {
+    let is_mine = |story: &Story| story.assignee.as_deref() == Some(selection.user.as_str());
+
+    let stories = db_state
+        .stories
+        .iter()
+        .filter(|(_, story)| selection.filter.matches(&story.status, || is_mine(story)))
+        .map(|(&id, _)| (db_state.epic_id_of_story(id), id))
+        .filter(|(epic_id, _)| selection.epic.is_none() || *epic_id == selection.epic)
+        .into_group_map();
+
+    let epics = db_state
+        .epics
+        .iter()
+        .filter(|(&id, _)| selection.epic.is_none_or(|epic| epic == id))
+        .filter(|(id, epic)| {
+            stories.contains_key(&Some(**id))
+                || selection.filter.matches(&epic.status, || {
+                    epic.stories
+                        .iter()
+                        .filter_map(|story_id| db_state.stories.get(story_id))
+                        .any(is_mine)
+                })
+        })
+        .map(|(&id, _)| Some(id))
+        .chain(stories.contains_key(&None).then_some(None));
+
+    epics
+        .sorted_by_key(|epic_id| epic_id.unwrap_or(ID::MAX))
+        .map(|epic_id| {
+            let story_ids = stories
+                .get(&epic_id)
+                .map(|ids| ids.iter().copied().sorted().collect())
+                .unwrap_or_default();
+            (epic_id, story_ids)
+        })
+        .collect()
+}
+
+/// Exports the selected epics and stories in the format
+pub fn export(db_state: &DBState, format: Format, selection: &Selection) -> Result<String> {
+    let selected = select(db_state, selection);
+    match format {
+        Format::Csv => csv(db_state, &selected),
+        Format::JsonLines => json_lines(db_state, &selected),
+        Format::Markdown => Ok(markdown(db_state, &selected)),
+        Format::JiraJson => jira::to_json(db_state, &selected),
+        Format::JiraCsv => jira::to_csv(db_state, &selected),
+    }
+}
+
+/// A row of the CSV export, estimates and the time spent are in minutes
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    id: ID,
+    key: Option<&'a str>,
+    /// `EPIC` or the type of the story
+    #[serde(rename = "type")]
+    item_type: String,
+    /// the id of the epic of a story
+    epic: Option<ID>,
+    name: &'a str,
+    description: &'a str,
+    status: String,
+    due_date: Option<String>,
+    project: Option<&'a str>,
+    fix_version: Option<&'a str>,
+    assignee: Option<&'a str>,
+    original_estimate_minutes: Option<u32>,
+    remaining_estimate_minutes: Option<u32>,
+    time_spent_minutes: Option<u32>,
+    derived_status: Option<bool>,
+    archived: Option<bool>,
+    updated_at: Option<String>,
+}
+
+impl<'a> CsvRow<'a> {
+    fn epic(id: ID, epic: &'a Epic) -> Self {
+        CsvRow {
+            id,
+            key: epic.key.as_deref(),
+            item_type: "EPIC".to_owned(),
+            epic: None,
+            name: &epic.name,
+            description: &epic.description,
+            status: epic.status.to_string(),
+            due_date: epic
+                .due_date
+                .map(|date| date.format("%Y-%m-%d").to_string()),
+            project: epic.project.as_deref(),
+            fix_version: None,
+            assignee: None,
+            original_estimate_minutes: None,
+            remaining_estimate_minutes: None,
+            time_spent_minutes: None,
+            derived_status: Some(epic.derived_status),
+            archived: Some(epic.archived),
+            updated_at: epic
+                .updated_at
+                .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string()),
+        }
+    }
+
+    fn story(id: ID, epic_id: Option<ID>, story: &'a Story) -> Self {
+        CsvRow {
+            id,
+            key: story.key.as_deref(),
+            item_type: story.story_type.to_string(),
+            epic: epic_id,
+            name: &story.name,
+            description: &story.description,
+            status: story.status.to_string(),
+            due_date: story
+                .due_date
+                .map(|date| date.format("%Y-%m-%d").to_string()),
+            project: None,
+            fix_version: story.fix_version.as_deref(),
+            assignee: story.assignee.as_deref(),
+            original_estimate_minutes: story.original_estimate,
+            remaining_estimate_minutes: story.remaining_estimate,
+            time_spent_minutes: Some(story.time_spent()),
+            derived_status: None,
+            archived: None,
+            updated_at: story
+                .updated_at
+                .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string()),
+        }
+    }
+}
+
+fn csv(db_state: &DBState, selected: &[(Option<ID>, Vec<ID>)]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for (epic_id, story_ids) in selected {
+        if let Some(epic_id) = epic_id {
+            writer.serialize(CsvRow::epic(*epic_id, &db_state.epics[epic_id]))?;
+        }
+        for story_id in story_ids {
+            writer.serialize(CsvRow::story(
+                *story_id,
+                *epic_id,
+                &db_state.stories[story_id],
+            ))?;
+        }
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// A line of the JSON Lines export, all fields of the epic or story as in the database
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum JsonRecord<'a> {
+    Epic {
+        id: ID,
+        #[serde(flatten)]
+        epic: &'a Epic,
+    },
+    Story {
+        id: ID,
+        epic: Option<ID>,
+        #[serde(flatten)]
+        story: &'a Story,
+    },
+}
+
+fn json_lines(db_state: &DBState, selected: &[(Option<ID>, Vec<ID>)]) -> Result<String> {
+    let mut lines = String::new();
+    for (epic_id, story_ids) in selected {
+        if let Some(epic_id) = epic_id {
+            let record = JsonRecord::Epic {
+                id: *epic_id,
+                epic: &db_state.epics[epic_id],
+            };
+            let _ = writeln!(lines, "{}", serde_json::to_string(&record)?);
+        }
+        for story_id in story_ids {
+            let record = JsonRecord::Story {
+                id: *story_id,
+                epic: *epic_id,
+                story: &db_state.stories[story_id],
+            };
+            let _ = writeln!(lines, "{}", serde_json::to_string(&record)?);
+        }
+    }
+    Ok(lines)
+}
+
+fn markdown(db_state: &DBState, selected: &[(Option<ID>, Vec<ID>)]) -> String {
+    let mut document = String::new();
+    let _ = writeln!(document, "# Epics and stories");
+
+    if selected.is_empty() {
+        let _ = writeln!(document);
+        let _ = writeln!(document, "No epics or stories.");
+        return document;
+    }
+
+    for (epic_id, story_ids) in selected {
+        let _ = writeln!(document);
+        match epic_id.map(|id| (id, &db_state.epics[&id])) {
+            Some((id, epic)) => {
+                let _ = writeln!(document, "## {} {}", db_state.display_id(id), epic.name);
+                let _ = writeln!(document);
+
+                let mut details = vec![format!("Status: {}", epic.status)];
+                if let Some(due_date) = epic.due_date {
+                    details.push(format!("Due: {}", due_date.format("%Y-%m-%d")));
+                }
+                if let Some(project) = &epic.project {
+                    details.push(format!("Project: {project}"));
+                }
+                if epic.archived {
+                    details.push("Archived".to_owned());
+                }
+                let _ = writeln!(document, "{}", details.join(" | "));
+                if !epic.description.is_empty() {
+                    let _ = writeln!(document);
+                    let _ = writeln!(document, "{}", epic.description);
+                }
+            }
+            None => {
+                let _ = writeln!(document, "## No epic");
+            }
+        }
+
+        let _ = writeln!(document);
+        if story_ids.is_empty() {
+            let _ = writeln!(document, "No stories.");
+        }
+        for story_id in story_ids {
+            let story = &db_state.stories[story_id];
+            // resolved and closed stories are ticked off
+            let check = match story.status.is_unresolved() {
+                true => " ",
+                false => "x",
+            };
+            let mut details = vec![story.status.to_string()];
+            if let Some(assignee) = &story.assignee {
+                details.push(format!("assignee {assignee}"));
+            }
+            if let Some(due_date) = story.due_date {
+                details.push(format!("due {}", due_date.format("%Y-%m-%d")));
+            }
+            let _ = writeln!(
+                document,
+                "- [{check}] {} {} {} ({})",
+                story.story_type,
+                db_state.display_id(*story_id),
+                story.name,
+                details.join(", ")
+            );
+        }
+    }
+
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Status;
+    use chrono::NaiveDate;
+
+    fn db_state() -> DBState {
+        let mut db_state = DBState::default();
+
+        let mut checkout = Epic::new("Checkout".to_owned(), "Pay, then ship".to_owned());
+        checkout.stories = vec![3, 4];
+        checkout.due_date = NaiveDate::from_ymd_opt(2099, 1, 31);
+        db_state.epics.insert(1, checkout);
+        let mut search = Epic::new("Search".to_owned(), "".to_owned());
+        search.status = Status::Closed;
+        db_state.epics.insert(2, search);
+
+        let mut card = Story::new("Pay with \"card\", fast".to_owned(), "".to_owned());
+        card.status = Status::InProgress;
+        card.assignee = Some("alice".to_owned());
+        card.original_estimate = Some(480);
+        db_state.stories.insert(3, card);
+        let mut mail = Story::new("Order mail".to_owned(), "".to_owned());
+        mail.status = Status::Resolved;
+        db_state.stories.insert(4, mail);
+
+        db_state
+    }
+
+    #[test]
+    fn export_should_write_csv() {
+        let csv = export(&db_state(), Format::Csv, &Selection::default()).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[0],
+            "id,key,type,epic,name,description,status,due_date,project,fix_version,assignee,\
+             original_estimate_minutes,remaining_estimate_minutes,time_spent_minutes,\
+             derived_status,archived,updated_at"
+        );
+        assert_eq!(
+            lines[1],
+            "1,,EPIC,,Checkout,\"Pay, then ship\",OPEN,2099-01-31,,,,,,,false,false,"
+        );
+        assert_eq!(
+            lines[2],
+            "3,,STORY,1,\"Pay with \"\"card\"\", fast\",,IN PROGRESS,,,,alice,480,,0,,,"
+        );
+        assert_eq!(lines[4], "2,,EPIC,,Search,,CLOSED,,,,,,,,false,false,");
+    }
+
+    #[test]
+    fn export_should_write_json_lines() {
+        let lines = export(&db_state(), Format::JsonLines, &Selection::default()).unwrap();
+        let records = lines
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0]["kind"], "epic");
+        assert_eq!(records[0]["id"], 1);
+        assert_eq!(records[0]["stories"], serde_json::json!([3, 4]));
+        assert_eq!(records[1]["kind"], "story");
+        assert_eq!(records[1]["epic"], 1);
+        assert_eq!(records[1]["assignee"], "alice");
+        assert_eq!(records[1]["work_logs"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn export_should_write_markdown() {
+        let markdown = export(&db_state(), Format::Markdown, &Selection::default()).unwrap();
+
+        assert_eq!(
+            markdown,
+            "# Epics and stories
+
+## 1 Checkout
+
+Status: OPEN | Due: 2099-01-31
+
+Pay, then ship
+
+- [ ] STORY 3 Pay with \"card\", fast (IN PROGRESS, assignee alice)
+- [x] STORY 4 Order mail (RESOLVED)
+
+## 2 Search
+
+Status: CLOSED
+
+No stories.
+"
+        );
+    }
+
+    #[test]
+    fn export_should_only_select_the_filtered_items() {
+        let db_state = db_state();
+        let ids = |selection: Selection| {
+            select(&db_state, &selection)
+                .into_iter()
+                .map(|(epic_id, story_ids)| (epic_id.unwrap(), story_ids))
+                .collect::<Vec<_>>()
+        };
+
+        let unresolved = Filter {
+            hide_done: true,
+            ..Filter::default()
+        };
+        assert_eq!(
+            ids(Selection {
+                filter: unresolved,
+                ..Selection::default()
+            }),
+            vec![(1, vec![3])]
+        );
+
+        let mine = Filter {
+            only_mine: true,
+            ..Filter::default()
+        };
+        assert_eq!(
+            ids(Selection {
+                filter: mine.clone(),
+                user: "alice".to_owned(),
+                epic: None,
+            }),
+            vec![(1, vec![3])]
+        );
+        assert_eq!(
+            ids(Selection {
+                filter: mine,
+                user: "bob".to_owned(),
+                epic: None,
+            }),
+            vec![]
+        );
+
+        assert_eq!(
+            ids(Selection {
+                epic: Some(2),
+                ..Selection::default()
+            }),
+            vec![(2, vec![])]
+        );
+    }
+}
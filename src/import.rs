@@ -0,0 +1,544 @@
+/// This module imports stories from CSV files, e.g. legacy backlogs kept in spreadsheets.
+///
+/// Every row becomes a story. The columns are mapped to the fields of the story by a mapping,
+/// and the epics of the stories are looked up by name and created if they don't exist yet.
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::dates::{parse_date, parse_duration};
+use crate::db::JiraDatabase;
+use crate::models::{DBState, Epic, Status, Story, StoryType, ID};
+
+/// The fields of a story a column can be mapped to
+#[derive(Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum Field {
+    Name,
+    Description,
+    Status,
+    Type,
+    /// the name of the epic of the story
+    Epic,
+    DueDate,
+    OriginalEstimate,
+    RemainingEstimate,
+    FixVersion,
+    Assignee,
+}
+
+const FIELDS: [Field; 10] = [
+    Field::Name,
+    Field::Description,
+    Field::Status,
+    Field::Type,
+    Field::Epic,
+    Field::DueDate,
+    Field::OriginalEstimate,
+    Field::RemainingEstimate,
+    Field::FixVersion,
+    Field::Assignee,
+];
+
+impl Field {
+    /// The name of the field in mappings, e.g. `due-date`
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::Description => "description",
+            Field::Status => "status",
+            Field::Type => "type",
+            Field::Epic => "epic",
+            Field::DueDate => "due-date",
+            Field::OriginalEstimate => "original-estimate",
+            Field::RemainingEstimate => "remaining-estimate",
+            Field::FixVersion => "fix-version",
+            Field::Assignee => "assignee",
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        FIELDS
+            .into_iter()
+            .find(|field| normalize(field.name()) == normalize(input))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown field '{input}', use one of {}!",
+                    FIELDS.iter().map(|field| field.name()).join(", ")
+                )
+            })
+    }
+}
+
+/// Column names are compared without case, spaces, `-` and `_`, e.g. `Due Date` is `due-date`
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .replace(|c: char| c == '-' || c == '_' || c.is_whitespace(), "")
+}
+
+/// Which column holds which field. Fields which aren't mapped are read from the column with
+/// their name, if there is one.
+#[derive(Debug, Default, Clone)]
+pub struct Mapping {
+    columns: HashMap<Field, String>,
+}
+
+impl Mapping {
+    /// Reads a mapping file, a JSON object of fields and columns like `{"name": "Summary"}`
+    pub fn load(path: &str) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Can't read the mapping '{path}'! {e}"))?;
+        let columns = serde_json::from_str::<HashMap<String, String>>(&text)
+            .map_err(|e| anyhow!("Invalid mapping '{path}'! {e}"))?;
+
+        let mut mapping = Mapping::default();
+        for (field, column) in columns {
+            mapping.columns.insert(Field::from_str(&field)?, column);
+        }
+        Ok(mapping)
+    }
+
+    /// Maps a field to a column given like `name=Summary`, overriding the mapping file
+    pub fn set(&mut self, spec: &str) -> Result<()> {
+        let (field, column) = spec
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Invalid mapping '{spec}', use FIELD=COLUMN!"))?;
+        self.columns
+            .insert(Field::from_str(field)?, column.trim().to_owned());
+        Ok(())
+    }
+
+    /// The index of the column of every field which has one
+    fn resolve(&self, headers: &csv::StringRecord) -> Result<HashMap<Field, usize>> {
+        let find = |column: &str| {
+            headers
+                .iter()
+                .position(|header| normalize(header) == normalize(column))
+        };
+
+        let mut indexes = HashMap::new();
+        for field in FIELDS {
+            let index = match self.columns.get(&field) {
+                Some(column) => {
+                    Some(find(column).ok_or_else(|| anyhow!("Column '{column}' not found!"))?)
+                }
+                None => find(field.name()),
+            };
+            if let Some(index) = index {
+                indexes.insert(field, index);
+            }
+        }
+
+        if !indexes.contains_key(&Field::Name) {
+            return Err(anyhow!(
+                "No column for the name of the stories, map one with name=COLUMN!"
+            ));
+        }
+        Ok(indexes)
+    }
+}
+
+/// A story which is imported, with where it is read from
+#[derive(Debug, PartialEq)]
+pub struct NewStory {
+    /// e.g. `line 4` of a CSV file or the key of a Jira issue
+    pub source: String,
+    /// the name of the epic the story is added to
+    pub epic: String,
+    /// the index of the new epic the story is added to, `None` for the epic with the name
+    pub new_epic: Option<usize>,
+    pub story: Story,
+}
+
+/// What an import creates, and the rows which can't be imported
+#[derive(Debug, Default, PartialEq)]
+pub struct Plan {
+    /// the epics which don't exist yet, in the order of the file
+    pub new_epics: Vec<Epic>,
+    pub stories: Vec<NewStory>,
+    /// the errors of the rows, e.g. `line 4: Invalid status 'doing'!`
+    pub errors: Vec<String>,
+    /// what is imported differently than it is in the file, e.g. epics with the name of an
+    /// existing one
+    pub warnings: Vec<String>,
+}
+
+impl Plan {
+    /// Adds the epic unless one with its name exists already
+    pub fn add_epic(&mut self, db_state: &DBState, epic: Epic) {
+        let exists = find_epic(db_state, &epic.name).is_some()
+            || self
+                .new_epics
+                .iter()
+                .any(|other| same_name(&other.name, &epic.name));
+        if !exists {
+            self.new_epics.push(epic);
+        }
+    }
+
+    /// Adds an epic which is kept apart from new epics with the same name, its stories are
+    /// added with its index. An epic with the name of an existing one isn't added, its stories
+    /// go to the existing epic by name.
+    pub fn add_linked_epic(
+        &mut self,
+        db_state: &DBState,
+        source: &str,
+        epic: Epic,
+    ) -> Option<usize> {
+        if find_epic(db_state, &epic.name).is_some() {
+            self.warnings.push(format!(
+                "{source}: Epic '{}' exists already, its stories are added to it without changing its status and due date.",
+                epic.name
+            ));
+            return None;
+        }
+        if self
+            .new_epics
+            .iter()
+            .any(|other| same_name(&other.name, &epic.name))
+        {
+            self.warnings.push(format!(
+                "{source}: Another epic is named '{}' too, both are created.",
+                epic.name
+            ));
+        }
+        self.new_epics.push(epic);
+        Some(self.new_epics.len() - 1)
+    }
+
+    /// Adds the story to the epic with the name, which is added too if it doesn't exist
+    pub fn add_story(&mut self, db_state: &DBState, source: String, epic: String, story: Story) {
+        self.add_epic(db_state, Epic::new(epic.clone(), "".to_owned()));
+        self.stories.push(NewStory {
+            source,
+            epic,
+            new_epic: None,
+            story,
+        });
+    }
+
+    /// Adds the story to the new epic with the index, see `add_linked_epic`
+    pub fn add_story_to_new_epic(&mut self, source: String, new_epic: usize, story: Story) {
+        let epic = self
+            .new_epics
+            .get(new_epic)
+            .map(|epic| epic.name.clone())
+            .unwrap_or_default();
+        self.stories.push(NewStory {
+            source,
+            epic,
+            new_epic: Some(new_epic),
+            story,
+        });
+    }
+}
+
+/// Epics are matched by name, ignoring case and surrounding spaces
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// The existing epic with the name, the oldest one if several have it
+fn find_epic(db_state: &DBState, name: &str) -> Option<ID> {
+    db_state
+        .epics
+        .iter()
+        .filter(|(_, epic)| same_name(&epic.name, name))
+        .map(|(&id, _)| id)
+        .min()
+}
+
+/// Reads the CSV file and validates its rows without changing anything. Stories without
+/// an epic column or value are added to the default epic.
+pub fn plan(
+    db_state: &DBState,
+    csv: &str,
+    mapping: &Mapping,
+    default_epic: Option<&str>,
+    today: NaiveDate,
+) -> Result<Plan> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(csv.as_bytes());
+    let indexes = mapping.resolve(reader.headers()?)?;
+
+    let mut plan = Plan::default();
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                let line = error.position().map_or(0, |position| position.line());
+                plan.errors.push(format!("line {line}: {error}"));
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |position| position.line());
+        // empty rows, e.g. at the end of a spreadsheet, are skipped
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+
+        let value = |field: Field| {
+            indexes
+                .get(&field)
+                .and_then(|&index| record.get(index))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        match new_story(db_state, &value, default_epic, today) {
+            Ok((epic, story)) => plan.add_story(db_state, format!("line {line}"), epic, story),
+            Err(error) => plan.errors.push(format!("line {line}: {error}")),
+        }
+    }
+    Ok(plan)
+}
+
+/// The story of a row and the name of its epic
+fn new_story<'a>(
+    db_state: &DBState,
+    value: &dyn Fn(Field) -> Option<&'a str>,
+    default_epic: Option<&str>,
+    today: NaiveDate,
+) -> Result<(String, Story)> {
+    let name = value(Field::Name).ok_or_else(|| anyhow!("Missing name!"))?;
+    let epic = value(Field::Epic)
+        .or(default_epic)
+        .ok_or_else(|| anyhow!("Missing epic, use --epic NAME for rows without one!"))?;
+
+    let mut story = Story::new(
+        name.to_owned(),
+        value(Field::Description).unwrap_or_default().to_owned(),
+    );
+    if let Some(status) = value(Field::Status) {
+        story.status = Status::from_str(status)?;
+    }
+    if let Some(story_type) = value(Field::Type) {
+        story.story_type = StoryType::from_str(story_type)?;
+    }
+    if let Some(due_date) = value(Field::DueDate) {
+        story.due_date = Some(parse_date(due_date, today)?);
+    }
+    if let Some(estimate) = value(Field::OriginalEstimate) {
+        story.original_estimate = Some(parse_duration(estimate)?);
+    }
+    if let Some(estimate) = value(Field::RemainingEstimate) {
+        story.remaining_estimate = Some(parse_duration(estimate)?);
+    }
+    if let Some(fix_version) = value(Field::FixVersion) {
+        if !db_state
+            .versions
+            .iter()
+            .any(|version| version.name == fix_version)
+        {
+            return Err(anyhow!("Version '{fix_version}' not found!"));
+        }
+        story.fix_version = Some(fix_version.to_owned());
+    }
+    story.assignee = value(Field::Assignee).map(str::to_owned);
+
+    Ok((epic.to_owned(), story))
+}
+
+/// Creates the epics and then the stories of the plan in a single write, so nothing is
+/// imported when one of them fails. Returns the ids of the new stories.
+pub fn apply(db: &JiraDatabase, plan: Plan) -> Result<Vec<ID>> {
+    db.apply_changes(|db_state| {
+        let mut new_epic_ids = vec![];
+        for epic in plan.new_epics {
+            new_epic_ids.push(db_state.insert_epic(epic)?);
+        }
+
+        let mut story_ids = vec![];
+        for NewStory {
+            epic,
+            new_epic,
+            story,
+            ..
+        } in plan.stories
+        {
+            let epic_id = match new_epic {
+                Some(index) => new_epic_ids.get(index).copied(),
+                None => find_epic(db_state, &epic),
+            }
+            .ok_or_else(|| anyhow!("Epic '{epic}' not found!"))?;
+            story_ids.push(db_state.insert_story(story, epic_id)?);
+        }
+        db_state.refresh_derived_statuses();
+        Ok(story_ids)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+    use crate::models::Version;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    fn db() -> JiraDatabase {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        db.create_epic(Epic::new("Checkout".to_owned(), "".to_owned()))
+            .unwrap();
+        db.create_version(Version::new("1.0".to_owned(), None))
+            .unwrap();
+        db
+    }
+
+    const BACKLOG: &str = "\
+Summary,Epic Name,Status,Due Date,Estimate,Type,Fix Version,Owner
+Pay with card,Checkout,in progress,2024-03-15,1d 4h,bug,1.0,alice
+Search by name,Search,,,,,,
+Filter by price,search,Open,next friday,2h,task,,bob
+";
+
+    fn mapping() -> Mapping {
+        let mut mapping = Mapping::default();
+        mapping.set("name=Summary").unwrap();
+        mapping.set("epic=Epic Name").unwrap();
+        mapping.set("original-estimate=Estimate").unwrap();
+        mapping.set("assignee=owner").unwrap();
+        mapping
+    }
+
+    #[test]
+    fn plan_should_map_the_columns() {
+        let db = db();
+        let plan = plan(&db.read_db().unwrap(), BACKLOG, &mapping(), None, today()).unwrap();
+
+        assert_eq!(plan.errors, Vec::<String>::new());
+        assert_eq!(
+            plan.new_epics,
+            vec![Epic::new("Search".to_owned(), "".to_owned())]
+        );
+        assert_eq!(plan.stories.len(), 3);
+
+        let pay = &plan.stories[0];
+        assert_eq!(pay.source, "line 2");
+        assert_eq!(pay.epic, "Checkout");
+        assert_eq!(pay.story.name, "Pay with card");
+        assert_eq!(pay.story.status, Status::InProgress);
+        assert_eq!(pay.story.due_date, NaiveDate::from_ymd_opt(2024, 3, 15));
+        assert_eq!(pay.story.original_estimate, Some(12 * 60));
+        assert_eq!(pay.story.story_type, StoryType::Bug);
+        assert_eq!(pay.story.fix_version, Some("1.0".to_owned()));
+        assert_eq!(pay.story.assignee, Some("alice".to_owned()));
+
+        let filter = &plan.stories[2];
+        assert_eq!(filter.story.due_date, NaiveDate::from_ymd_opt(2024, 3, 8));
+        assert_eq!(filter.story.story_type, StoryType::Task);
+    }
+
+    #[test]
+    fn plan_should_report_invalid_rows() {
+        let db = db();
+        let csv = "\
+name,epic,status,fix-version,due-date
+Pay,Checkout,doing,,
+,Checkout,,,
+Ship,,,,
+Mail,Checkout,,2.0,
+Print,Checkout,,,someday
+Fine,Checkout,,,
+";
+        let plan = plan(
+            &db.read_db().unwrap(),
+            csv,
+            &Mapping::default(),
+            None,
+            today(),
+        )
+        .unwrap();
+
+        assert_eq!(plan.stories.len(), 1);
+        assert_eq!(plan.errors.len(), 5);
+        assert_eq!(plan.errors[0], "line 2: Invalid status 'doing'!");
+        assert_eq!(plan.errors[1], "line 3: Missing name!");
+        assert_eq!(plan.errors[3], "line 5: Version '2.0' not found!");
+    }
+
+    #[test]
+    fn plan_should_use_the_default_epic() {
+        let db = db();
+        let plan = plan(
+            &db.read_db().unwrap(),
+            "name\nPay\n",
+            &Mapping::default(),
+            Some("Imported"),
+            today(),
+        )
+        .unwrap();
+
+        assert_eq!(plan.new_epics[0].name, "Imported");
+        assert_eq!(plan.stories[0].epic, "Imported");
+    }
+
+    #[test]
+    fn plan_should_fail_without_name_column() {
+        let db = db();
+        let db_state = db.read_db().unwrap();
+
+        assert_eq!(
+            plan(
+                &db_state,
+                "title\nPay\n",
+                &Mapping::default(),
+                None,
+                today()
+            )
+            .is_err(),
+            true
+        );
+        let mut mapping = Mapping::default();
+        mapping.set("name=Summary").unwrap();
+        assert_eq!(
+            plan(&db_state, "title\nPay\n", &mapping, None, today()).is_err(),
+            true
+        );
+        assert_eq!(mapping.set("owner=Summary").is_err(), true);
+        assert_eq!(mapping.set("Summary").is_err(), true);
+    }
+
+    #[test]
+    fn apply_should_create_epics_and_stories() {
+        let db = db();
+        let plan = plan(&db.read_db().unwrap(), BACKLOG, &mapping(), None, today()).unwrap();
+
+        let story_ids = apply(&db, plan).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(story_ids, vec![3, 4, 5]);
+        assert_eq!(db_state.epics[&1].stories, vec![3]);
+        assert_eq!(db_state.epics[&2].name, "Search");
+        assert_eq!(db_state.epics[&2].stories, vec![4, 5]);
+        assert_eq!(db_state.backlog, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn apply_should_import_nothing_when_a_story_fails() {
+        let db = db();
+        let mut plan = plan(&db.read_db().unwrap(), BACKLOG, &mapping(), None, today()).unwrap();
+        plan.stories.push(NewStory {
+            source: "line 9".to_owned(),
+            epic: "Unknown".to_owned(),
+            new_epic: None,
+            story: Story::new("Lost".to_owned(), "".to_owned()),
+        });
+        let before = db.read_db().unwrap();
+
+        assert_eq!(apply(&db, plan).is_err(), true);
+        assert_eq!(db.read_db().unwrap(), before);
+    }
+}
@@ -0,0 +1,409 @@
+/// This module checks the consistency of the database and repairs the problems found.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
+use crate::models::{DBState, Epic, TrashedItem, ID};
+
+/// Name of the epic orphan stories are moved to by `repair`
+pub const RECOVERED_EPIC_NAME: &str = "Recovered stories";
+
+/// An inconsistency found in the database
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Problem {
+    /// a story is referenced by more than one epic, or more than once by the same epic
+    DuplicateStoryReference { story_id: ID, epic_ids: Vec<ID> },
+    /// an epic references a story which does not exist
+    MissingStory { epic_id: ID, story_id: ID },
+    /// a story does not belong to any epic
+    OrphanStory { story_id: ID },
+    /// the backlog references a story which does not exist or is ranked twice
+    InvalidBacklogEntry { story_id: ID },
+    /// `last_item_id` is lower than an id in use, so new items would overwrite existing ones
+    LastItemIdTooLow { last_item_id: ID, max_id: ID },
+    /// the counter of a project is lower than a key in use, e.g. `WEB-12` with counter 5
+    ProjectCounterTooLow {
+        project_key: String,
+        last_item_id: ID,
+        max_key: ID,
+    },
+    /// the running timer belongs to a story which does not exist
+    MissingTimerStory { story_id: ID },
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::DuplicateStoryReference { story_id, epic_ids } => write!(
+                f,
+                "story {story_id} is referenced several times by epics: {}",
+                epic_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+            ),
+            Problem::MissingStory { epic_id, story_id } => {
+                write!(f, "epic {epic_id} references missing story {story_id}")
+            }
+            Problem::OrphanStory { story_id } => {
+                write!(f, "story {story_id} does not belong to any epic")
+            }
+            Problem::InvalidBacklogEntry { story_id } => {
+                write!(f, "backlog entry {story_id} is missing or ranked twice")
+            }
+            Problem::LastItemIdTooLow {
+                last_item_id,
+                max_id,
+            } => write!(
+                f,
+                "last item id {last_item_id} is lower than the highest id {max_id} in use"
+            ),
+            Problem::ProjectCounterTooLow {
+                project_key,
+                last_item_id,
+                max_key,
+            } => write!(
+                f,
+                "counter {last_item_id} of project {project_key} is lower than the key {project_key}-{max_key} in use"
+            ),
+            Problem::MissingTimerStory { story_id } => {
+                write!(f, "the running timer belongs to missing story {story_id}")
+            }
+        }
+    }
+}
+
+/// Returns all problems found in the database, an empty list if it is consistent.
+pub fn check(db_state: &DBState) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    // which epics reference a story, ordered by id to get a stable report
+    let mut owners: BTreeMap<ID, Vec<ID>> = BTreeMap::new();
+    for (&epic_id, epic) in db_state.epics.iter().collect::<BTreeMap<_, _>>() {
+        for &story_id in &epic.stories {
+            owners.entry(story_id).or_default().push(epic_id);
+            if !db_state.stories.contains_key(&story_id) {
+                problems.push(Problem::MissingStory { epic_id, story_id });
+            }
+        }
+    }
+
+    for (&story_id, epic_ids) in &owners {
+        if epic_ids.len() > 1 && db_state.stories.contains_key(&story_id) {
+            problems.push(Problem::DuplicateStoryReference {
+                story_id,
+                epic_ids: epic_ids.clone(),
+            });
+        }
+    }
+
+    for &story_id in db_state.stories.keys().collect::<BTreeSet<_>>() {
+        if !owners.contains_key(&story_id) {
+            problems.push(Problem::OrphanStory { story_id });
+        }
+    }
+
+    let mut ranked = BTreeSet::new();
+    for &story_id in &db_state.backlog {
+        if !db_state.stories.contains_key(&story_id) || !ranked.insert(story_id) {
+            problems.push(Problem::InvalidBacklogEntry { story_id });
+        }
+    }
+
+    let max_id = max_id(db_state);
+    if max_id > db_state.last_item_id {
+        problems.push(Problem::LastItemIdTooLow {
+            last_item_id: db_state.last_item_id,
+            max_id,
+        });
+    }
+
+    for project in &db_state.projects {
+        let max_key = max_project_key(db_state, &project.key);
+        if max_key > project.last_item_id {
+            problems.push(Problem::ProjectCounterTooLow {
+                project_key: project.key.clone(),
+                last_item_id: project.last_item_id,
+                max_key,
+            });
+        }
+    }
+
+    if let Some(timer) = &db_state.active_timer {
+        if !db_state.stories.contains_key(&timer.story_id) {
+            problems.push(Problem::MissingTimerStory {
+                story_id: timer.story_id,
+            });
+        }
+    }
+
+    problems
+}
+
+/// Repairs all problems found by `check` and returns them. Nothing is deleted:
+/// duplicate references are kept once in the epic with the lowest id, references to missing
+/// stories are dropped and orphan stories are moved to a new epic. A timer of a missing
+/// story is stopped without logging work.
+pub fn repair(db_state: &mut DBState) -> Vec<Problem> {
+    let problems = check(db_state);
+
+    // the counters first, so new ids don't collide with existing items
+    db_state.last_item_id = db_state.last_item_id.max(max_id(db_state));
+    for index in 0..db_state.projects.len() {
+        let max_key = max_project_key(db_state, &db_state.projects[index].key);
+        let project = &mut db_state.projects[index];
+        project.last_item_id = project.last_item_id.max(max_key);
+    }
+
+    let mut orphans = vec![];
+    for problem in &problems {
+        match problem {
+            Problem::DuplicateStoryReference { story_id, epic_ids } => {
+                // the epic ids are sorted, an epic referencing the story twice is listed twice
+                let mut epic_ids = epic_ids.clone();
+                epic_ids.dedup();
+                for (index, epic_id) in epic_ids.iter().enumerate() {
+                    if let Some(epic) = db_state.epics.get_mut(epic_id) {
+                        // the first epic keeps its first reference, the others none
+                        let mut seen = index > 0;
+                        epic.stories.retain(|id| {
+                            let keep = id != story_id || !seen;
+                            seen |= id == story_id;
+                            keep
+                        });
+                    }
+                }
+            }
+            Problem::MissingStory { epic_id, story_id } => {
+                if let Some(epic) = db_state.epics.get_mut(epic_id) {
+                    epic.stories.retain(|id| id != story_id);
+                }
+            }
+            Problem::OrphanStory { story_id } => orphans.push(*story_id),
+            Problem::MissingTimerStory { .. } => db_state.active_timer = None,
+            Problem::InvalidBacklogEntry { .. }
+            | Problem::LastItemIdTooLow { .. }
+            | Problem::ProjectCounterTooLow { .. } => {}
+        }
+    }
+
+    if !orphans.is_empty() {
+        let mut epic = Epic::new(
+            RECOVERED_EPIC_NAME.to_owned(),
+            "Stories which did not belong to any epic".to_owned(),
+        );
+        epic.stories = orphans;
+        db_state.last_item_id += 1;
+        db_state.epics.insert(db_state.last_item_id, epic);
+    }
+
+    // keep the first rank of every existing story
+    let mut ranked = BTreeSet::new();
+    let stories = &db_state.stories;
+    db_state
+        .backlog
+        .retain(|id| stories.contains_key(id) && ranked.insert(*id));
+
+    problems
+}
+
+/// The highest id in use, including the items in the trash
+fn max_id(db_state: &DBState) -> ID {
+    let trash_ids = db_state.trash.iter().flat_map(|item| {
+        let story_ids = match &item.item {
+            TrashedItem::Epic { stories, .. } => stories.keys().copied().collect(),
+            TrashedItem::Story { .. } => vec![],
+        };
+        story_ids.into_iter().chain([item.id])
+    });
+
+    db_state
+        .epics
+        .keys()
+        .chain(db_state.stories.keys())
+        .copied()
+        .chain(trash_ids)
+        .max()
+        .unwrap_or(0)
+}
+
+/// The highest number of the keys like `WEB-12` in use for the given project
+fn max_project_key(db_state: &DBState, project_key: &str) -> ID {
+    let prefix = format!("{project_key}-");
+    let trash_keys = db_state.trash.iter().flat_map(|item| match &item.item {
+        TrashedItem::Epic { epic, stories } => stories
+            .values()
+            .map(|story| story.key.clone())
+            .chain([epic.key.clone()])
+            .collect::<Vec<_>>(),
+        TrashedItem::Story { story, .. } => vec![story.key.clone()],
+    });
+
+    db_state
+        .epics
+        .values()
+        .map(|epic| epic.key.clone())
+        .chain(db_state.stories.values().map(|story| story.key.clone()))
+        .chain(trash_keys)
+        .flatten()
+        .filter_map(|key| key.strip_prefix(&prefix)?.parse::<ID>().ok())
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Project, Story, Timer};
+    use chrono::NaiveDate;
+
+    fn broken_db_state() -> DBState {
+        let mut db_state = DBState {
+            last_item_id: 2,
+            ..Default::default()
+        };
+
+        let mut epic_1 = Epic::new("".to_owned(), "".to_owned());
+        epic_1.stories = vec![3, 4];
+        let mut epic_2 = Epic::new("".to_owned(), "".to_owned());
+        epic_2.stories = vec![3];
+        db_state.epics.insert(1, epic_1);
+        db_state.epics.insert(2, epic_2);
+
+        db_state
+            .stories
+            .insert(3, Story::new("".to_owned(), "".to_owned()));
+        let mut orphan = Story::new("".to_owned(), "".to_owned());
+        orphan.key = Some("WEB-7".to_owned());
+        db_state.stories.insert(5, orphan);
+
+        db_state.backlog = vec![3, 4, 3];
+        db_state
+            .projects
+            .push(Project::new("WEB".to_owned(), "".to_owned()));
+
+        db_state
+    }
+
+    #[test]
+    fn check_should_find_no_problems_in_consistent_db() {
+        let mut db_state = DBState::default();
+        let mut epic = Epic::new("".to_owned(), "".to_owned());
+        epic.stories = vec![2];
+        db_state.epics.insert(1, epic);
+        db_state
+            .stories
+            .insert(2, Story::new("".to_owned(), "".to_owned()));
+        db_state.backlog = vec![2];
+        db_state.last_item_id = 2;
+
+        assert_eq!(check(&db_state), vec![]);
+    }
+
+    #[test]
+    fn check_should_find_all_problems() {
+        let db_state = broken_db_state();
+
+        assert_eq!(
+            check(&db_state),
+            vec![
+                Problem::MissingStory {
+                    epic_id: 1,
+                    story_id: 4
+                },
+                Problem::DuplicateStoryReference {
+                    story_id: 3,
+                    epic_ids: vec![1, 2]
+                },
+                Problem::OrphanStory { story_id: 5 },
+                Problem::InvalidBacklogEntry { story_id: 4 },
+                Problem::InvalidBacklogEntry { story_id: 3 },
+                Problem::LastItemIdTooLow {
+                    last_item_id: 2,
+                    max_id: 5
+                },
+                Problem::ProjectCounterTooLow {
+                    project_key: "WEB".to_owned(),
+                    last_item_id: 0,
+                    max_key: 7
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn repair_should_keep_a_story_referenced_twice_by_one_epic() {
+        let mut db_state = DBState {
+            last_item_id: 3,
+            ..Default::default()
+        };
+        let mut epic_1 = Epic::new("".to_owned(), "".to_owned());
+        epic_1.stories = vec![2, 3, 2];
+        db_state.epics.insert(1, epic_1);
+        for story_id in [2, 3] {
+            db_state
+                .stories
+                .insert(story_id, Story::new("".to_owned(), "".to_owned()));
+        }
+        db_state.backlog = vec![2, 3];
+
+        assert_eq!(
+            check(&db_state),
+            vec![Problem::DuplicateStoryReference {
+                story_id: 2,
+                epic_ids: vec![1, 1]
+            }]
+        );
+        assert_eq!(repair(&mut db_state).len(), 1);
+        assert_eq!(check(&db_state), vec![]);
+        assert_eq!(db_state.epics.get(&1).unwrap().stories, vec![2, 3]);
+
+        // twice in the second epic too
+        let mut epic_4 = Epic::new("".to_owned(), "".to_owned());
+        epic_4.stories = vec![3, 3];
+        db_state.epics.insert(4, epic_4);
+        db_state.last_item_id = 4;
+
+        repair(&mut db_state);
+        assert_eq!(check(&db_state), vec![]);
+        assert_eq!(db_state.epics.get(&1).unwrap().stories, vec![2, 3]);
+        assert_eq!(db_state.epics.get(&4).unwrap().stories.is_empty(), true);
+    }
+
+    #[test]
+    fn repair_should_stop_the_timer_of_a_missing_story() {
+        let mut db_state = DBState {
+            active_timer: Some(Timer {
+                story_id: 7,
+                author: "alice".to_owned(),
+                started: NaiveDate::from_ymd_opt(2026, 10, 19)
+                    .unwrap()
+                    .and_hms_opt(9, 0, 0)
+                    .unwrap(),
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            check(&db_state),
+            vec![Problem::MissingTimerStory { story_id: 7 }]
+        );
+        assert_eq!(repair(&mut db_state).len(), 1);
+        assert_eq!(db_state.active_timer, None);
+    }
+
+    #[test]
+    fn repair_should_fix_all_problems() {
+        let mut db_state = broken_db_state();
+
+        assert_eq!(repair(&mut db_state).len(), 7);
+        assert_eq!(check(&db_state), vec![]);
+
+        assert_eq!(db_state.epics.get(&1).unwrap().stories, vec![3]);
+        assert_eq!(db_state.epics.get(&2).unwrap().stories.is_empty(), true);
+        assert_eq!(db_state.backlog, vec![3]);
+        assert_eq!(db_state.projects[0].last_item_id, 7);
+
+        // the orphan story is kept in a new epic
+        assert_eq!(db_state.last_item_id, 6);
+        let recovered = db_state.epics.get(&6).unwrap();
+        assert_eq!(recovered.name, RECOVERED_EPIC_NAME.to_owned());
+        assert_eq!(recovered.stories, vec![5]);
+    }
+}
@@ -1,20 +1,97 @@
-use std::io;
+#[cfg(test)]
+use std::{cell::RefCell, collections::VecDeque};
+use std::{
+    env,
+    io::{self, Write},
+};
 
-pub const DFT: &str = "\x1B[0m";
-pub const GREEN: &str = "\x1B[38;2;20;198;13m";
-pub const RED: &str = "\x1B[38;2;255;0;0m";
+use crate::ui::Palette;
 
-//pub const BLUE: &str = "\x1B[38;2;58;150;221m";
-//pub const TEAL: &str = "\x1B[38;2;0;214;214m";
+/// Where the app reads the commands of the main loop and the answers to the prompts from:
+/// the keyboard, or a script in the tests.
+pub trait InputSource {
+    /// Shows the message and reads the next line without the line break,
+    /// `None` at the end of the input or when the prompt is cancelled.
+    fn read_line(&self, message: &str) -> Option<String>;
 
-pub fn get_user_input() -> String {
-    let mut user_input = String::new();
+    /// The answer to a prompt, empty at the end of the input
+    fn prompt(&self, message: &str) -> String {
+        self.read_line(message).unwrap_or_default()
+    }
 
-    io::stdin().read_line(&mut user_input).unwrap();
+    /// Shows the line separating a prompt from the page above
+    fn show_separator(&self, separator: &str);
 
-    user_input
+    /// Shows why an answer to a prompt was rejected, in the error colour if there is one
+    fn show_error(&self, error: &str);
 }
 
-pub fn wait_for_key_press() {
-    io::stdin().read_line(&mut String::new()).unwrap();
+/// Reads the input from stdin, the `[Y/n]` choices of a message are highlighted.
+#[derive(Default)]
+pub struct Keyboard {
+    pub palette: Palette,
+}
+
+impl InputSource for Keyboard {
+    fn read_line(&self, message: &str) -> Option<String> {
+        print!("{}", self.palette.highlight_keys(message));
+        io::stdout().flush().ok()?;
+
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim().to_string()),
+        }
+    }
+
+    fn show_separator(&self, separator: &str) {
+        println!("{separator}");
+    }
+
+    fn show_error(&self, error: &str) {
+        println!("{}{error}{}", self.palette.error, self.palette.reset);
+    }
+}
+
+/// Plays back a script of lines, e.g. `["c", "Epic", "Description", "q"]`,
+/// and records the messages and the errors of the prompts.
+#[cfg(test)]
+pub struct ScriptedInput {
+    lines: RefCell<VecDeque<String>>,
+    pub messages: RefCell<Vec<String>>,
+    pub errors: RefCell<Vec<String>>,
+}
+
+#[cfg(test)]
+impl ScriptedInput {
+    pub fn new(lines: &[&str]) -> Self {
+        Self {
+            lines: RefCell::new(lines.iter().map(|line| line.to_string()).collect()),
+            messages: RefCell::new(vec![]),
+            errors: RefCell::new(vec![]),
+        }
+    }
+}
+
+#[cfg(test)]
+impl InputSource for ScriptedInput {
+    fn read_line(&self, message: &str) -> Option<String> {
+        if !message.is_empty() {
+            self.messages.borrow_mut().push(message.to_string());
+        }
+        self.lines.borrow_mut().pop_front()
+    }
+
+    fn show_separator(&self, _separator: &str) {}
+
+    fn show_error(&self, error: &str) {
+        self.errors.borrow_mut().push(error.to_string());
+    }
+}
+
+/// The name of the user running the app, as reported by the environment.
+pub fn current_user_name() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
 }
@@ -0,0 +1,845 @@
+/// This module exchanges epics and stories with Atlassian Jira, which the app is modelled on.
+///
+/// Issues are read from the JSON of the REST search endpoint (`/rest/api/2/search` and
+/// `/rest/api/3/search`) and from Jira's CSV export, and written back in both formats.
+/// Issues of the type `Epic` become epics, all others stories of the epic they link to.
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::import::Plan;
+use crate::models::{DBState, Epic, Minutes, Status, Story, StoryType, ID};
+
+/// How Jira's CSV export writes dates, e.g. `15/May/24 12:00 AM`, the time is left out
+const CSV_DATE_FORMAT: &str = "%d/%b/%y";
+
+/// The columns of the CSV export, in the order of Jira's own export
+const CSV_COLUMNS: [&str; 12] = [
+    "Summary",
+    "Issue key",
+    "Issue id",
+    "Parent id",
+    "Issue Type",
+    "Status",
+    "Status Category",
+    "Assignee",
+    "Due Date",
+    "Original Estimate",
+    "Remaining Estimate",
+    "Description",
+];
+
+/// An issue as read from or written to either format
+#[derive(Debug, Default, PartialEq)]
+struct Issue {
+    id: String,
+    key: String,
+    summary: String,
+    description: String,
+    issue_type: String,
+    status: String,
+    /// `new`, `indeterminate` or `done`, in CSV exports the name like `In Progress`
+    status_category: Option<String>,
+    assignee: Option<String>,
+    due_date: Option<NaiveDate>,
+    original_estimate: Option<Minutes>,
+    remaining_estimate: Option<Minutes>,
+    /// the key or id of the epic, or of the story of a sub-task
+    parent: Option<String>,
+}
+
+impl Issue {
+    fn is_epic(&self) -> bool {
+        self.issue_type.eq_ignore_ascii_case("epic")
+    }
+
+    /// The status of the workflow or, for custom ones like `QA`, of its category
+    fn status(&self) -> Result<Status> {
+        match self.status.trim().to_lowercase().as_str() {
+            "to do" | "open" | "backlog" | "reopened" | "selected for development" => {
+                return Ok(Status::Open)
+            }
+            "in progress" | "in review" => return Ok(Status::InProgress),
+            "resolved" => return Ok(Status::Resolved),
+            "done" | "closed" => return Ok(Status::Closed),
+            _ => {}
+        }
+
+        let category = self.status_category.as_deref().map(str::to_lowercase);
+        match category.as_deref() {
+            Some("new" | "to do") => Ok(Status::Open),
+            Some("indeterminate" | "in progress") => Ok(Status::InProgress),
+            Some("done") => Ok(Status::Closed),
+            _ => Err(anyhow!("Unknown status '{}'!", self.status)),
+        }
+    }
+
+    /// Bugs and tasks keep their type, sub-tasks become tasks and anything else a story
+    fn story_type(&self) -> StoryType {
+        match self.issue_type.trim().to_lowercase().as_str() {
+            "bug" => StoryType::Bug,
+            "task" | "sub-task" | "subtask" => StoryType::Task,
+            _ => StoryType::Story,
+        }
+    }
+
+    fn epic(db_state: &DBState, id: ID) -> Self {
+        let epic = &db_state.epics[&id];
+        Issue {
+            id: id.to_string(),
+            key: db_state.display_id(id),
+            summary: epic.name.clone(),
+            description: epic.description.clone(),
+            issue_type: "Epic".to_owned(),
+            due_date: epic.due_date,
+            ..Issue::with_status(&epic.status)
+        }
+    }
+
+    fn story(db_state: &DBState, id: ID, epic_id: Option<ID>) -> Self {
+        let story = &db_state.stories[&id];
+        let issue_type = match story.story_type {
+            StoryType::Story => "Story",
+            StoryType::Bug => "Bug",
+            StoryType::Task => "Task",
+        };
+        Issue {
+            id: id.to_string(),
+            key: db_state.display_id(id),
+            summary: story.name.clone(),
+            description: story.description.clone(),
+            issue_type: issue_type.to_owned(),
+            assignee: story.assignee.clone(),
+            due_date: story.due_date,
+            original_estimate: story.original_estimate,
+            remaining_estimate: story.remaining_estimate,
+            parent: epic_id.map(|epic_id| epic_id.to_string()),
+            ..Issue::with_status(&story.status)
+        }
+    }
+
+    /// An issue with the status of Jira's default workflow
+    fn with_status(status: &Status) -> Self {
+        let (status, category) = match status {
+            Status::Open => ("To Do", "new"),
+            Status::InProgress => ("In Progress", "indeterminate"),
+            Status::Resolved => ("Resolved", "done"),
+            Status::Closed => ("Done", "done"),
+        };
+        Issue {
+            status: status.to_owned(),
+            status_category: Some(category.to_owned()),
+            ..Issue::default()
+        }
+    }
+}
+
+/// The response of the search endpoint, with the fields the app knows
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResult {
+    #[serde(default)]
+    start_at: usize,
+    #[serde(default)]
+    max_results: usize,
+    #[serde(default)]
+    total: usize,
+    issues: Vec<JsonIssue>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonIssue {
+    id: String,
+    key: String,
+    fields: JsonFields,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonFields {
+    summary: String,
+    issuetype: JsonIssueType,
+    status: JsonStatus,
+    /// plain text in version 2 of the API, a document in version 3
+    #[serde(default)]
+    description: Option<Value>,
+    #[serde(default)]
+    assignee: Option<JsonUser>,
+    #[serde(default)]
+    duedate: Option<NaiveDate>,
+    /// in seconds
+    #[serde(default)]
+    timeoriginalestimate: Option<u64>,
+    /// the remaining estimate in seconds
+    #[serde(default)]
+    timeestimate: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<JsonParent>,
+    /// the epic link of company-managed projects in Jira Cloud, the key of the epic
+    #[serde(
+        default,
+        rename = "customfield_10014",
+        skip_serializing_if = "Option::is_none"
+    )]
+    epic_link: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonIssueType {
+    name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonStatus {
+    name: String,
+    #[serde(default)]
+    status_category: Option<JsonStatusCategory>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonStatusCategory {
+    key: String,
+}
+
+/// Jira Server has user names, Jira Cloud only display names
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonUser {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default)]
+    display_name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonParent {
+    id: String,
+    key: String,
+}
+
+impl From<JsonIssue> for Issue {
+    fn from(JsonIssue { id, key, fields }: JsonIssue) -> Self {
+        let seconds_to_minutes = |seconds: u64| Minutes::try_from(seconds / 60).ok();
+        Issue {
+            id,
+            key,
+            summary: fields.summary,
+            description: fields
+                .description
+                .as_ref()
+                .map(plain_text)
+                .unwrap_or_default(),
+            issue_type: fields.issuetype.name,
+            status: fields.status.name,
+            status_category: fields.status.status_category.map(|category| category.key),
+            assignee: fields
+                .assignee
+                .map(|user| user.name.unwrap_or(user.display_name)),
+            due_date: fields.duedate,
+            original_estimate: fields.timeoriginalestimate.and_then(seconds_to_minutes),
+            remaining_estimate: fields.timeestimate.and_then(seconds_to_minutes),
+            parent: fields.parent.map(|parent| parent.id).or(fields.epic_link),
+        }
+    }
+}
+
+impl From<Issue> for JsonIssue {
+    fn from(issue: Issue) -> Self {
+        JsonIssue {
+            id: issue.id,
+            key: issue.key,
+            fields: JsonFields {
+                summary: issue.summary,
+                issuetype: JsonIssueType {
+                    name: issue.issue_type,
+                },
+                status: JsonStatus {
+                    name: issue.status,
+                    status_category: issue.status_category.map(|key| JsonStatusCategory { key }),
+                },
+                description: Some(Value::String(issue.description)),
+                assignee: issue.assignee.map(|name| JsonUser {
+                    name: Some(name.clone()),
+                    display_name: name,
+                }),
+                duedate: issue.due_date,
+                timeoriginalestimate: issue.original_estimate.map(|minutes| minutes as u64 * 60),
+                timeestimate: issue.remaining_estimate.map(|minutes| minutes as u64 * 60),
+                parent: issue.parent.map(|id| JsonParent {
+                    key: id.clone(),
+                    id,
+                }),
+                epic_link: None,
+            },
+        }
+    }
+}
+
+/// The text of a description, documents of version 3 of the API have one paragraph per line
+fn plain_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Object(node) => {
+            if let Some(Value::String(text)) = node.get("text") {
+                return text.clone();
+            }
+            let separator = match node.get("type").and_then(Value::as_str) {
+                Some("doc") => "\n",
+                _ => "",
+            };
+            node.get("content")
+                .and_then(Value::as_array)
+                .map(|content| {
+                    content
+                        .iter()
+                        .map(plain_text)
+                        .collect::<Vec<_>>()
+                        .join(separator)
+                })
+                .unwrap_or_default()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Reads the issues of a search result, stories without an epic are added to the default epic
+pub fn read_json(db_state: &DBState, json: &str, default_epic: Option<&str>) -> Result<Plan> {
+    let result = serde_json::from_str::<SearchResult>(json)
+        .map_err(|e| anyhow!("Invalid Jira search result! {e}"))?;
+    let issues = result.issues.into_iter().map(Issue::from).collect();
+    Ok(plan(db_state, issues, vec![], default_epic))
+}
+
+/// The columns of a CSV export, only the summary, key, type and status are required
+struct CsvColumns {
+    summary: usize,
+    key: usize,
+    issue_type: usize,
+    status: usize,
+    id: Option<usize>,
+    status_category: Option<usize>,
+    assignee: Option<usize>,
+    description: Option<usize>,
+    due_date: Option<usize>,
+    original_estimate: Option<usize>,
+    remaining_estimate: Option<usize>,
+    parent: Option<usize>,
+    epic_link: Option<usize>,
+}
+
+impl CsvColumns {
+    fn new(headers: &csv::StringRecord) -> Result<Self> {
+        // columns can be repeated, e.g. `Sprint` once per sprint, the first one is used
+        let column = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                headers
+                    .iter()
+                    .position(|header| header.trim().eq_ignore_ascii_case(name))
+            })
+        };
+        let required = |name: &str| {
+            column(&[name])
+                .ok_or_else(|| anyhow!("Column '{name}' not found, is it a Jira export?"))
+        };
+
+        Ok(CsvColumns {
+            summary: required("Summary")?,
+            key: required("Issue key")?,
+            issue_type: required("Issue Type")?,
+            status: required("Status")?,
+            id: column(&["Issue id"]),
+            status_category: column(&["Status Category"]),
+            assignee: column(&["Assignee"]),
+            description: column(&["Description"]),
+            due_date: column(&["Due Date"]),
+            original_estimate: column(&["Original Estimate"]),
+            remaining_estimate: column(&["Remaining Estimate"]),
+            // the id of the parent in Jira Cloud, `Parent id` for sub-tasks in Jira Server
+            parent: column(&["Parent id", "Parent"]),
+            epic_link: column(&["Custom field (Epic Link)", "Epic Link"]),
+        })
+    }
+
+    fn issue(&self, record: &csv::StringRecord) -> Result<Issue> {
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|index| record.get(index))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let owned = |index: Option<usize>| cell(index).map(str::to_owned);
+        let estimate = |index: Option<usize>| {
+            cell(index)
+                .map(|seconds| {
+                    seconds
+                        .parse::<Minutes>()
+                        .map(|seconds| seconds / 60)
+                        .map_err(|_| anyhow!("Invalid estimate '{seconds}'!"))
+                })
+                .transpose()
+        };
+
+        let due_date = cell(self.due_date)
+            .map(|value| {
+                // `15/May/24 12:00 AM`, or ISO dates in exports from other tools
+                let date = value.split_whitespace().next().unwrap_or_default();
+                NaiveDate::parse_from_str(date, CSV_DATE_FORMAT)
+                    .or_else(|_| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+                    .map_err(|_| anyhow!("Invalid date '{value}'!"))
+            })
+            .transpose()?;
+
+        Ok(Issue {
+            id: owned(self.id).unwrap_or_default(),
+            key: owned(Some(self.key)).ok_or_else(|| anyhow!("Missing issue key!"))?,
+            summary: owned(Some(self.summary)).ok_or_else(|| anyhow!("Missing summary!"))?,
+            description: owned(self.description).unwrap_or_default(),
+            issue_type: owned(Some(self.issue_type)).unwrap_or_default(),
+            status: owned(Some(self.status)).unwrap_or_default(),
+            status_category: owned(self.status_category),
+            assignee: owned(self.assignee),
+            due_date,
+            original_estimate: estimate(self.original_estimate)?,
+            remaining_estimate: estimate(self.remaining_estimate)?,
+            parent: owned(self.parent).or_else(|| owned(self.epic_link)),
+        })
+    }
+}
+
+/// Reads the issues of a CSV export, stories without an epic are added to the default epic
+pub fn read_csv(db_state: &DBState, csv: &str, default_epic: Option<&str>) -> Result<Plan> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(csv.as_bytes());
+    let columns = CsvColumns::new(reader.headers()?)?;
+
+    let mut issues = vec![];
+    let mut errors = vec![];
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                let line = error.position().map_or(0, |position| position.line());
+                errors.push(format!("line {line}: {error}"));
+                continue;
+            }
+        };
+        match columns.issue(&record) {
+            Ok(issue) => issues.push(issue),
+            Err(error) => {
+                let source = record
+                    .get(columns.key)
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty())
+                    .map(str::to_owned)
+                    .unwrap_or_else(|| {
+                        format!("line {}", record.position().map_or(0, |p| p.line()))
+                    });
+                errors.push(format!("{source}: {error}"));
+            }
+        }
+    }
+    Ok(plan(db_state, issues, errors, default_epic))
+}
+
+/// The epics and stories of the issues, sub-tasks are added to the epic of their story
+fn plan(
+    db_state: &DBState,
+    issues: Vec<Issue>,
+    errors: Vec<String>,
+    default_epic: Option<&str>,
+) -> Plan {
+    let mut plan = Plan {
+        errors,
+        ..Plan::default()
+    };
+    let links: HashMap<&str, &Issue> = issues
+        .iter()
+        .flat_map(|issue| [(issue.id.as_str(), issue), (issue.key.as_str(), issue)])
+        .filter(|(link, _)| !link.is_empty())
+        .collect();
+
+    // the new epics by the key of their issue, epics with the same summary are kept apart
+    let mut new_epics: HashMap<&str, usize> = HashMap::new();
+    for issue in issues.iter().filter(|issue| issue.is_epic()) {
+        match issue.status() {
+            Ok(status) => {
+                let mut epic = Epic::new(issue.summary.clone(), issue.description.clone());
+                epic.status = status;
+                epic.due_date = issue.due_date;
+                if let Some(index) = plan.add_linked_epic(db_state, &issue.key, epic) {
+                    new_epics.insert(issue.key.as_str(), index);
+                }
+            }
+            Err(error) => plan.errors.push(format!("{}: {error}", issue.key)),
+        }
+    }
+
+    for issue in issues.iter().filter(|issue| !issue.is_epic()) {
+        let source = issue.key.clone();
+        match story(issue, &links) {
+            Ok((Some(epic), story)) => match new_epics.get(epic.key.as_str()) {
+                Some(&index) => plan.add_story_to_new_epic(source, index, story),
+                None => plan.add_story(db_state, source, epic.summary.clone(), story),
+            },
+            Ok((None, story)) => match default_epic {
+                Some(epic) => plan.add_story(db_state, source, epic.to_owned(), story),
+                None => plan.errors.push(format!(
+                    "{source}: Missing epic, use --epic NAME for issues without one!"
+                )),
+            },
+            Err(error) => plan.errors.push(format!("{source}: {error}")),
+        }
+    }
+    plan
+}
+
+/// The story of an issue and the issue of its epic, if it links to one
+fn story<'a>(
+    issue: &Issue,
+    links: &HashMap<&str, &'a Issue>,
+) -> Result<(Option<&'a Issue>, Story)> {
+    // a sub-task links to its story, which links to the epic
+    let mut parent = issue.parent.as_deref();
+    let mut epic = None;
+    for _ in 0..2 {
+        let Some(link) = parent else {
+            break;
+        };
+        let linked = links
+            .get(link)
+            .ok_or_else(|| anyhow!("Parent '{link}' is not in the export!"))?;
+        if linked.is_epic() {
+            epic = Some(*linked);
+            break;
+        }
+        parent = linked.parent.as_deref();
+    }
+
+    let mut story = Story::new(issue.summary.clone(), issue.description.clone());
+    story.status = issue.status()?;
+    story.story_type = issue.story_type();
+    story.assignee = issue.assignee.clone();
+    story.due_date = issue.due_date;
+    story.original_estimate = issue.original_estimate;
+    story.remaining_estimate = issue.remaining_estimate;
+    Ok((epic, story))
+}
+
+/// The issues of the selected epics and stories, each epic before its stories
+fn issues(db_state: &DBState, selected: &[(Option<ID>, Vec<ID>)]) -> Vec<Issue> {
+    selected
+        .iter()
+        .flat_map(|(epic_id, story_ids)| {
+            let epic = epic_id.map(|epic_id| Issue::epic(db_state, epic_id));
+            let stories = story_ids
+                .iter()
+                .map(move |&story_id| Issue::story(db_state, story_id, *epic_id));
+            epic.into_iter().chain(stories)
+        })
+        .collect()
+}
+
+/// Writes the epics and stories like a response of the search endpoint
+pub fn to_json(db_state: &DBState, selected: &[(Option<ID>, Vec<ID>)]) -> Result<String> {
+    let issues = issues(db_state, selected)
+        .into_iter()
+        .map(|issue| {
+            let parent_id = issue.parent.as_deref().and_then(|id| id.parse::<ID>().ok());
+            let mut json_issue = JsonIssue::from(issue);
+            // the issues refer to their parent by id, the search result has its key too
+            if let (Some(parent), Some(parent_id)) = (&mut json_issue.fields.parent, parent_id) {
+                parent.key = db_state.display_id(parent_id);
+            }
+            json_issue
+        })
+        .collect::<Vec<_>>();
+    let result = SearchResult {
+        start_at: 0,
+        max_results: issues.len(),
+        total: issues.len(),
+        issues,
+    };
+    Ok(serde_json::to_string_pretty(&result)? + "\n")
+}
+
+/// Writes the epics and stories like Jira's CSV export, which its CSV importer reads too
+pub fn to_csv(db_state: &DBState, selected: &[(Option<ID>, Vec<ID>)]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(CSV_COLUMNS)?;
+
+    for issue in issues(db_state, selected) {
+        let category = issue.status_category.as_deref().map(|key| match key {
+            "new" => "To Do",
+            "indeterminate" => "In Progress",
+            _ => "Done",
+        });
+        let seconds = |minutes: Option<Minutes>| {
+            minutes
+                .map(|minutes| (minutes as u64 * 60).to_string())
+                .unwrap_or_default()
+        };
+        writer.write_record([
+            issue.summary.as_str(),
+            &issue.key,
+            &issue.id,
+            issue.parent.as_deref().unwrap_or_default(),
+            &issue.issue_type,
+            &issue.status,
+            category.unwrap_or_default(),
+            issue.assignee.as_deref().unwrap_or_default(),
+            &issue
+                .due_date
+                .map(|date| format!("{} 12:00 AM", date.format(CSV_DATE_FORMAT)))
+                .unwrap_or_default(),
+            &seconds(issue.original_estimate),
+            &seconds(issue.remaining_estimate),
+            &issue.description,
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+    use crate::db::JiraDatabase;
+    use crate::import;
+
+    const SEARCH_RESULT: &str = include_str!("../tests/fixtures/jira-search.json");
+    const CSV_EXPORT: &str = include_str!("../tests/fixtures/jira-export.csv");
+
+    fn story<'a>(plan: &'a Plan, source: &str) -> &'a import::NewStory {
+        plan.stories
+            .iter()
+            .find(|new_story| new_story.source == source)
+            .unwrap()
+    }
+
+    #[test]
+    fn read_json_should_map_the_issues() {
+        let plan = read_json(&DBState::default(), SEARCH_RESULT, Some("Chores")).unwrap();
+
+        assert_eq!(plan.errors, Vec::<String>::new());
+        assert_eq!(plan.new_epics.len(), 2);
+        let checkout = &plan.new_epics[0];
+        assert_eq!(checkout.name, "Checkout");
+        assert_eq!(
+            checkout.description,
+            "Everything between the cart and the order mail."
+        );
+        assert_eq!(checkout.status, Status::InProgress);
+        assert_eq!(checkout.due_date, NaiveDate::from_ymd_opt(2024, 6, 30));
+        assert_eq!(plan.new_epics[1].name, "Chores");
+
+        let pay = story(&plan, "SHOP-2");
+        assert_eq!(pay.epic, "Checkout");
+        assert_eq!(pay.story.name, "Pay with card");
+        assert_eq!(
+            pay.story.description,
+            "Visa and Mastercard.\n3-D Secure included."
+        );
+        assert_eq!(pay.story.status, Status::InProgress);
+        assert_eq!(pay.story.story_type, StoryType::Story);
+        assert_eq!(pay.story.assignee, Some("alice".to_owned()));
+        assert_eq!(pay.story.due_date, NaiveDate::from_ymd_opt(2024, 5, 15));
+        assert_eq!(pay.story.original_estimate, Some(480));
+        assert_eq!(pay.story.remaining_estimate, Some(240));
+
+        // linked with the epic link field instead of the parent
+        let declined = story(&plan, "SHOP-3");
+        assert_eq!(declined.epic, "Checkout");
+        assert_eq!(declined.story.status, Status::Closed);
+        assert_eq!(declined.story.story_type, StoryType::Bug);
+
+        let tests = story(&plan, "SHOP-4");
+        assert_eq!(tests.epic, "Checkout");
+        assert_eq!(tests.story.status, Status::Open);
+        assert_eq!(tests.story.story_type, StoryType::Task);
+
+        // a custom status of the `In Progress` category
+        let readme = story(&plan, "SHOP-5");
+        assert_eq!(readme.epic, "Chores");
+        assert_eq!(readme.story.status, Status::InProgress);
+    }
+
+    #[test]
+    fn read_csv_should_map_the_issues() {
+        let plan = read_csv(&DBState::default(), CSV_EXPORT, Some("Chores")).unwrap();
+
+        assert_eq!(plan.errors, Vec::<String>::new());
+        assert_eq!(plan.new_epics.len(), 2);
+        assert_eq!(plan.new_epics[0].status, Status::Open);
+        assert_eq!(
+            plan.new_epics[0].due_date,
+            NaiveDate::from_ymd_opt(2024, 6, 30)
+        );
+
+        let pay = story(&plan, "WEB-2");
+        assert_eq!(pay.epic, "Checkout");
+        assert_eq!(
+            pay.story.description,
+            "Visa and Mastercard, \"3-D Secure\" included.\nAmex later."
+        );
+        assert_eq!(pay.story.assignee, Some("alice".to_owned()));
+        assert_eq!(pay.story.due_date, NaiveDate::from_ymd_opt(2024, 5, 15));
+        assert_eq!(pay.story.original_estimate, Some(480));
+
+        assert_eq!(story(&plan, "WEB-3").story.status, Status::Resolved);
+        // the sub-task links to its story by id
+        assert_eq!(story(&plan, "WEB-4").epic, "Checkout");
+        assert_eq!(story(&plan, "WEB-5").epic, "Chores");
+        assert_eq!(story(&plan, "WEB-5").story.status, Status::Closed);
+    }
+
+    #[test]
+    fn read_should_report_invalid_issues() {
+        let plan = read_csv(&DBState::default(), CSV_EXPORT, None).unwrap();
+        assert_eq!(
+            plan.errors,
+            vec!["WEB-5: Missing epic, use --epic NAME for issues without one!".to_owned()]
+        );
+
+        let csv = "\
+Summary,Issue key,Issue Type,Status,Parent,Original Estimate
+Pay,WEB-2,Story,QA,,
+Ship,WEB-3,Story,Open,WEB-9,
+Mail,WEB-4,Story,Open,,1h
+";
+        let plan = read_csv(&DBState::default(), csv, Some("Chores")).unwrap();
+        assert_eq!(
+            plan.errors,
+            vec![
+                "WEB-4: Invalid estimate '1h'!".to_owned(),
+                "WEB-2: Unknown status 'QA'!".to_owned(),
+                "WEB-3: Parent 'WEB-9' is not in the export!".to_owned(),
+            ]
+        );
+
+        assert_eq!(
+            read_csv(&DBState::default(), "id,name\n", None).is_err(),
+            true
+        );
+        assert_eq!(read_json(&DBState::default(), "[]", None).is_err(), true);
+    }
+
+    #[test]
+    fn read_should_add_stories_to_existing_epics() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        db.create_epic(Epic::new("Checkout".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let plan = read_json(&db.read_db().unwrap(), SEARCH_RESULT, Some("Chores")).unwrap();
+        assert_eq!(plan.new_epics.len(), 1);
+        assert_eq!(
+            plan.warnings,
+            vec!["SHOP-1: Epic 'Checkout' exists already, its stories are added to it without changing its status and due date.".to_owned()]
+        );
+
+        let story_ids = import::apply(&db, plan).unwrap();
+        let db_state = db.read_db().unwrap();
+        assert_eq!(story_ids.len(), 4);
+        assert_eq!(db_state.epics[&1].stories.len(), 3);
+        assert_eq!(db_state.epics[&2].name, "Chores");
+    }
+
+    #[test]
+    fn read_should_keep_epics_with_the_same_summary_apart() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        let csv = "\
+Summary,Issue key,Issue Type,Status,Parent
+Checkout,WEB-1,Epic,Open,
+Checkout,WEB-2,Epic,Done,
+Pay,WEB-3,Story,Open,WEB-1
+Ship,WEB-4,Story,Open,WEB-2
+";
+        let plan = read_csv(&db.read_db().unwrap(), csv, None).unwrap();
+        assert_eq!(plan.errors, Vec::<String>::new());
+        assert_eq!(
+            plan.warnings,
+            vec!["WEB-2: Another epic is named 'Checkout' too, both are created.".to_owned()]
+        );
+        assert_eq!(plan.new_epics.len(), 2);
+        assert_eq!(story(&plan, "WEB-3").new_epic, Some(0));
+        assert_eq!(story(&plan, "WEB-4").new_epic, Some(1));
+
+        import::apply(&db, plan).unwrap();
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&1].stories, vec![3]);
+        assert_eq!(db_state.epics[&2].stories, vec![4]);
+        assert_eq!(db_state.epics[&2].status, Status::Closed);
+    }
+
+    fn exported() -> DBState {
+        let mut db_state = DBState::default();
+        let mut epic = Epic::new("Checkout".to_owned(), "Cart, then mail".to_owned());
+        epic.stories = vec![2];
+        epic.due_date = NaiveDate::from_ymd_opt(2024, 6, 30);
+        db_state.epics.insert(1, epic);
+
+        let mut story = Story::new("Pay".to_owned(), "".to_owned());
+        story.status = Status::Resolved;
+        story.story_type = StoryType::Bug;
+        story.assignee = Some("alice".to_owned());
+        story.original_estimate = Some(90);
+        db_state.stories.insert(2, story);
+
+        db_state
+    }
+
+    #[test]
+    fn to_csv_should_write_jira_columns() {
+        let db_state = exported();
+        let selected = [(Some(1), vec![2])];
+        let csv = to_csv(&db_state, &selected).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                "Summary,Issue key,Issue id,Parent id,Issue Type,Status,Status Category,Assignee,\
+                 Due Date,Original Estimate,Remaining Estimate,Description",
+                "Checkout,1,1,,Epic,To Do,To Do,,30/Jun/24 12:00 AM,,,\"Cart, then mail\"",
+                "Pay,2,2,1,Bug,Resolved,Done,alice,,5400,,",
+            ]
+        );
+    }
+
+    #[test]
+    fn exports_should_read_back_the_same() {
+        let db_state = exported();
+        let selected = [(Some(1), vec![2])];
+
+        for plan in [
+            read_json(
+                &DBState::default(),
+                &to_json(&db_state, &selected).unwrap(),
+                None,
+            )
+            .unwrap(),
+            read_csv(
+                &DBState::default(),
+                &to_csv(&db_state, &selected).unwrap(),
+                None,
+            )
+            .unwrap(),
+        ] {
+            assert_eq!(plan.errors, Vec::<String>::new());
+            assert_eq!(plan.new_epics.len(), 1);
+            let epic = &plan.new_epics[0];
+            assert_eq!(epic.name, "Checkout");
+            assert_eq!(epic.description, "Cart, then mail");
+            assert_eq!(epic.due_date, db_state.epics[&1].due_date);
+            assert_eq!(plan.stories[0].epic, "Checkout");
+            assert_eq!(plan.stories[0].story, db_state.stories[&2]);
+        }
+    }
+}
@@ -1,73 +1,195 @@
+// the test suites compare booleans with assert_eq!, e.g. `assert_eq!(result.is_ok(), true)`
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
 /// The entry point for the my-jira CLI app.
 ///
 // library modules:
+mod cli;
+mod dates;
 mod db;
+mod export;
+mod import;
+mod integrity;
 mod io_utils;
+mod jira;
 mod models;
 mod navigator;
+mod reports;
+mod tui;
 mod ui;
 
 // namespace
 use db::*;
 use io_utils::*;
 use navigator::*;
+use std::io::{self, IsTerminal, Write};
 use std::rc::Rc;
 
+const DB_PATH: &str = "./data/db.json";
+/// the key bindings of the interactive app, the defaults are used without this file
+const KEYMAP_PATH: &str = "./data/keymap.json";
+
+/// entry point of the command line, starts the interactive app when no command is given
+pub fn run_cli(args: &[String]) -> anyhow::Result<()> {
+    // `--no-color` turns off the colours of the interactive app
+    let no_color = args.iter().any(|arg| arg == "--no-color");
+    let args = args
+        .iter()
+        .filter(|arg| *arg != "--no-color")
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let db = JiraDatabase::new(DB_PATH.to_string());
+    if args.is_empty() {
+        let palette = palette(&db, no_color);
+        let keymap = ui::Keymap::load(KEYMAP_PATH)?;
+        return run(Rc::new(db), palette, Rc::new(keymap));
+    }
+    if args[0] == "tui" {
+        let palette = palette(&db, no_color);
+        let keymap = ui::Keymap::load(KEYMAP_PATH)?;
+        return tui::run(Rc::new(db), palette, Rc::new(keymap));
+    }
+    cli::execute(&db, &args)
+}
+
+/// The colours of the user's theme, as far as the terminal supports them
+fn palette(db: &JiraDatabase, no_color: bool) -> ui::Palette {
+    let support = match no_color {
+        true => ui::ColorSupport::None,
+        false => ui::ColorSupport::detect(),
+    };
+    let theme = db
+        .read_db()
+        .ok()
+        .and_then(|db_state| db_state.settings_of(&current_user_name()).theme)
+        .and_then(|name| ui::Theme::by_name(&name))
+        .unwrap_or_default();
+    theme.palette(support)
+}
+
+/// The rows of long tables the user wants to see at once, `None` fits them into the terminal.
+/// It is read before every page, so a change with `my-jira page-size` applies right away.
+fn page_size(db: &JiraDatabase) -> Option<usize> {
+    db.read_db()
+        .ok()
+        .and_then(|db_state| db_state.settings_of(&current_user_name()).page_size)
+}
+
 /// entry point of application
-pub fn run() {
-    let db = Rc::new(JiraDatabase::new("./data/db.json".to_string()));
-    let mut nav = Navigator::new(db);
+pub fn run(
+    db: Rc<JiraDatabase>,
+    palette: ui::Palette,
+    keymap: Rc<ui::Keymap>,
+) -> anyhow::Result<()> {
+    let keyboard = Keyboard {
+        palette: palette.clone(),
+    };
+    // a piped output is not cleared either
+    let clear_screen = io::stdout().is_terminal();
+    run_with(
+        db,
+        Rc::new(keyboard),
+        &mut io::stdout(),
+        palette,
+        keymap,
+        clear_screen,
+    )
+}
+
+/// The main loop: reads the commands and the answers to the prompts from `input` and draws
+/// the pages to `out`. The terminal is cleared before every page when `clear_screen` is set.
+fn run_with(
+    db: Rc<JiraDatabase>,
+    input: Rc<dyn InputSource>,
+    out: &mut dyn Write,
+    palette: ui::Palette,
+    keymap: Rc<ui::Keymap>,
+    clear_screen: bool,
+) -> anyhow::Result<()> {
+    let prompts = ui::Prompts::with_input(input.clone(), palette.clone());
+    let mut nav = Navigator::with_prompts(db.clone(), prompts);
+    let ui::Palette {
+        error: error_color,
+        reset,
+        ..
+    } = palette.clone();
+
+    // a quick check on load, the problems are only reported, `my-jira check --repair` fixes them
+    if let Ok(problems) = db.check() {
+        if !problems.is_empty() {
+            writeln!(
+                out,
+                "{error_color}The database has {} problem(s), run `my-jira check` for details.\nPress enter key to continue...{reset}",
+                problems.len()
+            )?;
+            input.read_line("");
+        }
+    }
 
     loop {
         // 1. get current page from navigator. If there is no current page exit the loop.
-        clearscreen::clear().unwrap();
+        if clear_screen {
+            clearscreen::clear()?;
+        }
         let page = match nav.get_current_page() {
             None => break,
             Some(page) => page,
         };
         // 2. render page
-        if let Err(error) = page.draw_page() {
-            println!(
-                "{RED}Error rendering page: {}\nPress enter key to continue...{DFT}",
-                error
-            );
-            wait_for_key_press();
+        let mut screen = ui::Screen::new(out)
+            .with_palette(palette.clone())
+            .with_keymap(keymap.clone())
+            .with_page_size(page_size(&db));
+        if let Err(error) = ui::draw_header(&db, &mut screen, &nav.breadcrumbs())
+            .and_then(|_| page.draw_page(&mut screen))
+        {
+            writeln!(
+                screen,
+                "{error_color}Error rendering page: {error}\nPress enter key to continue...{reset}"
+            )?;
+            input.read_line("");
+        };
+        // 3. get user input, the app ends with the input
+        let Some(input_line) = input.read_line("") else {
+            break;
         };
-        // 3. get user input
-        let input: String = get_user_input().trim().into();
         // 4. pass input to page's input handler
-        let result = page.handle_input(input.as_str());
+        let result = keymap.handle_input(page.as_ref(), input_line.as_str());
         // 5. if the page's input handler returns an action let the navigator process the action
         match result {
             Ok(action) => {
                 if let Some(action) = action {
                     if let Err(error) = nav.handle_action(action) {
-                        println!(
-                            "{RED}Error handle action: {}\nPress enter key to continue...{DFT}",
-                            error
-                        );
-                        wait_for_key_press();
+                        writeln!(
+                            screen,
+                            "{error_color}Error handle action: {error}\nPress enter key to continue...{reset}"
+                        )?;
+                        input.read_line("");
                     }
                 }
             }
-            Err(_e) => {
-                println!("Input doesn't lead to any action.");
+            Err(e) => {
+                writeln!(screen, "Input doesn't lead to any action. {e}")?;
             }
         }
     }
+
+    Ok(())
 }
 
 // #########.#########.#########.#########.#########.#########.#########.#########.#########.#########.
 #[cfg(test)]
 mod tests {
     use super::*;
+    use io_utils::ScriptedInput;
     use models::{DBState, Epic, Status, Story};
     use std::collections::HashMap;
     use std::fs::{File, OpenOptions};
     use std::io::BufReader;
+    use tempfile::NamedTempFile;
 
-    #[test]
-    fn test_write_db_with_entries() {
+    fn db_state_with_entries() -> DBState {
         let mut epics = HashMap::new();
         let mut stories = HashMap::new();
 
@@ -93,32 +215,216 @@ mod tests {
         story.status = Status::InProgress;
         stories.insert(3, story);
 
-        let db = DBState {
+        DBState {
             last_item_id: 3,
-            epics: epics,
-            stories: stories,
-        };
+            epics,
+            stories,
+            backlog: vec![2, 3],
+            ..Default::default()
+        }
+    }
+
+    fn write_db_with_entries(path: &std::path::Path) {
+        let db = db_state_with_entries();
 
         let file = OpenOptions::new()
             .create(true)
             .write(true)
             .truncate(true)
-            .open("./src/data/foo2.json")
+            .open(path)
             .expect("failure to open file for write");
 
         serde_json::to_writer_pretty(&file, &db).expect("failure to serialiaze to JSON");
     }
 
+    #[test]
+    fn test_write_db_with_entries() {
+        let tmpfile = NamedTempFile::new().unwrap();
+        write_db_with_entries(tmpfile.path());
+    }
+
     #[test]
     fn test_read_db() {
-        let file = File::open("./src/data/foo2.json").expect("failure to open file for read");
+        let tmpfile = NamedTempFile::new().unwrap();
+        write_db_with_entries(tmpfile.path());
+
+        let file = File::open(tmpfile.path()).expect("failure to open file for read");
         let reader = BufReader::new(file);
 
         // Read the JSON contents of the file as an instance of `DBState`.
         let u: DBState =
             serde_json::from_reader(reader).expect("failure to deserialiaze from JSON");
 
-        println!("{u:?}");
+        assert_eq!(u, db_state_with_entries());
         //let content: Result<DBState> = serde_json::from_reader(&file); //, &db).expect("failure to serialiaze to JSON");
     }
+
+    mod end_to_end {
+        use super::*;
+
+        /// Runs the main loop with the script against a database file with the given state,
+        /// returns the final state and the output without colours.
+        fn run_script(db_state: &DBState, script: &[&str]) -> (DBState, String, Rc<ScriptedInput>) {
+            let file = NamedTempFile::new().unwrap();
+            serde_json::to_writer(file.as_file(), db_state).unwrap();
+            let db = Rc::new(JiraDatabase::new(file.path().to_str().unwrap().to_owned()));
+            let input = Rc::new(ScriptedInput::new(script));
+            let palette = ui::Theme::default().palette(ui::ColorSupport::None);
+            let mut output = vec![];
+
+            let result = run_with(
+                db.clone(),
+                input.clone(),
+                &mut output,
+                palette,
+                Rc::new(ui::Keymap::default()),
+                false,
+            );
+            assert_eq!(result.is_ok(), true);
+
+            (
+                db.read_db().unwrap(),
+                String::from_utf8(output).unwrap(),
+                input,
+            )
+        }
+
+        #[test]
+        fn run_should_create_epic_and_story() {
+            let script = [
+                "c",
+                "Checkout",
+                "Payment and order confirmation",
+                "1",
+                "c",
+                "Pay with credit card",
+                "",
+                "p",
+                "q",
+            ];
+            let (db_state, output, input) = run_script(&DBState::default(), &script);
+
+            let epic = db_state.epics.get(&1).unwrap();
+            assert_eq!(epic.name, "Checkout".to_owned());
+            assert_eq!(epic.stories, vec![2]);
+            let story = db_state.stories.get(&2).unwrap();
+            assert_eq!(story.name, "Pay with credit card".to_owned());
+            assert_eq!(db_state.backlog, vec![2]);
+
+            assert_eq!(input.messages.borrow().len(), 4);
+            assert_eq!(output.contains("EPICS"), true);
+            assert_eq!(output.contains("Pay with credit card"), true);
+            assert_eq!(output.contains('\x1B'), false);
+        }
+
+        #[test]
+        fn run_should_move_deleted_epic_to_trash() {
+            let mut db_state = DBState {
+                last_item_id: 1,
+                ..Default::default()
+            };
+            db_state
+                .epics
+                .insert(1, Epic::new("Checkout".to_owned(), "".to_owned()));
+
+            let (db_state, output, _) = run_script(&db_state, &["1", "d", "Y", "q"]);
+
+            assert_eq!(db_state.epics.is_empty(), true);
+            assert_eq!(db_state.trash.len(), 1);
+            assert_eq!(output.contains("EPIC"), true);
+        }
+
+        #[test]
+        fn run_should_keep_db_when_delete_is_not_confirmed() {
+            let mut db_state = DBState {
+                last_item_id: 1,
+                ..Default::default()
+            };
+            db_state
+                .epics
+                .insert(1, Epic::new("Checkout".to_owned(), "".to_owned()));
+
+            let (db_state, _, _) = run_script(&db_state, &["1", "d", "n", "q"]);
+
+            assert_eq!(db_state.epics.len(), 1);
+            assert_eq!(db_state.trash.is_empty(), true);
+        }
+
+        #[test]
+        fn run_should_report_errors_and_continue() {
+            let mut db_state = DBState {
+                last_item_id: 1,
+                ..Default::default()
+            };
+            let mut epic = Epic::new("Checkout".to_owned(), "".to_owned());
+            epic.derived_status = true;
+            db_state.epics.insert(1, epic);
+
+            // the status of an epic with a derived status can't be changed
+            let (db_state, output, _) = run_script(&db_state, &["1", "u", "3", "", "p", "q"]);
+
+            assert_eq!(db_state.epics.get(&1).unwrap().status, Status::Open);
+            assert_eq!(
+                output
+                    .lines()
+                    .any(|line| line == "Error handle action: Can't update the Epic 1 status!"),
+                true
+            );
+        }
+
+        #[test]
+        fn run_should_end_with_the_input() {
+            let (db_state, output, _) = run_script(&DBState::default(), &["c", "Checkout"]);
+
+            // the end of the input cancels the prompt for the description
+            assert_eq!(db_state.epics.is_empty(), true);
+            assert_eq!(output.matches("EPICS").count(), 2);
+        }
+
+        #[test]
+        fn run_should_handle_help_and_command_palette() {
+            let mut db_state = DBState {
+                last_item_id: 2,
+                ..Default::default()
+            };
+            let mut epic = Epic::new("Checkout".to_owned(), "".to_owned());
+            epic.stories = vec![2];
+            db_state.epics.insert(1, epic);
+            db_state
+                .stories
+                .insert(2, Story::new("Pay".to_owned(), "".to_owned()));
+
+            let script = ["?", "p", ":goto 2", ":st res", ":teleport", "q"];
+            let (db_state, output, _) = run_script(&db_state, &script);
+
+            assert_eq!(db_state.stories.get(&2).unwrap().status, Status::Resolved);
+            assert_eq!(output.contains("COMMAND PALETTE"), true);
+            assert_eq!(output.contains("Unknown command 'teleport'!"), true);
+        }
+
+        #[test]
+        fn run_should_go_to_stories_below_their_epic() {
+            let mut db_state = DBState {
+                last_item_id: 2,
+                ..Default::default()
+            };
+            let mut epic = Epic::new("Checkout".to_owned(), "".to_owned());
+            epic.stories = vec![2];
+            db_state.epics.insert(1, epic);
+            db_state
+                .stories
+                .insert(2, Story::new("Pay".to_owned(), "".to_owned()));
+
+            // the story is typed on the home page, previous shows its epic
+            let (_, output, _) = run_script(&db_state, &["2", "p"]);
+
+            let pages = output
+                .lines()
+                .filter(|line| line.starts_with("---"))
+                .map(|line| line.trim_matches('-').trim())
+                .collect::<Vec<_>>();
+            assert_eq!(pages, vec!["EPICS", "STORY", "WORK LOG", "EPIC", "STORIES"]);
+            assert_eq!(output.contains("Home > Epic 1 Checkout > Story 2\n"), true);
+        }
+    }
 }
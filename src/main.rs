@@ -1,3 +1,8 @@
 fn main() {
-    my_jira::run();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if let Err(error) = my_jira::run_cli(&args) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
 }
@@ -1,26 +1,148 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 /// This module contains all data-structures of the entire model.
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use crate::dates::total_duration;
 
 pub type ID = u32;
+pub type Minutes = u32;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Action {
-    NavigateToEpicDetail { epic_id: u32 },
-    NavigateToStoryDetail { epic_id: u32, story_id: u32 },
+    NavigateToProjects,
+    NavigateToProject {
+        project_key: Option<String>,
+    },
+    NavigateToEpicDetail {
+        epic_id: u32,
+    },
+    NavigateToStoryDetail {
+        epic_id: u32,
+        story_id: u32,
+    },
     NavigateToPreviousPage,
-    CreateEpic,
-    UpdateEpicStatus { epic_id: u32 },
-    DeleteEpic { epic_id: u32 },
-    CreateStory { epic_id: u32 },
-    UpdateStoryStatus { story_id: u32 },
-    DeleteStory { epic_id: u32, story_id: u32 },
+    NavigateToBacklog,
+    NavigateToUpcoming,
+    NavigateToReleases,
+    NavigateToRelease {
+        version: String,
+    },
+    NavigateToTrash,
+    NavigateToArchive,
+    /// the help page of the current page
+    NavigateToHelp,
+    /// goes back to the first page
+    NavigateToHome,
+    /// goes to the page left with previous
+    NavigateForward,
+    /// the recently visited epics and stories
+    NavigateToRecent,
+    /// shows the next or previous pages of a long table
+    ScrollList {
+        pages: isize,
+    },
+    /// goes to an epic or story by its id or key
+    GoTo {
+        id: String,
+    },
+    /// changes how a table is sorted for the current user
+    SortTable {
+        table: Table,
+        change: SortChange,
+    },
+    CreateProject,
+    CreateEpic {
+        project_key: Option<String>,
+    },
+    UpdateEpicStatus {
+        epic_id: u32,
+    },
+    UpdateEpicDueDate {
+        epic_id: u32,
+    },
+    ToggleEpicDerivedStatus {
+        epic_id: u32,
+    },
+    ToggleEpicArchived {
+        epic_id: u32,
+    },
+    DeleteEpic {
+        epic_id: u32,
+    },
+    CreateStory {
+        epic_id: u32,
+    },
+    UpdateStoryStatus {
+        story_id: u32,
+    },
+    UpdateStoryType {
+        story_id: u32,
+    },
+    UpdateStoryFixVersion {
+        story_id: u32,
+    },
+    UpdateStoryDueDate {
+        story_id: u32,
+    },
+    UpdateStoryEstimate {
+        story_id: u32,
+    },
+    LogWork {
+        story_id: u32,
+    },
+    ToggleTimer {
+        story_id: u32,
+    },
+    DeleteStory {
+        epic_id: u32,
+        story_id: u32,
+    },
+    MoveStoryInBacklog {
+        story_id: u32,
+        direction: RankMove,
+    },
+    CreateVersion,
+    ReleaseVersion {
+        version: String,
+    },
+    RestoreItem {
+        id: u32,
+    },
+    /// changes which items the tables show for the current user
+    ChangeFilter {
+        change: FilterChange,
+    },
+    /// assigns the story to the current user, or unassigns it if it is assigned to them
+    ToggleStoryAssignee {
+        story_id: u32,
+    },
+    /// changes the status of an epic or story without a prompt
+    UpdateStatus {
+        id: u32,
+        status: Status,
+    },
+    /// moves a story to the epic with the given id or key
+    MoveStoryToEpic {
+        story_id: u32,
+        epic_id: String,
+    },
     Exit,
 }
 
-/// Possible states of a task
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+/// Possible moves of a story within the backlog ranking
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RankMove {
+    Up,
+    Down,
+    Top,
+    Bottom,
+}
+
+/// Possible states of a task, in the order of the workflow
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
 pub enum Status {
     Open,
     InProgress,
@@ -28,6 +150,50 @@ pub enum Status {
     Closed,
 }
 
+impl Status {
+    /// A story is unresolved as long as somebody still has to work on it
+    pub fn is_unresolved(&self) -> bool {
+        matches!(self, Status::Open | Status::InProgress)
+    }
+}
+
+/// Possible types of a story
+#[derive(
+    Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default, Hash, PartialOrd, Ord,
+)]
+pub enum StoryType {
+    #[default]
+    Story,
+    Bug,
+    Task,
+}
+
+impl Display for StoryType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match &self {
+            StoryType::Story => "STORY",
+            StoryType::Bug => "BUG",
+            StoryType::Task => "TASK",
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+impl FromStr for StoryType {
+    type Err = anyhow::Error;
+
+    /// Parses a type like `story`, `BUG` or `Task`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "story" => Ok(StoryType::Story),
+            "bug" => Ok(StoryType::Bug),
+            "task" => Ok(StoryType::Task),
+            _ => Err(anyhow::anyhow!("Invalid type '{input}'!")),
+        }
+    }
+}
+
 impl Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let text = match &self {
@@ -40,12 +206,49 @@ impl Display for Status {
         write!(f, "{text}")
     }
 }
+
+impl FromStr for Status {
+    type Err = anyhow::Error;
+
+    /// Parses a status like `open`, `in-progress`, `IN PROGRESS`, `resolved` or `closed`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let normalized = input
+            .trim()
+            .to_lowercase()
+            .replace(|c: char| c == '-' || c == '_' || c.is_whitespace(), "");
+        match normalized.as_str() {
+            "open" => Ok(Status::Open),
+            "inprogress" => Ok(Status::InProgress),
+            "resolved" => Ok(Status::Resolved),
+            "closed" => Ok(Status::Closed),
+            _ => Err(anyhow::anyhow!("Invalid status '{input}'!")),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Epic {
     pub name: String,
     pub description: String,
     pub status: Status,
     pub stories: Vec<ID>,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    /// key of the project the epic belongs to
+    #[serde(default)]
+    pub project: Option<String>,
+    /// human-readable id like `WEB-12`, assigned when created within a project
+    #[serde(default)]
+    pub key: Option<String>,
+    /// the status is calculated from the stories instead of being set manually
+    #[serde(default)]
+    pub derived_status: bool,
+    /// archived epics are hidden from the home page
+    #[serde(default)]
+    pub archived: bool,
+    /// when the epic was created or changed last
+    #[serde(default)]
+    pub updated_at: Option<NaiveDateTime>,
 }
 
 impl Epic {
@@ -56,8 +259,18 @@ impl Epic {
             description,
             status: Status::Open,
             stories: vec![],
+            due_date: None,
+            project: None,
+            key: None,
+            derived_status: false,
+            archived: false,
+            updated_at: None,
         }
     }
+
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        is_overdue(&self.status, self.due_date, today)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -65,6 +278,28 @@ pub struct Story {
     pub name: String,
     pub description: String,
     pub status: Status,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub original_estimate: Option<Minutes>,
+    #[serde(default)]
+    pub remaining_estimate: Option<Minutes>,
+    #[serde(default)]
+    pub work_logs: Vec<WorkLog>,
+    /// human-readable id like `WEB-12`, assigned when created within a project
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub story_type: StoryType,
+    /// name of the version the story is shipped with
+    #[serde(default)]
+    pub fix_version: Option<String>,
+    /// name of the user working on the story
+    #[serde(default)]
+    pub assignee: Option<String>,
+    /// when the story was created or changed last
+    #[serde(default)]
+    pub updated_at: Option<NaiveDateTime>,
 }
 
 impl Story {
@@ -74,14 +309,1011 @@ impl Story {
             name,
             description,
             status: Status::Open,
+            due_date: None,
+            original_estimate: None,
+            remaining_estimate: None,
+            work_logs: vec![],
+            key: None,
+            story_type: StoryType::Story,
+            fix_version: None,
+            assignee: None,
+            updated_at: None,
+        }
+    }
+
+    /// Total time logged against this story
+    pub fn time_spent(&self) -> Minutes {
+        total_duration(self.work_logs.iter().map(|log| log.duration))
+    }
+
+    /// Adds the work log, the logged work is taken from the remaining estimate
+    pub fn add_work_log(&mut self, work_log: WorkLog) {
+        self.remaining_estimate = self
+            .remaining_estimate
+            .map(|remaining| remaining.saturating_sub(work_log.duration));
+        self.work_logs.push(work_log);
+    }
+
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        is_overdue(&self.status, self.due_date, today)
+    }
+}
+
+/// A project groups epics under a short key, e.g. `WEB`, with its own item counter
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Project {
+    pub key: String,
+    pub name: String,
+    pub last_item_id: ID,
+}
+
+impl Project {
+    pub fn new(key: String, name: String) -> Self {
+        Project {
+            key: key.to_uppercase(),
+            name,
+            last_item_id: 0,
         }
     }
 }
 
-/// This struct represents the entire db state which includes the last_item_id, epics, and stories
+/// Number of stories per status, e.g. of an epic
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct StatusCounts {
+    pub open: usize,
+    pub in_progress: usize,
+    pub resolved: usize,
+    pub closed: usize,
+}
+
+impl StatusCounts {
+    pub fn add(&mut self, status: &Status) {
+        match status {
+            Status::Open => self.open += 1,
+            Status::InProgress => self.in_progress += 1,
+            Status::Resolved => self.resolved += 1,
+            Status::Closed => self.closed += 1,
+        }
+    }
+
+    /// number of resolved or closed stories
+    pub fn done(&self) -> usize {
+        self.resolved + self.closed
+    }
+
+    pub fn total(&self) -> usize {
+        self.open + self.in_progress + self.resolved + self.closed
+    }
+
+    /// all stories are done, so the epic could be resolved
+    pub fn is_complete(&self) -> bool {
+        self.total() > 0 && self.done() == self.total()
+    }
+}
+
+/// Rules how the status of an epic in derived status mode is calculated from its stories
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(default)]
+pub struct DerivedStatusRules {
+    /// status when all stories are closed
+    pub all_closed: Status,
+    /// status when all stories are resolved or closed
+    pub all_done: Status,
+    /// status when some stories are in progress or done, but not all are done
+    pub in_progress: Status,
+    /// status when all stories are still open
+    pub not_started: Status,
+    /// status of an epic without stories, `None` keeps the current status
+    pub no_stories: Option<Status>,
+}
+
+impl Default for DerivedStatusRules {
+    fn default() -> Self {
+        DerivedStatusRules {
+            all_closed: Status::Closed,
+            all_done: Status::Resolved,
+            in_progress: Status::InProgress,
+            not_started: Status::Open,
+            no_stories: None,
+        }
+    }
+}
+
+impl DerivedStatusRules {
+    /// Returns the status of an epic with the given story counts, `None` if it should not change.
+    pub fn derive(&self, counts: &StatusCounts) -> Option<Status> {
+        if counts.total() == 0 {
+            return self.no_stories.clone();
+        }
+
+        let status = if counts.closed == counts.total() {
+            &self.all_closed
+        } else if counts.is_complete() {
+            &self.all_done
+        } else if counts.open == counts.total() {
+            &self.not_started
+        } else {
+            &self.in_progress
+        };
+        Some(status.clone())
+    }
+}
+
+/// A version the stories are released with
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Version {
+    pub name: String,
+    pub release_date: Option<NaiveDate>,
+    pub released: bool,
+}
+
+impl Version {
+    pub fn new(name: String, release_date: Option<NaiveDate>) -> Self {
+        Version {
+            name,
+            release_date,
+            released: false,
+        }
+    }
+}
+
+/// A single entry of work logged against a story
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct WorkLog {
+    pub author: String,
+    pub started: NaiveDateTime,
+    pub duration: Minutes,
+    pub note: String,
+}
+
+/// A running timer which is turned into a work log when stopped
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Timer {
+    pub story_id: ID,
+    pub author: String,
+    pub started: NaiveDateTime,
+}
+
+impl Timer {
+    /// The time elapsed since the timer was started, at least one minute
+    pub fn elapsed(&self, now: NaiveDateTime) -> Minutes {
+        let minutes = (now - self.started).num_minutes().max(1);
+        Minutes::try_from(minutes).unwrap_or(Minutes::MAX)
+    }
+}
+
+/// A deleted epic or story, kept in the trash until it is restored or purged
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct DeletedItem {
+    pub id: ID,
+    pub deleted_at: NaiveDateTime,
+    pub item: TrashedItem,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum TrashedItem {
+    /// an epic together with the stories which were deleted with it
+    Epic {
+        epic: Epic,
+        stories: HashMap<ID, Story>,
+    },
+    Story {
+        epic_id: ID,
+        story: Story,
+    },
+}
+
+impl DeletedItem {
+    pub fn name(&self) -> &str {
+        match &self.item {
+            TrashedItem::Epic { epic, .. } => &epic.name,
+            TrashedItem::Story { story, .. } => &story.name,
+        }
+    }
+
+    /// The project key like `WEB-12` if the item has one, otherwise the plain id
+    pub fn display_id(&self) -> String {
+        let key = match &self.item {
+            TrashedItem::Epic { epic, .. } => epic.key.as_ref(),
+            TrashedItem::Story { story, .. } => story.key.as_ref(),
+        };
+        key.cloned().unwrap_or_else(|| self.id.to_string())
+    }
+
+    /// An item is expired if it has been in the trash for longer than the retention period
+    pub fn is_expired(&self, retention_days: u32, now: NaiveDateTime) -> bool {
+        (now - self.deleted_at).num_days() >= i64::from(retention_days)
+    }
+}
+
+/// An item is overdue if it is still unresolved after its due date
+fn is_overdue(status: &Status, due_date: Option<NaiveDate>, today: NaiveDate) -> bool {
+    status.is_unresolved() && due_date.is_some_and(|due_date| due_date < today)
+}
+
+/// The tables of the interactive app whose sorting and columns can be changed
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Table {
+    /// the epics on the home page
+    Epics,
+    /// the stories of an epic
+    Stories,
+}
+
+impl FromStr for Table {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "epics" => Ok(Table::Epics),
+            "stories" => Ok(Table::Stories),
+            _ => Err(anyhow::anyhow!(
+                "Invalid table '{input}', use epics or stories!"
+            )),
+        }
+    }
+}
+
+/// How the sorting of a table is changed
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SortChange {
+    /// sorts by the next key of `SORT_KEYS`
+    NextKey,
+    /// swaps ascending and descending
+    Reverse,
+    Set {
+        key: Column,
+        descending: bool,
+    },
+}
+
+/// A column of a table, also the keys the rows can be sorted by
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Column {
+    Id,
+    Name,
+    Status,
+    /// the rank in the backlog, the best rank of its stories for an epic
+    Priority,
+    /// when the item was changed last
+    Updated,
+    Due,
+    /// the stories done of an epic, can't be sorted by
+    Progress,
+}
+
+/// The columns in the order the rows are sorted by with the sort command
+pub const SORT_KEYS: [Column; 6] = [
+    Column::Id,
+    Column::Name,
+    Column::Status,
+    Column::Priority,
+    Column::Updated,
+    Column::Due,
+];
+
+impl Column {
+    /// The name of the column in the header and on the command line, e.g. `priority`
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Name => "name",
+            Column::Status => "status",
+            Column::Priority => "priority",
+            Column::Updated => "updated",
+            Column::Due => "due",
+            Column::Progress => "progress",
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        [
+            Column::Id,
+            Column::Name,
+            Column::Status,
+            Column::Priority,
+            Column::Updated,
+            Column::Due,
+            Column::Progress,
+        ]
+        .into_iter()
+        .find(|column| column.name() == input.trim().to_lowercase())
+        .ok_or_else(|| anyhow::anyhow!("Invalid column '{input}'!"))
+    }
+}
+
+/// A visible column of a table, the width is the default of the column if not set
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ColumnSetting {
+    pub column: Column,
+    #[serde(default)]
+    pub width: Option<usize>,
+}
+
+impl FromStr for ColumnSetting {
+    type Err = anyhow::Error;
+
+    /// Parses a column with an optional width, e.g. `name` or `name:30`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (column, width) = match input.split_once(':') {
+            Some((column, width)) => {
+                let width = width
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&width| width > 0)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid width '{width}'!"))?;
+                (column, Some(width))
+            }
+            None => (input, None),
+        };
+        Ok(ColumnSetting {
+            column: Column::from_str(column)?,
+            width,
+        })
+    }
+}
+
+/// How a table is sorted and which columns it shows
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct TableSettings {
+    #[serde(default = "default_sort")]
+    pub sort: Column,
+    #[serde(default)]
+    pub descending: bool,
+    /// the visible columns, the default columns of the table if empty
+    #[serde(default)]
+    pub columns: Vec<ColumnSetting>,
+}
+
+fn default_sort() -> Column {
+    Column::Id
+}
+
+impl TableSettings {
+    /// The default columns of the table, e.g. without the progress for stories
+    pub fn default_columns(table: Table) -> Vec<ColumnSetting> {
+        let columns: &[Column] = match table {
+            Table::Epics => &[
+                Column::Id,
+                Column::Name,
+                Column::Status,
+                Column::Progress,
+                Column::Due,
+            ],
+            Table::Stories => &[Column::Id, Column::Name, Column::Status, Column::Due],
+        };
+        columns
+            .iter()
+            .map(|&column| ColumnSetting {
+                column,
+                width: None,
+            })
+            .collect()
+    }
+
+    /// The settings of a table nobody changed: sorted by id with the default columns
+    pub fn new(table: Table) -> Self {
+        TableSettings {
+            sort: Column::Id,
+            descending: false,
+            columns: Self::default_columns(table),
+        }
+    }
+
+    pub fn change_sort(&mut self, change: SortChange) {
+        match change {
+            SortChange::NextKey => {
+                // the next key is sorted ascending
+                let index = SORT_KEYS.iter().position(|&key| key == self.sort);
+                self.sort = SORT_KEYS[index.map_or(0, |index| (index + 1) % SORT_KEYS.len())];
+                self.descending = false;
+            }
+            SortChange::Reverse => self.descending = !self.descending,
+            SortChange::Set { key, descending } => {
+                self.sort = key;
+                self.descending = descending;
+            }
+        }
+    }
+}
+
+/// Which epics and stories the tables show, e.g. only the unresolved ones
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct Filter {
+    /// hides resolved and closed items
+    #[serde(default)]
+    pub hide_done: bool,
+    /// only shows items with this status
+    #[serde(default)]
+    pub status: Option<Status>,
+    /// only shows stories assigned to the user, and epics with such stories
+    #[serde(default)]
+    pub only_mine: bool,
+}
+
+/// How the filter of the tables is changed
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum FilterChange {
+    ToggleHideDone,
+    /// shows the next status only, after the last status all statuses again
+    NextStatus,
+    SetStatus(Option<Status>),
+    ToggleOnlyMine,
+    Clear,
+}
+
+impl Filter {
+    pub fn change(&mut self, change: FilterChange) {
+        match change {
+            FilterChange::ToggleHideDone => self.hide_done = !self.hide_done,
+            FilterChange::NextStatus => {
+                self.status = match self.status {
+                    None => Some(Status::Open),
+                    Some(Status::Open) => Some(Status::InProgress),
+                    Some(Status::InProgress) => Some(Status::Resolved),
+                    Some(Status::Resolved) => Some(Status::Closed),
+                    Some(Status::Closed) => None,
+                }
+            }
+            FilterChange::SetStatus(status) => self.status = status,
+            FilterChange::ToggleOnlyMine => self.only_mine = !self.only_mine,
+            FilterChange::Clear => *self = Filter::default(),
+        }
+    }
+
+    /// Whether an item with the status is shown, `is_mine` is only asked for if needed
+    pub fn matches(&self, status: &Status, is_mine: impl FnOnce() -> bool) -> bool {
+        (!self.hide_done || status.is_unresolved())
+            && self.status.as_ref().is_none_or(|only| only == status)
+            && (!self.only_mine || is_mine())
+    }
+
+    /// What the filter hides, e.g. `unresolved, mine`, `None` if everything is shown
+    pub fn describe(&self) -> Option<String> {
+        let mut parts = vec![];
+        if self.hide_done {
+            parts.push("unresolved".to_owned());
+        }
+        if let Some(status) = &self.status {
+            parts.push(status.to_string().to_lowercase());
+        }
+        if self.only_mine {
+            parts.push("mine".to_owned());
+        }
+        match parts.is_empty() {
+            true => None,
+            false => Some(parts.join(", ")),
+        }
+    }
+}
+
+/// The number of recently visited epics and stories kept per user
+pub const RECENT_ITEMS: usize = 10;
+
+/// Preferences of a user of the interactive app
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct UserSettings {
+    /// name of the colour theme, `None` for the default theme
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// the recently visited epics and stories, the last visited first
+    #[serde(default)]
+    pub recent: Vec<ID>,
+    /// rows of long tables shown at once, `None` fits them into the terminal
+    #[serde(default)]
+    pub page_size: Option<usize>,
+    /// the sorting and columns of the tables the user changed
+    #[serde(default)]
+    pub tables: HashMap<Table, TableSettings>,
+    /// which epics and stories the tables show
+    #[serde(default)]
+    pub filter: Filter,
+}
+
+impl UserSettings {
+    /// How the table is sorted and which columns it shows
+    pub fn table(&self, table: Table) -> TableSettings {
+        let mut settings = self
+            .tables
+            .get(&table)
+            .cloned()
+            .unwrap_or_else(|| TableSettings::new(table));
+        if settings.columns.is_empty() {
+            settings.columns = TableSettings::default_columns(table);
+        }
+        settings
+    }
+
+    /// Changes the sorting or columns of a table
+    pub fn update_table(&mut self, table: Table, update: impl FnOnce(&mut TableSettings)) {
+        let mut settings = self.table(table);
+        update(&mut settings);
+        // the default columns aren't stored, so they follow changes of the defaults
+        if settings.columns == TableSettings::default_columns(table) {
+            settings.columns.clear();
+        }
+        self.tables.insert(table, settings);
+    }
+
+    /// Puts the item at the top of the recently visited items
+    pub fn visit(&mut self, id: ID) {
+        self.recent.retain(|&recent_id| recent_id != id);
+        self.recent.insert(0, id);
+        self.recent.truncate(RECENT_ITEMS);
+    }
+}
+
+/// This struct represents the entire db state which includes the last_item_id, epics, and stories
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct DBState {
     pub last_item_id: ID,
     pub epics: HashMap<ID, Epic>,
     pub stories: HashMap<ID, Story>,
+    /// story ids in backlog rank order, highest priority first
+    #[serde(default)]
+    pub backlog: Vec<ID>,
+    /// the timer which is currently running, if any
+    #[serde(default)]
+    pub active_timer: Option<Timer>,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    #[serde(default)]
+    pub versions: Vec<Version>,
+    /// how the status of epics in derived status mode is calculated
+    #[serde(default)]
+    pub derived_status_rules: DerivedStatusRules,
+    /// deleted epics and stories, oldest first
+    #[serde(default)]
+    pub trash: Vec<DeletedItem>,
+    /// number of days deleted items are kept by `purge`, `None` keeps them until restored
+    #[serde(default)]
+    pub trash_retention_days: Option<u32>,
+    /// the preferences of the users by user name
+    #[serde(default)]
+    pub user_settings: HashMap<String, UserSettings>,
+}
+
+impl DBState {
+    /// The preferences of the user, the defaults if nothing has been changed yet.
+    pub fn settings_of(&self, user: &str) -> UserSettings {
+        self.user_settings.get(user).cloned().unwrap_or_default()
+    }
+
+    /// Resolves an id as entered by the user, either the plain id or a project key like `WEB-12`.
+    pub fn resolve_id(&self, input: &str) -> Option<ID> {
+        if let Ok(id) = input.parse::<ID>() {
+            return Some(id);
+        }
+
+        let epic_keys = self.epics.iter().map(|(&id, epic)| (id, &epic.key));
+        let story_keys = self.stories.iter().map(|(&id, story)| (id, &story.key));
+        epic_keys
+            .chain(story_keys)
+            .find(|(_, key)| {
+                key.as_ref()
+                    .is_some_and(|key| key.eq_ignore_ascii_case(input))
+            })
+            .map(|(id, _)| id)
+    }
+
+    /// Returns the id as shown to the user, the project key like `WEB-12` if there is one.
+    pub fn display_id(&self, id: ID) -> String {
+        let key = match self.epics.get(&id) {
+            Some(epic) => epic.key.as_ref(),
+            None => self.stories.get(&id).and_then(|story| story.key.as_ref()),
+        };
+        key.cloned().unwrap_or_else(|| id.to_string())
+    }
+
+    /// Resolves an id of an item in the trash, either the plain id or a project key like `WEB-12`.
+    pub fn resolve_trash_id(&self, input: &str) -> Option<ID> {
+        self.trash
+            .iter()
+            .find(|item| {
+                item.id.to_string() == input || item.display_id().eq_ignore_ascii_case(input)
+            })
+            .map(|item| item.id)
+    }
+
+    /// Returns the number of stories per status of the given epic.
+    pub fn epic_status_counts(&self, epic_id: ID) -> StatusCounts {
+        let mut counts = StatusCounts::default();
+        let story_ids = self
+            .epics
+            .get(&epic_id)
+            .map_or(&[][..], |epic| &epic.stories);
+        for story in story_ids.iter().filter_map(|id| self.stories.get(id)) {
+            counts.add(&story.status);
+        }
+        counts
+    }
+
+    /// Recalculates the status of all epics in derived status mode from their stories.
+    pub fn refresh_derived_statuses(&mut self) {
+        let epic_ids = self
+            .epics
+            .iter()
+            .filter(|(_, epic)| epic.derived_status)
+            .map(|(&id, _)| id)
+            .collect::<Vec<_>>();
+
+        for epic_id in epic_ids {
+            let counts = self.epic_status_counts(epic_id);
+            if let Some(status) = self.derived_status_rules.derive(&counts) {
+                if let Some(epic) = self.epics.get_mut(&epic_id) {
+                    epic.status = status;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of resolved or closed stories and the total number of stories of a version.
+    pub fn version_progress(&self, version: &str) -> (usize, usize) {
+        let stories = self
+            .stories
+            .values()
+            .filter(|story| story.fix_version.as_deref() == Some(version));
+        let (done, total) = stories.fold((0, 0), |(done, total), story| {
+            (done + usize::from(!story.status.is_unresolved()), total + 1)
+        });
+        (done, total)
+    }
+
+    /// Allocates the next human-readable id of the given project, e.g. `WEB-13`.
+    pub fn next_project_key(&mut self, project_key: &str) -> Option<String> {
+        let project = self
+            .projects
+            .iter_mut()
+            .find(|project| project.key == project_key)?;
+        project.last_item_id += 1;
+        Some(format!("{}-{}", project.key, project.last_item_id))
+    }
+
+    /// Adds the epic with the next id, epics of a project get a key like `WEB-12`.
+    pub fn insert_epic(&mut self, mut epic: Epic) -> anyhow::Result<ID> {
+        if let Some(project) = &epic.project {
+            epic.key = Some(
+                self.next_project_key(project)
+                    .ok_or(anyhow::anyhow!("Project not found!"))?,
+            );
+        }
+
+        self.last_item_id += 1;
+        self.epics.insert(self.last_item_id, epic);
+        Ok(self.last_item_id)
+    }
+
+    /// Adds the story with the next id to the epic and at the bottom of the backlog,
+    /// stories get a key of the project of their epic.
+    pub fn insert_story(&mut self, mut story: Story, epic_id: ID) -> anyhow::Result<ID> {
+        let project = self
+            .epics
+            .get(&epic_id)
+            .ok_or(anyhow::anyhow!("Epic not found!"))?
+            .project
+            .clone();
+        if let Some(project) = project {
+            story.key = self.next_project_key(&project);
+        }
+
+        self.last_item_id += 1;
+        let story_id = self.last_item_id;
+        if let Some(epic) = self.epics.get_mut(&epic_id) {
+            epic.stories.push(story_id);
+        }
+        self.stories.insert(story_id, story);
+        self.backlog.push(story_id);
+        Ok(story_id)
+    }
+
+    /// Returns the id of the epic which contains the given story.
+    pub fn epic_id_of_story(&self, story_id: ID) -> Option<ID> {
+        self.epics
+            .iter()
+            .filter(|(_, epic)| epic.stories.contains(&story_id))
+            .map(|(&id, _)| id)
+            .min()
+    }
+
+    /// Returns all story ids in rank order. Stories which are not (yet) ranked,
+    /// e.g. from a database written before the backlog existed, are appended by id.
+    pub fn ranked_story_ids(&self) -> Vec<ID> {
+        let mut ranked: Vec<ID> = self
+            .backlog
+            .iter()
+            .filter(|id| self.stories.contains_key(id))
+            .unique()
+            .copied()
+            .collect();
+        let unranked = self
+            .stories
+            .keys()
+            .filter(|id| !ranked.contains(id))
+            .sorted()
+            .copied()
+            .collect::<Vec<ID>>();
+        ranked.extend(unranked);
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_overdue_should_only_flag_unresolved_items_past_their_due_date() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let mut story = Story::new("".to_owned(), "".to_owned());
+
+        assert_eq!(story.is_overdue(today), false);
+
+        story.due_date = NaiveDate::from_ymd_opt(2026, 10, 19);
+        assert_eq!(story.is_overdue(today), false);
+
+        story.due_date = NaiveDate::from_ymd_opt(2026, 10, 18);
+        assert_eq!(story.is_overdue(today), true);
+
+        story.status = Status::Resolved;
+        assert_eq!(story.is_overdue(today), false);
+    }
+
+    #[test]
+    fn resolve_id_should_accept_plain_ids_and_project_keys() {
+        let mut db_state = DBState::default();
+        db_state
+            .projects
+            .push(Project::new("web".to_owned(), "".to_owned()));
+
+        let mut epic = Epic::new("".to_owned(), "".to_owned());
+        epic.key = db_state.next_project_key("WEB");
+        db_state.epics.insert(4, epic);
+
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        story.key = db_state.next_project_key("WEB");
+        db_state.stories.insert(7, story);
+
+        assert_eq!(db_state.resolve_id("4"), Some(4));
+        assert_eq!(db_state.resolve_id("WEB-1"), Some(4));
+        assert_eq!(db_state.resolve_id("web-2"), Some(7));
+        assert_eq!(db_state.resolve_id("WEB-3"), None);
+        assert_eq!(db_state.resolve_id("junk"), None);
+
+        assert_eq!(db_state.display_id(4), "WEB-1".to_owned());
+        assert_eq!(db_state.display_id(7), "WEB-2".to_owned());
+        assert_eq!(db_state.display_id(9), "9".to_owned());
+        assert_eq!(db_state.next_project_key("APP"), None);
+    }
+
+    #[test]
+    fn epic_status_counts_should_count_the_stories_of_the_epic() {
+        let mut db_state = DBState::default();
+
+        let mut epic = Epic::new("".to_owned(), "".to_owned());
+        epic.stories = vec![2, 3, 4];
+        db_state.epics.insert(1, epic);
+
+        for (id, status) in [
+            (2, Status::Resolved),
+            (3, Status::Closed),
+            (4, Status::InProgress),
+            (5, Status::Open),
+        ] {
+            let mut story = Story::new("".to_owned(), "".to_owned());
+            story.status = status;
+            db_state.stories.insert(id, story);
+        }
+
+        let counts = db_state.epic_status_counts(1);
+        assert_eq!(
+            counts,
+            StatusCounts {
+                open: 0,
+                in_progress: 1,
+                resolved: 1,
+                closed: 1
+            }
+        );
+        assert_eq!(counts.done(), 2);
+        assert_eq!(counts.total(), 3);
+        assert_eq!(counts.is_complete(), false);
+
+        db_state.stories.get_mut(&4).unwrap().status = Status::Resolved;
+        assert_eq!(db_state.epic_status_counts(1).is_complete(), true);
+
+        // an epic without stories is never complete
+        assert_eq!(db_state.epic_status_counts(999).is_complete(), false);
+    }
+
+    #[test]
+    fn status_should_parse_from_str() {
+        assert_eq!("open".parse::<Status>().unwrap(), Status::Open);
+        assert_eq!("In Progress".parse::<Status>().unwrap(), Status::InProgress);
+        assert_eq!("in-progress".parse::<Status>().unwrap(), Status::InProgress);
+        assert_eq!("RESOLVED".parse::<Status>().unwrap(), Status::Resolved);
+        assert_eq!("closed".parse::<Status>().unwrap(), Status::Closed);
+        assert_eq!("done".parse::<Status>().is_err(), true);
+    }
+
+    #[test]
+    fn story_type_should_parse_from_str() {
+        assert_eq!("story".parse::<StoryType>().unwrap(), StoryType::Story);
+        assert_eq!(" BUG ".parse::<StoryType>().unwrap(), StoryType::Bug);
+        assert_eq!("Task".parse::<StoryType>().unwrap(), StoryType::Task);
+        assert_eq!("epic".parse::<StoryType>().is_err(), true);
+    }
+
+    #[test]
+    fn derived_status_rules_should_derive_the_epic_status() {
+        let rules = DerivedStatusRules::default();
+        let counts = |open, in_progress, resolved, closed| StatusCounts {
+            open,
+            in_progress,
+            resolved,
+            closed,
+        };
+
+        assert_eq!(rules.derive(&counts(0, 0, 0, 0)), None);
+        assert_eq!(rules.derive(&counts(2, 0, 0, 0)), Some(Status::Open));
+        assert_eq!(rules.derive(&counts(2, 1, 0, 0)), Some(Status::InProgress));
+        assert_eq!(rules.derive(&counts(2, 0, 1, 0)), Some(Status::InProgress));
+        assert_eq!(rules.derive(&counts(0, 0, 1, 1)), Some(Status::Resolved));
+        assert_eq!(rules.derive(&counts(0, 0, 0, 2)), Some(Status::Closed));
+
+        let rules = DerivedStatusRules {
+            all_closed: Status::Resolved,
+            no_stories: Some(Status::Open),
+            ..Default::default()
+        };
+        assert_eq!(rules.derive(&counts(0, 0, 0, 0)), Some(Status::Open));
+        assert_eq!(rules.derive(&counts(0, 0, 0, 2)), Some(Status::Resolved));
+    }
+
+    #[test]
+    fn refresh_derived_statuses_should_only_update_epics_in_derived_mode() {
+        let mut db_state = DBState::default();
+
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        story.status = Status::InProgress;
+        db_state.stories.insert(3, story);
+
+        let mut manual = Epic::new("".to_owned(), "".to_owned());
+        manual.stories = vec![3];
+        db_state.epics.insert(1, manual.clone());
+
+        let mut derived = manual;
+        derived.derived_status = true;
+        db_state.epics.insert(2, derived);
+
+        db_state.refresh_derived_statuses();
+
+        assert_eq!(db_state.epics.get(&1).unwrap().status, Status::Open);
+        assert_eq!(db_state.epics.get(&2).unwrap().status, Status::InProgress);
+    }
+
+    #[test]
+    fn deleted_item_should_expire_after_retention() {
+        let deleted_at = NaiveDate::from_ymd_opt(2026, 10, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let mut story = Story::new("story".to_owned(), "".to_owned());
+        story.key = Some("WEB-3".to_owned());
+        let item = DeletedItem {
+            id: 3,
+            deleted_at,
+            item: TrashedItem::Story { epic_id: 1, story },
+        };
+
+        assert_eq!(item.name(), "story");
+        assert_eq!(item.display_id(), "WEB-3".to_owned());
+        assert_eq!(
+            item.is_expired(30, deleted_at + chrono::Duration::days(29)),
+            false
+        );
+        assert_eq!(
+            item.is_expired(30, deleted_at + chrono::Duration::days(30)),
+            true
+        );
+
+        let db_state = DBState {
+            trash: vec![item],
+            ..Default::default()
+        };
+        assert_eq!(db_state.resolve_trash_id("web-3"), Some(3));
+        assert_eq!(db_state.resolve_trash_id("3"), Some(3));
+        assert_eq!(db_state.resolve_trash_id("4"), None);
+    }
+
+    #[test]
+    fn visit_should_keep_the_last_visited_items_first() {
+        let mut settings = UserSettings::default();
+        for id in 1..=RECENT_ITEMS as ID + 2 {
+            settings.visit(id);
+        }
+        settings.visit(5);
+
+        assert_eq!(settings.recent.len(), RECENT_ITEMS);
+        assert_eq!(settings.recent[..3], [5, 12, 11]);
+        assert_eq!(settings.recent.contains(&2), false);
+    }
+
+    #[test]
+    fn change_sort_should_cycle_through_the_sort_keys() {
+        let mut settings = UserSettings::default();
+        settings.update_table(Table::Stories, |table| {
+            table.change_sort(SortChange::Reverse);
+            table.change_sort(SortChange::NextKey);
+        });
+        assert_eq!(settings.table(Table::Stories).sort, Column::Name);
+        assert_eq!(settings.table(Table::Stories).descending, false);
+        // the columns nobody changed follow the defaults
+        assert_eq!(settings.tables[&Table::Stories].columns.is_empty(), true);
+
+        settings.update_table(Table::Stories, |table| {
+            table.change_sort(SortChange::Set {
+                key: Column::Due,
+                descending: true,
+            });
+            table.change_sort(SortChange::NextKey);
+        });
+        assert_eq!(settings.table(Table::Stories).sort, Column::Id);
+        assert_eq!(
+            settings.table(Table::Epics),
+            TableSettings::new(Table::Epics)
+        );
+    }
+
+    #[test]
+    fn filter_should_hide_items() {
+        let mut filter = Filter::default();
+        assert_eq!(filter.matches(&Status::Closed, || false), true);
+        assert_eq!(filter.describe(), None);
+
+        filter.change(FilterChange::ToggleHideDone);
+        assert_eq!(filter.matches(&Status::Closed, || false), false);
+        assert_eq!(filter.matches(&Status::InProgress, || false), true);
+
+        filter.change(FilterChange::NextStatus);
+        filter.change(FilterChange::NextStatus);
+        assert_eq!(filter.matches(&Status::Open, || false), false);
+        assert_eq!(filter.matches(&Status::InProgress, || false), true);
+
+        filter.change(FilterChange::ToggleOnlyMine);
+        assert_eq!(filter.matches(&Status::InProgress, || false), false);
+        assert_eq!(filter.matches(&Status::InProgress, || true), true);
+        assert_eq!(
+            filter.describe(),
+            Some("unresolved, in progress, mine".to_owned())
+        );
+
+        filter.change(FilterChange::Clear);
+        assert_eq!(filter, Filter::default());
+    }
+
+    #[test]
+    fn column_setting_should_parse_widths() {
+        assert_eq!(
+            ColumnSetting::from_str("Name:30").unwrap(),
+            ColumnSetting {
+                column: Column::Name,
+                width: Some(30)
+            }
+        );
+        assert_eq!(
+            ColumnSetting::from_str("updated").unwrap(),
+            ColumnSetting {
+                column: Column::Updated,
+                width: None
+            }
+        );
+        assert_eq!(ColumnSetting::from_str("name:").is_err(), true);
+        assert_eq!(ColumnSetting::from_str("owner").is_err(), true);
+    }
 }
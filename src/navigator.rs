@@ -1,68 +1,268 @@
-///
-///
 use anyhow::{anyhow, Context, Ok, Result}; // ,
 use std::rc::Rc;
 
 use crate::{
+    dates::now,
     db::JiraDatabase,
-    models::Action,
-    ui::{EpicDetail, HomePage, Page, Prompts, StoryDetail},
+    io_utils::current_user_name,
+    models::{Action, ID},
+    ui::{
+        ArchivePage, BacklogPage, EpicDetail, HelpPage, HomePage, Page, PageKind, Pagination,
+        ProjectsPage, Prompts, RecentPage, ReleasePage, ReleasesPage, StoryDetail, TrashPage,
+        UpcomingPage,
+    },
 };
 
 pub struct Navigator {
     pages: Vec<Box<dyn Page>>,
+    /// the pages left with previous, the next page last
+    forward: Vec<Box<dyn Page>>,
     prompts: Prompts,
     db: Rc<JiraDatabase>,
 }
 
 impl Navigator {
     pub fn new(db: Rc<JiraDatabase>) -> Self {
-        // create the default Navigator with the home-page as intial page
-        let page: Box<dyn Page> = Box::new(HomePage { db: db.clone() });
+        // create the default Navigator with the home-page as intial page,
+        // or the project picker once projects have been created
+        let has_projects = db
+            .read_db()
+            .is_ok_and(|db_state| !db_state.projects.is_empty());
+        let page: Box<dyn Page> = if has_projects {
+            Box::new(ProjectsPage { db: db.clone() })
+        } else {
+            Box::new(HomePage {
+                db: db.clone(),
+                project: None,
+                pagination: Pagination::default(),
+            })
+        };
         let pages = vec![page];
 
         Self {
             pages,
+            forward: vec![],
             prompts: Prompts::new(),
             db,
         }
     }
 
+    /// A navigator which asks for input with the given prompts, e.g. the modal prompts of the TUI
+    pub fn with_prompts(db: Rc<JiraDatabase>, prompts: Prompts) -> Self {
+        let mut navigator = Self::new(db);
+        navigator.prompts = prompts;
+        navigator
+    }
+
+    pub fn get_page_count(&self) -> usize {
+        self.pages.len()
+    }
+
     #[allow(clippy::borrowed_box)]
     pub fn get_current_page(&self) -> Option<&Box<dyn Page>> {
         // this should always return the last element in the pages vector
         self.pages.last()
     }
 
+    /// The names of the pages on the way to the current page, e.g. `Home > Epic 6 Bugfixes`
+    pub fn breadcrumbs(&self) -> Vec<String> {
+        self.pages.iter().map(|page| page.breadcrumb()).collect()
+    }
+
+    /// Shows a new page, the pages left with previous can't be gone to with forward anymore
+    fn push(&mut self, page: Box<dyn Page>) {
+        self.forward.clear();
+        self.pages.push(page);
+    }
+
+    /// Puts the epic or story at the top of the recently visited items of the user
+    fn visit(&self, id: ID) -> Result<()> {
+        self.db
+            .update_user_settings(&current_user_name(), |settings| settings.visit(id))
+    }
+
+    /// Whether the current page shows the epic or story
+    fn is_current_page(&self, kind: PageKind, id: ID) -> bool {
+        self.get_current_page()
+            .is_some_and(|page| page.kind() == kind && page.item_id() == Some(id))
+    }
+
     pub fn handle_action(&mut self, action: Action) -> Result<()> {
         match action {
+            Action::NavigateToProjects => {
+                // create a new ProjectsPage instance and add it to the pages vector
+                let projects = Box::new(ProjectsPage {
+                    db: self.db.clone(),
+                });
+                self.push(projects);
+            }
+            Action::NavigateToProject { project_key } => {
+                // create a new HomePage instance for the project and add it to the pages vector
+                let home_page = Box::new(HomePage {
+                    db: self.db.clone(),
+                    project: project_key,
+                    pagination: Pagination::default(),
+                });
+                self.push(home_page);
+            }
             Action::NavigateToEpicDetail { epic_id } => {
+                self.visit(epic_id)?;
                 // create a new EpicDetail instance and add it to the pages vector
                 let epic_detail = Box::new(EpicDetail {
                     epic_id,
                     db: self.db.clone(),
+                    pagination: Pagination::default(),
                 });
-                self.pages.push(epic_detail);
+                self.push(epic_detail);
             }
             Action::NavigateToStoryDetail { epic_id, story_id } => {
+                self.visit(story_id)?;
                 // create a new StoryDetail instance and add it to the pages vector
                 let story_detail = Box::new(StoryDetail {
                     epic_id,
                     story_id,
                     db: self.db.clone(),
                 });
-                self.pages.push(story_detail);
+                self.push(story_detail);
+            }
+            Action::NavigateToBacklog => {
+                // create a new BacklogPage instance and add it to the pages vector
+                let backlog = Box::new(BacklogPage {
+                    db: self.db.clone(),
+                });
+                self.push(backlog);
+            }
+            Action::NavigateToUpcoming => {
+                // create a new UpcomingPage instance and add it to the pages vector
+                let upcoming = Box::new(UpcomingPage {
+                    db: self.db.clone(),
+                });
+                self.push(upcoming);
+            }
+            Action::NavigateToReleases => {
+                // create a new ReleasesPage instance and add it to the pages vector
+                let releases = Box::new(ReleasesPage {
+                    db: self.db.clone(),
+                });
+                self.push(releases);
+            }
+            Action::NavigateToRelease { version } => {
+                // create a new ReleasePage instance and add it to the pages vector
+                let release = Box::new(ReleasePage {
+                    version,
+                    db: self.db.clone(),
+                });
+                self.push(release);
+            }
+            Action::NavigateToTrash => {
+                // create a new TrashPage instance and add it to the pages vector
+                let trash = Box::new(TrashPage {
+                    db: self.db.clone(),
+                });
+                self.push(trash);
+            }
+            Action::NavigateToArchive => {
+                // create a new ArchivePage instance and add it to the pages vector
+                let archive = Box::new(ArchivePage {
+                    db: self.db.clone(),
+                });
+                self.push(archive);
+            }
+            Action::NavigateToHelp => {
+                // create a new HelpPage instance for the current page and add it to the pages vector
+                let page = self
+                    .get_current_page()
+                    .ok_or_else(|| anyhow!("No page to show the help for!"))?
+                    .kind();
+                self.push(Box::new(HelpPage { page }));
+            }
+            Action::GoTo { id } => {
+                // open the epic or story with the id or key typed on any page, a story is opened
+                // below its epic so that going back shows the epic first
+                let db_state = self.db.read_db()?;
+                let item_id = db_state
+                    .resolve_id(&id)
+                    .ok_or_else(|| anyhow!("Unknown id '{id}'!"))?;
+                let (epic_id, story_id) = if db_state.epics.contains_key(&item_id) {
+                    (item_id, None)
+                } else {
+                    let epic_id = db_state
+                        .epic_id_of_story(item_id)
+                        .ok_or_else(|| anyhow!("No epic or story with id '{id}'!"))?;
+                    (epic_id, Some(item_id))
+                };
+
+                if !self.is_current_page(PageKind::EpicDetail, epic_id) {
+                    self.handle_action(Action::NavigateToEpicDetail { epic_id })?;
+                }
+                if let Some(story_id) = story_id {
+                    self.handle_action(Action::NavigateToStoryDetail { epic_id, story_id })?;
+                }
             }
             Action::NavigateToPreviousPage => {
-                // remove the last page from the pages vector
-                self.pages.pop();
+                // remove the last page from the pages vector, forward shows it again
+                if let Some(page) = self.pages.pop() {
+                    self.forward.push(page);
+                }
             }
-            Action::CreateEpic => {
-                // prompt the user to create a new epic and persist it in the database
-                let epic = (self.prompts.create_epic)();
+            Action::NavigateToHome => {
+                // go back to the first page, forward shows the pages one by one again
+                while self.pages.len() > 1 {
+                    self.handle_action(Action::NavigateToPreviousPage)?;
+                }
+            }
+            Action::NavigateForward => {
+                // show the page left with previous again
+                if let Some(page) = self.forward.pop() {
+                    self.pages.push(page);
+                }
+            }
+            Action::ScrollList { pages } => {
+                // show other rows of the table of the current page
+                if let Some(page) = self.get_current_page() {
+                    page.scroll(pages);
+                }
+            }
+            Action::SortTable { table, change } => {
+                // the sorting is remembered for the user, like the theme
                 self.db
-                    .create_epic(epic)
-                    .map_err(|e| anyhow!("Can't create the Epic! {e}"))?;
+                    .update_user_settings(&current_user_name(), |settings| {
+                        settings.update_table(table, |table| table.change_sort(change))
+                    })
+                    .map_err(|e| anyhow!("Can't sort the table! {e}"))?;
+            }
+            Action::ChangeFilter { change } => {
+                // the filter is remembered for the user, like the sorting
+                self.db
+                    .update_user_settings(&current_user_name(), |settings| {
+                        settings.filter.change(change)
+                    })
+                    .map_err(|e| anyhow!("Can't change the filter! {e}"))?;
+            }
+            Action::NavigateToRecent => {
+                // create a new RecentPage instance for the user and add it to the pages vector
+                let recent = Box::new(RecentPage {
+                    db: self.db.clone(),
+                    user: current_user_name(),
+                });
+                self.push(recent);
+            }
+            Action::CreateProject => {
+                // prompt the user to create a new project and persist it in the database
+                if let Some(project) = (self.prompts.create_project)() {
+                    self.db
+                        .create_project(project)
+                        .map_err(|e| anyhow!("Can't create the Project! {e}"))?;
+                }
+            }
+            Action::CreateEpic { project_key } => {
+                // prompt the user to create a new epic and persist it in the database
+                if let Some(mut epic) = (self.prompts.create_epic)() {
+                    epic.project = project_key;
+                    self.db
+                        .create_epic(epic)
+                        .map_err(|e| anyhow!("Can't create the Epic! {e}"))?;
+                }
             }
             Action::UpdateEpicStatus { epic_id } => {
                 // prompt the user to update status and persist it in the database
@@ -72,22 +272,56 @@ impl Navigator {
                         .with_context(|| format!("Can't update the Epic {} status!", epic_id))?;
                 }
             }
+            Action::UpdateEpicDueDate { epic_id } => {
+                // prompt the user to update the due date and persist it in the database
+                if let Some(due_date) = (self.prompts.update_due_date)() {
+                    self.db
+                        .update_epic_due_date(epic_id, due_date)
+                        .with_context(|| format!("Can't update the Epic {} due date!", epic_id))?;
+                }
+            }
+            Action::ToggleEpicDerivedStatus { epic_id } => {
+                let derived_status = self
+                    .db
+                    .read_db()?
+                    .epics
+                    .get(&epic_id)
+                    .ok_or_else(|| anyhow!("Epic {} not found!", epic_id))?
+                    .derived_status;
+                self.db
+                    .set_epic_derived_status(epic_id, !derived_status)
+                    .with_context(|| format!("Can't change the Epic {} status mode!", epic_id))?;
+            }
+            Action::ToggleEpicArchived { epic_id } => {
+                let archived = self
+                    .db
+                    .read_db()?
+                    .epics
+                    .get(&epic_id)
+                    .ok_or_else(|| anyhow!("Epic {} not found!", epic_id))?
+                    .archived;
+                self.db
+                    .set_epic_archived(epic_id, !archived)
+                    .map_err(|e| anyhow!("Can't archive Epic! {e}"))?;
+            }
             Action::DeleteEpic { epic_id } => {
                 // prompt the user to delete the epic and persist it in the database
                 if (self.prompts.delete_epic)() {
                     self.db
                         .delete_epic(epic_id)
                         .map_err(|e| anyhow!("Can't delete Epic! {e}"))?;
-                    // navigate to previous screen
+                    // navigate to previous screen, the pages of the epic can't be shown anymore
                     self.pages.pop();
+                    self.forward.clear();
                 }
             }
             Action::CreateStory { epic_id } => {
                 // prompt the user to create a new story and persist it in the database
-                let story = (self.prompts.create_story)();
-                self.db
-                    .create_story(story, epic_id)
-                    .map_err(|e| anyhow!("Can't create story. {e}"))?;
+                if let Some(story) = (self.prompts.create_story)() {
+                    self.db
+                        .create_story(story, epic_id)
+                        .map_err(|e| anyhow!("Can't create story. {e}"))?;
+                }
             }
             Action::UpdateStoryStatus { story_id } => {
                 // prompt the user to update status and persist it in the database
@@ -97,15 +331,150 @@ impl Navigator {
                         .map_err(|e| anyhow!("Can't update the Story status! {e}"))?;
                 }
             }
+            Action::UpdateStoryType { story_id } => {
+                // prompt the user to update the type and persist it in the database
+                if let Some(story_type) = (self.prompts.update_story_type)() {
+                    self.db
+                        .update_story_type(story_id, story_type)
+                        .map_err(|e| anyhow!("Can't update the Story type! {e}"))?;
+                }
+            }
+            Action::ToggleStoryAssignee { story_id } => {
+                // stories are only assigned to the current user, other users assign themselves
+                let user = current_user_name();
+                let assignee = self.db.read_db()?.stories.get(&story_id).and_then(|story| {
+                    match story.assignee.as_deref() == Some(user.as_str()) {
+                        true => None,
+                        false => Some(user),
+                    }
+                });
+                self.db
+                    .update_story_assignee(story_id, assignee)
+                    .map_err(|e| anyhow!("Can't assign the Story! {e}"))?;
+            }
+            Action::UpdateStoryFixVersion { story_id } => {
+                // prompt the user to update the fix version and persist it in the database
+                if let Some(fix_version) = (self.prompts.update_fix_version)() {
+                    self.db
+                        .update_story_fix_version(story_id, fix_version)
+                        .map_err(|e| anyhow!("Can't update the Story fix version! {e}"))?;
+                }
+            }
+            Action::UpdateStoryDueDate { story_id } => {
+                // prompt the user to update the due date and persist it in the database
+                if let Some(due_date) = (self.prompts.update_due_date)() {
+                    self.db
+                        .update_story_due_date(story_id, due_date)
+                        .map_err(|e| anyhow!("Can't update the Story due date! {e}"))?;
+                }
+            }
+            Action::UpdateStoryEstimate { story_id } => {
+                // prompt the user to update the estimates and persist them in the database
+                if let Some((original, remaining)) = (self.prompts.update_estimate)() {
+                    self.db
+                        .update_story_estimate(story_id, original, remaining)
+                        .map_err(|e| anyhow!("Can't update the Story estimate! {e}"))?;
+                }
+            }
+            Action::LogWork { story_id } => {
+                // prompt the user for the work done and persist it in the database
+                if let Some(work_log) = (self.prompts.log_work)() {
+                    self.db
+                        .log_work(story_id, work_log)
+                        .map_err(|e| anyhow!("Can't log work! {e}"))?;
+                }
+            }
+            Action::ToggleTimer { story_id } => {
+                // stop the running timer, start a new one unless it was running for this story
+                let db_state = self.db.read_db()?;
+                let running_story_id = db_state.active_timer.map(|timer| timer.story_id);
+
+                if running_story_id.is_some() {
+                    self.db
+                        .stop_timer(now(), String::new())
+                        .map_err(|e| anyhow!("Can't stop the timer! {e}"))?;
+                }
+                if running_story_id != Some(story_id) {
+                    self.db
+                        .start_timer(story_id, current_user_name(), now())
+                        .map_err(|e| anyhow!("Can't start the timer! {e}"))?;
+                }
+            }
             Action::DeleteStory { epic_id, story_id } => {
                 // prompt the user to delete the story and persist it in the database
                 if (self.prompts.delete_story)() {
                     self.db
                         .delete_story(epic_id, story_id)
                         .map_err(|e| anyhow!("Can't delete story! {e}"))?;
-                    // navigate to previous screen
+                    // navigate to previous screen, the pages of the story can't be shown anymore
+                    self.pages.pop();
+                    self.forward.clear();
+                }
+            }
+            Action::RestoreItem { id } => {
+                self.db
+                    .restore_item(id)
+                    .map_err(|e| anyhow!("Can't restore item {id}! {e}"))?;
+            }
+            Action::MoveStoryInBacklog {
+                story_id,
+                direction,
+            } => {
+                // re-rank the story and persist it in the database
+                self.db
+                    .move_story_in_backlog(story_id, direction)
+                    .with_context(|| format!("Can't move Story {} in the backlog!", story_id))?;
+            }
+            Action::CreateVersion => {
+                // prompt the user to create a new version and persist it in the database
+                if let Some(version) = (self.prompts.create_version)() {
+                    self.db
+                        .create_version(version)
+                        .map_err(|e| anyhow!("Can't create the Version! {e}"))?;
+                }
+            }
+            Action::ReleaseVersion { version } => {
+                // mark the version as released and persist it in the database
+                self.db
+                    .release_version(&version)
+                    .with_context(|| format!("Can't release Version {}!", version))?;
+            }
+            Action::UpdateStatus { id, status } => {
+                // change the status of the epic or story without a prompt
+                if self.db.read_db()?.epics.contains_key(&id) {
+                    self.db
+                        .update_epic_status(id, status)
+                        .with_context(|| format!("Can't update the Epic {} status!", id))?;
+                } else {
+                    self.db
+                        .update_story_status(id, status)
+                        .map_err(|e| anyhow!("Can't update the Story status! {e}"))?;
+                }
+            }
+            Action::MoveStoryToEpic { story_id, epic_id } => {
+                // move the story and show it below its new epic instead of the old one
+                let db_state = self.db.read_db()?;
+                let old_epic_id = db_state.epic_id_of_story(story_id);
+                let epic_id = db_state
+                    .resolve_id(&epic_id)
+                    .ok_or_else(|| anyhow!("Unknown epic '{epic_id}'!"))?;
+                self.db
+                    .move_story_to_epic(story_id, epic_id)
+                    .with_context(|| {
+                        format!("Can't move Story {} to Epic {}!", story_id, epic_id)
+                    })?;
+
+                if self.is_current_page(PageKind::StoryDetail, story_id) {
                     self.pages.pop();
                 }
+                if let Some(old_epic_id) = old_epic_id {
+                    if self.is_current_page(PageKind::EpicDetail, old_epic_id) {
+                        self.pages.pop();
+                    }
+                }
+                self.handle_action(Action::GoTo {
+                    id: story_id.to_string(),
+                })?;
             }
             Action::Exit => {
                 // remove all pages from the pages vector
@@ -122,10 +491,6 @@ mod tests {
     impl Navigator {
         // Private functions used for testing
 
-        fn get_page_count(&self) -> usize {
-            self.pages.len()
-        }
-
         fn set_prompts(&mut self, prompts: Prompts) {
             self.prompts = prompts;
         }
@@ -134,8 +499,13 @@ mod tests {
     use super::*;
     use crate::{
         db::test_utils::MockDB,
-        models::{Epic, Status, Story},
+        models::{
+            Column, Epic, FilterChange, Project, RankMove, SortChange, Status, Story, StoryType,
+            Table, TableSettings, Version, WorkLog,
+        },
+        ui::PageKind,
     };
+    use chrono::NaiveDate;
 
     #[test]
     fn should_start_on_home_page() {
@@ -152,6 +522,62 @@ mod tests {
         assert_eq!(home_page.is_some(), true);
     }
 
+    #[test]
+    fn should_start_on_projects_page_if_projects_exist() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        db.create_project(Project::new("WEB".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut nav = Navigator::new(db);
+
+        assert_eq!(nav.get_page_count(), 1);
+
+        let current_page = nav.get_current_page().unwrap();
+        let projects_page = current_page.as_any().downcast_ref::<ProjectsPage>();
+        assert_eq!(projects_page.is_some(), true);
+
+        nav.handle_action(Action::NavigateToProject {
+            project_key: Some("WEB".to_owned()),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+
+        let current_page = nav.get_current_page().unwrap();
+        let home_page = current_page.as_any().downcast_ref::<HomePage>().unwrap();
+        assert_eq!(home_page.project, Some("WEB".to_owned()));
+    }
+
+    #[test]
+    fn handle_action_should_handle_create_project_and_epic() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.create_project =
+            Box::new(|| Some(Project::new("WEB".to_owned(), "Website".to_owned())));
+        prompts.create_epic =
+            Box::new(|| Some(Epic::new("name".to_owned(), "description".to_owned())));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateProject).unwrap();
+        nav.handle_action(Action::CreateEpic {
+            project_key: Some("WEB".to_owned()),
+        })
+        .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.projects.len(), 1);
+
+        let epic = db_state.epics.into_iter().next().unwrap().1;
+        assert_eq!(epic.project, Some("WEB".to_owned()));
+        assert_eq!(epic.key, Some("WEB-1".to_owned()));
+    }
+
     #[test]
     fn handle_action_should_navigate_pages() {
         let db = Rc::new(JiraDatabase {
@@ -229,11 +655,13 @@ mod tests {
         let mut nav = Navigator::new(Rc::clone(&db));
 
         let mut prompts = Prompts::new();
-        prompts.create_epic = Box::new(|| Epic::new("name".to_owned(), "description".to_owned()));
+        prompts.create_epic =
+            Box::new(|| Some(Epic::new("name".to_owned(), "description".to_owned())));
 
         nav.set_prompts(prompts);
 
-        nav.handle_action(Action::CreateEpic).unwrap();
+        nav.handle_action(Action::CreateEpic { project_key: None })
+            .unwrap();
 
         let db_state = db.read_db().unwrap();
         assert_eq!(db_state.epics.len(), 1);
@@ -269,6 +697,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn handle_action_should_handle_toggle_epic_derived_status() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::ToggleEpicDerivedStatus { epic_id })
+            .unwrap();
+        let epic = db.read_db().unwrap().epics.get(&epic_id).unwrap().clone();
+        assert_eq!(epic.derived_status, true);
+        assert_eq!(epic.status, Status::InProgress);
+
+        nav.handle_action(Action::ToggleEpicDerivedStatus { epic_id })
+            .unwrap();
+        let epic = db.read_db().unwrap().epics.get(&epic_id).unwrap().clone();
+        assert_eq!(epic.derived_status, false);
+
+        assert_eq!(
+            nav.handle_action(Action::ToggleEpicDerivedStatus { epic_id: 999 })
+                .is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_archive_and_restore() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        db.update_epic_status(epic_id, Status::Closed).unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::ToggleEpicArchived { epic_id })
+            .unwrap();
+        assert_eq!(db.read_db().unwrap().epics[&epic_id].archived, true);
+
+        db.delete_epic(epic_id).unwrap();
+        nav.handle_action(Action::RestoreItem { id: epic_id })
+            .unwrap();
+        assert_eq!(db.read_db().unwrap().epics.contains_key(&epic_id), true);
+        assert_eq!(
+            nav.handle_action(Action::RestoreItem { id: epic_id })
+                .is_err(),
+            true
+        );
+
+        nav.handle_action(Action::NavigateToTrash).unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+        nav.handle_action(Action::NavigateToArchive).unwrap();
+        assert_eq!(nav.get_page_count(), 3);
+    }
+
     #[test]
     fn handle_action_should_handle_delete_epic() {
         let db = Rc::new(JiraDatabase {
@@ -303,7 +797,8 @@ mod tests {
         let mut nav = Navigator::new(Rc::clone(&db));
 
         let mut prompts = Prompts::new();
-        prompts.create_story = Box::new(|| Story::new("name".to_owned(), "description".to_owned()));
+        prompts.create_story =
+            Box::new(|| Some(Story::new("name".to_owned(), "description".to_owned())));
 
         nav.set_prompts(prompts);
 
@@ -317,6 +812,25 @@ mod tests {
         assert_eq!(story.description, "description".to_owned());
     }
 
+    #[test]
+    fn handle_action_should_not_create_a_cancelled_story() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        let mut prompts = Prompts::new();
+        prompts.create_story = Box::new(|| None);
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateStory { epic_id }).unwrap();
+
+        assert_eq!(db.read_db().unwrap().stories.is_empty(), true);
+    }
+
     #[test]
     fn handle_action_should_handle_update_story() {
         let db = Rc::new(JiraDatabase {
@@ -371,4 +885,569 @@ mod tests {
         let db_state = db.read_db().unwrap();
         assert_eq!(db_state.stories.len(), 0);
     }
+
+    #[test]
+    fn handle_action_should_handle_update_story_due_date() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_due_date = Box::new(|| Some(NaiveDate::from_ymd_opt(2026, 11, 1)));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateStoryDueDate { story_id })
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(
+            db_state.stories.get(&story_id).unwrap().due_date,
+            NaiveDate::from_ymd_opt(2026, 11, 1)
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_log_work() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_estimate = Box::new(|| Some((Some(120), Some(120))));
+        prompts.log_work = Box::new(|| {
+            Some(WorkLog {
+                author: "alice".to_owned(),
+                started: NaiveDate::from_ymd_opt(2026, 10, 19)
+                    .unwrap()
+                    .and_hms_opt(9, 0, 0)
+                    .unwrap(),
+                duration: 30,
+                note: "".to_owned(),
+            })
+        });
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateStoryEstimate { story_id })
+            .unwrap();
+        nav.handle_action(Action::LogWork { story_id }).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        let story = db_state.stories.get(&story_id).unwrap();
+        assert_eq!(story.time_spent(), 30);
+        assert_eq!(story.original_estimate, Some(120));
+        assert_eq!(story.remaining_estimate, Some(90));
+    }
+
+    #[test]
+    fn handle_action_should_handle_toggle_timer() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id_1 = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let story_id_2 = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        // start
+        nav.handle_action(Action::ToggleTimer {
+            story_id: story_id_1,
+        })
+        .unwrap();
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.active_timer.unwrap().story_id, story_id_1);
+
+        // switch to another story
+        nav.handle_action(Action::ToggleTimer {
+            story_id: story_id_2,
+        })
+        .unwrap();
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.active_timer.unwrap().story_id, story_id_2);
+        assert_eq!(
+            db_state.stories.get(&story_id_1).unwrap().work_logs.len(),
+            1
+        );
+
+        // stop
+        nav.handle_action(Action::ToggleTimer {
+            story_id: story_id_2,
+        })
+        .unwrap();
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.active_timer, None);
+        assert_eq!(
+            db_state.stories.get(&story_id_2).unwrap().work_logs.len(),
+            1
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_versions() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.create_version = Box::new(|| Some(Version::new("1.0".to_owned(), None)));
+        prompts.update_fix_version = Box::new(|| Some(Some("1.0".to_owned())));
+        prompts.update_story_type = Box::new(|| Some(StoryType::Bug));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateVersion).unwrap();
+        nav.handle_action(Action::UpdateStoryFixVersion { story_id })
+            .unwrap();
+        nav.handle_action(Action::UpdateStoryType { story_id })
+            .unwrap();
+        nav.handle_action(Action::ReleaseVersion {
+            version: "1.0".to_owned(),
+        })
+        .unwrap();
+
+        nav.handle_action(Action::NavigateToRelease {
+            version: "1.0".to_owned(),
+        })
+        .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        let release_page = current_page.as_any().downcast_ref::<ReleasePage>();
+        assert_eq!(release_page.is_some(), true);
+
+        let db_state = db.read_db().unwrap();
+        let story = db_state.stories.get(&story_id).unwrap();
+        assert_eq!(story.fix_version, Some("1.0".to_owned()));
+        assert_eq!(story.story_type, StoryType::Bug);
+        assert_eq!(db_state.versions[0].released, true);
+    }
+
+    #[test]
+    fn handle_action_should_navigate_to_backlog() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+
+        let mut nav = Navigator::new(db);
+
+        nav.handle_action(Action::NavigateToBacklog).unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+
+        let current_page = nav.get_current_page().unwrap();
+        let backlog_page = current_page.as_any().downcast_ref::<BacklogPage>();
+        assert_eq!(backlog_page.is_some(), true);
+    }
+
+    #[test]
+    fn handle_action_should_handle_move_story_in_backlog() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id_1 = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let story_id_2 = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::MoveStoryInBacklog {
+            story_id: story_id_2,
+            direction: RankMove::Top,
+        })
+        .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.backlog, vec![story_id_2, story_id_1]);
+    }
+
+    #[test]
+    fn handle_action_should_navigate_to_help() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let mut nav = Navigator::new(db);
+
+        nav.handle_action(Action::NavigateToHelp).unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+
+        let current_page = nav.get_current_page().unwrap();
+        let help_page = current_page.as_any().downcast_ref::<HelpPage>().unwrap();
+        assert_eq!(help_page.page, PageKind::Home);
+    }
+
+    #[test]
+    fn handle_action_should_handle_palette_commands() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let other_epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::GoTo {
+            id: story_id.to_string(),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 3);
+        let current_page = nav.get_current_page().unwrap();
+        let story_detail = current_page.as_any().downcast_ref::<StoryDetail>().unwrap();
+        assert_eq!(story_detail.epic_id, epic_id);
+
+        nav.handle_action(Action::UpdateStatus {
+            id: story_id,
+            status: Status::Closed,
+        })
+        .unwrap();
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].status,
+            Status::Closed
+        );
+
+        nav.handle_action(Action::MoveStoryToEpic {
+            story_id,
+            epic_id: other_epic_id.to_string(),
+        })
+        .unwrap();
+        // the old epic is replaced by the new one
+        assert_eq!(nav.get_page_count(), 3);
+        let current_page = nav.get_current_page().unwrap();
+        let story_detail = current_page.as_any().downcast_ref::<StoryDetail>().unwrap();
+        assert_eq!(story_detail.epic_id, other_epic_id);
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        let epic_detail = current_page.as_any().downcast_ref::<EpicDetail>().unwrap();
+        assert_eq!(epic_detail.epic_id, other_epic_id);
+
+        nav.handle_action(Action::UpdateStatus {
+            id: other_epic_id,
+            status: Status::InProgress,
+        })
+        .unwrap();
+        assert_eq!(
+            db.read_db().unwrap().epics[&other_epic_id].status,
+            Status::InProgress
+        );
+
+        assert_eq!(
+            nav.handle_action(Action::GoTo {
+                id: "999".to_owned()
+            })
+            .is_err(),
+            true
+        );
+        assert_eq!(
+            nav.handle_action(Action::MoveStoryToEpic {
+                story_id,
+                epic_id: "999".to_owned()
+            })
+            .is_err(),
+            true
+        );
+        assert_eq!(nav.get_page_count(), 2);
+    }
+
+    #[test]
+    fn handle_action_should_go_to_items_below_their_epic() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let other_epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        // an epic is opened once
+        nav.handle_action(Action::GoTo {
+            id: epic_id.to_string(),
+        })
+        .unwrap();
+        nav.handle_action(Action::GoTo {
+            id: epic_id.to_string(),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+
+        // a story of the epic shown is opened on top of it
+        nav.handle_action(Action::GoTo {
+            id: story_id.to_string(),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 3);
+
+        // a story of another epic is opened below its own epic
+        nav.handle_action(Action::NavigateToEpicDetail {
+            epic_id: other_epic_id,
+        })
+        .unwrap();
+        nav.handle_action(Action::GoTo {
+            id: story_id.to_string(),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 6);
+
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        let epic_detail = current_page.as_any().downcast_ref::<EpicDetail>().unwrap();
+        assert_eq!(epic_detail.epic_id, epic_id);
+    }
+
+    #[test]
+    fn handle_action_should_go_home_and_forward_again() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("Bugfixes".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+        nav.handle_action(Action::NavigateToStoryDetail { epic_id, story_id })
+            .unwrap();
+        assert_eq!(
+            nav.breadcrumbs(),
+            vec![
+                "Home".to_owned(),
+                format!("Epic {epic_id} Bugfixes"),
+                format!("Story {story_id}")
+            ]
+        );
+
+        nav.handle_action(Action::NavigateToHome).unwrap();
+        assert_eq!(nav.get_page_count(), 1);
+
+        nav.handle_action(Action::NavigateForward).unwrap();
+        nav.handle_action(Action::NavigateForward).unwrap();
+        assert_eq!(nav.get_page_count(), 3);
+        let current_page = nav.get_current_page().unwrap();
+        assert_eq!(current_page.item_id(), Some(story_id));
+
+        // nothing to go forward to
+        nav.handle_action(Action::NavigateForward).unwrap();
+        assert_eq!(nav.get_page_count(), 3);
+
+        // a new page ends the way forward
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+        nav.handle_action(Action::NavigateToBacklog).unwrap();
+        nav.handle_action(Action::NavigateForward).unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        assert_eq!(current_page.kind(), PageKind::Backlog);
+    }
+
+    #[test]
+    fn handle_action_should_remember_recently_visited_items() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::GoTo {
+            id: story_id.to_string(),
+        })
+        .unwrap();
+        nav.handle_action(Action::NavigateToRecent).unwrap();
+
+        let settings = db.read_db().unwrap().settings_of(&current_user_name());
+        assert_eq!(settings.recent, vec![story_id, epic_id]);
+
+        let current_page = nav.get_current_page().unwrap();
+        let recent_page = current_page.as_any().downcast_ref::<RecentPage>().unwrap();
+        assert_eq!(
+            recent_page.selectable_ids().unwrap(),
+            vec![story_id.to_string(), epic_id.to_string()]
+        );
+    }
+
+    #[test]
+    fn handle_action_should_filter_the_tables() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let mine = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let closed = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(closed, Status::Closed).unwrap();
+        let other_epic = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::ToggleStoryAssignee { story_id: mine })
+            .unwrap();
+        assert_eq!(
+            db.read_db().unwrap().stories[&mine].assignee,
+            Some(current_user_name())
+        );
+
+        nav.handle_action(Action::ChangeFilter {
+            change: FilterChange::ToggleOnlyMine,
+        })
+        .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        assert_eq!(
+            current_page.selectable_ids().unwrap(),
+            vec![epic_id.to_string()]
+        );
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        assert_eq!(
+            current_page.selectable_ids().unwrap(),
+            vec![mine.to_string()]
+        );
+
+        // the filter is remembered, also for the next session
+        nav.handle_action(Action::ChangeFilter {
+            change: FilterChange::ToggleOnlyMine,
+        })
+        .unwrap();
+        nav.handle_action(Action::ChangeFilter {
+            change: FilterChange::ToggleHideDone,
+        })
+        .unwrap();
+        let nav = Navigator::new(Rc::clone(&db));
+        let current_page = nav.get_current_page().unwrap();
+        assert_eq!(
+            current_page.selectable_ids().unwrap(),
+            vec![epic_id.to_string(), other_epic.to_string()]
+        );
+        let settings = db.read_db().unwrap().settings_of(&current_user_name());
+        assert_eq!(settings.filter.hide_done, true);
+        assert_eq!(settings.filter.only_mine, false);
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        assert_eq!(
+            current_page.selectable_ids().unwrap(),
+            vec![mine.to_string()]
+        );
+
+        // unassigned again
+        nav.handle_action(Action::ToggleStoryAssignee { story_id: mine })
+            .unwrap();
+        assert_eq!(db.read_db().unwrap().stories[&mine].assignee, None);
+    }
+
+    #[test]
+    fn handle_action_should_sort_the_table_of_the_page() {
+        let db = Rc::new(JiraDatabase {
+            database: Box::new(MockDB::new()),
+        });
+        let epic_b = db
+            .create_epic(Epic::new("Bravo".to_owned(), "".to_owned()))
+            .unwrap();
+        let epic_a = db
+            .create_epic(Epic::new("alpha".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::SortTable {
+            table: Table::Epics,
+            change: SortChange::NextKey,
+        })
+        .unwrap();
+
+        let settings = db.read_db().unwrap().settings_of(&current_user_name());
+        assert_eq!(settings.table(Table::Epics).sort, Column::Name);
+        assert_eq!(settings.table(Table::Stories).sort, Column::Id);
+        let current_page = nav.get_current_page().unwrap();
+        assert_eq!(
+            current_page.selectable_ids().unwrap(),
+            vec![epic_a.to_string(), epic_b.to_string()]
+        );
+
+        nav.handle_action(Action::SortTable {
+            table: Table::Epics,
+            change: SortChange::Reverse,
+        })
+        .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        assert_eq!(
+            current_page.selectable_ids().unwrap(),
+            vec![epic_b.to_string(), epic_a.to_string()]
+        );
+
+        nav.handle_action(Action::SortTable {
+            table: Table::Epics,
+            change: SortChange::Set {
+                key: Column::Id,
+                descending: false,
+            },
+        })
+        .unwrap();
+        let settings = db.read_db().unwrap().settings_of(&current_user_name());
+        assert_eq!(
+            settings.table(Table::Epics),
+            TableSettings::new(Table::Epics)
+        );
+    }
 }
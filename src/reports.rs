@@ -0,0 +1,278 @@
+/// This module generates the plain text reports of the CLI commands.
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, IsoWeek, NaiveDate, Weekday};
+use itertools::Itertools;
+
+use crate::dates::{format_duration, total_duration};
+use crate::models::{DBState, Minutes, StoryType, ID};
+
+/// Renders the logged work per person and week, optionally restricted to one author or week.
+pub fn timesheet(db_state: &DBState, author: Option<&str>, week: Option<IsoWeek>) -> String {
+    let mut report = String::new();
+
+    // (author, week, story id) -> time spent
+    let entries = db_state
+        .stories
+        .iter()
+        .flat_map(|(&story_id, story)| {
+            story
+                .work_logs
+                .iter()
+                .map(move |log| ((log.author.clone(), log.started.iso_week(), story_id), log))
+        })
+        .filter(|((log_author, log_week, _), _)| {
+            author.is_none_or(|author| author == log_author)
+                && week.is_none_or(|week| week == *log_week)
+        })
+        .into_grouping_map()
+        .fold(0, |total: Minutes, _, log| {
+            total.saturating_add(log.duration)
+        });
+
+    let by_author_and_week = entries
+        .into_iter()
+        .map(|((author, week, story_id), spent)| ((author, week), (story_id, spent)))
+        .into_group_map();
+
+    if by_author_and_week.is_empty() {
+        let _ = writeln!(report, "No work logged.");
+        return report;
+    }
+
+    for ((author, week), stories) in by_author_and_week
+        .into_iter()
+        .sorted_by_key(|((author, week), _)| (author.clone(), week.year(), week.week()))
+    {
+        let _ = writeln!(
+            report,
+            "{author} - week {}-W{:02} ({} - {})",
+            week.year(),
+            week.week(),
+            week_day(week, Weekday::Mon),
+            week_day(week, Weekday::Sun),
+        );
+        for (story_id, spent) in stories.iter().sorted() {
+            let _ = writeln!(
+                report,
+                "  {:>6}  {:<50} {:>10}",
+                story_id,
+                story_name(db_state, *story_id),
+                format_duration(*spent),
+            );
+        }
+        let total = total_duration(stories.iter().map(|(_, spent)| *spent));
+        let _ = writeln!(
+            report,
+            "  {:>6}  {:<50} {:>10}",
+            "",
+            "total",
+            format_duration(total)
+        );
+        let _ = writeln!(report);
+    }
+
+    report
+}
+
+/// Renders Markdown release notes of the resolved stories of a version, grouped by type and epic.
+pub fn release_notes(db_state: &DBState, version_name: &str) -> Result<String> {
+    let version = db_state
+        .versions
+        .iter()
+        .find(|version| version.name == version_name)
+        .ok_or_else(|| anyhow!("Version '{version_name}' not found!"))?;
+
+    let mut notes = String::new();
+    let _ = writeln!(notes, "# Release notes {}", version.name);
+    let _ = writeln!(notes);
+    if let Some(release_date) = version.release_date {
+        let _ = writeln!(notes, "Release date: {}", release_date.format("%Y-%m-%d"));
+        let _ = writeln!(notes);
+    }
+
+    // (type, epic id) -> story ids
+    let groups = db_state
+        .stories
+        .iter()
+        .filter(|(_, story)| story.fix_version.as_deref() == Some(version_name))
+        .filter(|(_, story)| !story.status.is_unresolved())
+        .map(|(&id, story)| (story.story_type, (db_state.epic_id_of_story(id), id)))
+        .into_group_map();
+
+    if groups.is_empty() {
+        let _ = writeln!(notes, "No resolved stories.");
+        return Ok(notes);
+    }
+
+    for (story_type, stories) in groups.into_iter().sorted_by_key(|(t, _)| *t) {
+        let heading = match story_type {
+            StoryType::Story => "Stories",
+            StoryType::Bug => "Bugs",
+            StoryType::Task => "Tasks",
+        };
+        let _ = writeln!(notes, "## {heading}");
+
+        for (epic_id, story_ids) in stories
+            .into_iter()
+            .into_group_map()
+            .into_iter()
+            .sorted_by_key(|(epic_id, _)| *epic_id)
+        {
+            let epic_name = epic_id
+                .and_then(|epic_id| db_state.epics.get(&epic_id))
+                .map(|epic| epic.name.as_str())
+                .unwrap_or("No epic");
+            let _ = writeln!(notes);
+            let _ = writeln!(notes, "### {epic_name}");
+            let _ = writeln!(notes);
+            for story_id in story_ids.into_iter().sorted() {
+                let _ = writeln!(
+                    notes,
+                    "- {} {}",
+                    db_state.display_id(story_id),
+                    story_name(db_state, story_id)
+                );
+            }
+        }
+        let _ = writeln!(notes);
+    }
+
+    Ok(notes)
+}
+
+fn week_day(week: IsoWeek, weekday: Weekday) -> NaiveDate {
+    NaiveDate::from_isoywd_opt(week.year(), week.week(), weekday).unwrap_or_default()
+}
+
+fn story_name(db_state: &DBState, story_id: ID) -> &str {
+    db_state
+        .stories
+        .get(&story_id)
+        .map(|story| story.name.as_str())
+        .unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Epic, Status, Story, Version, WorkLog};
+
+    fn work_log(author: &str, day: u32, duration: Minutes) -> WorkLog {
+        WorkLog {
+            author: author.to_owned(),
+            started: NaiveDate::from_ymd_opt(2026, 10, day)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap(),
+            duration,
+            note: "".to_owned(),
+        }
+    }
+
+    fn db_state() -> DBState {
+        let mut db_state = DBState::default();
+
+        let mut story = Story::new("Login page".to_owned(), "".to_owned());
+        // monday and tuesday of week 43, monday of week 44
+        story.work_logs = vec![
+            work_log("alice", 19, 60),
+            work_log("alice", 20, 30),
+            work_log("bob", 26, 120),
+        ];
+        db_state.stories.insert(2, story);
+
+        db_state
+    }
+
+    #[test]
+    fn timesheet_should_group_by_author_and_week() {
+        let report = timesheet(&db_state(), None, None);
+
+        assert_eq!(
+            report,
+            format!(
+                "alice - week 2026-W43 (2026-10-19 - 2026-10-25)\n\
+                 \x20      2  {:<50} {:>10}\n\
+                 \x20         {:<50} {:>10}\n\
+                 \n\
+                 bob - week 2026-W44 (2026-10-26 - 2026-11-01)\n\
+                 \x20      2  {:<50} {:>10}\n\
+                 \x20         {:<50} {:>10}\n\
+                 \n",
+                "Login page", "1h 30m", "total", "1h 30m", "Login page", "2h", "total", "2h"
+            )
+        );
+    }
+
+    #[test]
+    fn timesheet_should_filter_by_author_and_week() {
+        let db_state = db_state();
+        let week = NaiveDate::from_ymd_opt(2026, 10, 21).unwrap().iso_week();
+
+        let report = timesheet(&db_state, Some("bob"), None);
+        assert_eq!(report.starts_with("bob - week 2026-W44"), true);
+        assert_eq!(report.contains("alice"), false);
+
+        let report = timesheet(&db_state, None, Some(week));
+        assert_eq!(report.starts_with("alice - week 2026-W43"), true);
+        assert_eq!(report.contains("bob"), false);
+
+        let report = timesheet(&db_state, Some("bob"), Some(week));
+        assert_eq!(report, "No work logged.\n");
+    }
+
+    #[test]
+    fn release_notes_should_fail_with_unknown_version() {
+        assert_eq!(release_notes(&DBState::default(), "1.0").is_err(), true);
+    }
+
+    #[test]
+    fn release_notes_should_group_resolved_stories_by_type_and_epic() {
+        let mut db_state = DBState::default();
+        db_state.versions.push(Version::new(
+            "1.0".to_owned(),
+            NaiveDate::from_ymd_opt(2026, 11, 1),
+        ));
+
+        let mut epic = Epic::new("Checkout".to_owned(), "".to_owned());
+        epic.stories = vec![2, 3, 4];
+        db_state.epics.insert(1, epic);
+
+        let stories = [
+            (2, "Pay by card", StoryType::Story, Status::Resolved),
+            (3, "Crash on empty cart", StoryType::Bug, Status::Closed),
+            (4, "Pay by invoice", StoryType::Story, Status::InProgress),
+        ];
+        for (id, name, story_type, status) in stories {
+            let mut story = Story::new(name.to_owned(), "".to_owned());
+            story.story_type = story_type;
+            story.status = status;
+            story.fix_version = Some("1.0".to_owned());
+            db_state.stories.insert(id, story);
+        }
+
+        let notes = release_notes(&db_state, "1.0").unwrap();
+
+        assert_eq!(
+            notes,
+            "# Release notes 1.0\n\
+             \n\
+             Release date: 2026-11-01\n\
+             \n\
+             ## Stories\n\
+             \n\
+             ### Checkout\n\
+             \n\
+             - 2 Pay by card\n\
+             \n\
+             ## Bugs\n\
+             \n\
+             ### Checkout\n\
+             \n\
+             - 3 Crash on empty cart\n\
+             \n"
+        );
+    }
+}
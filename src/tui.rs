@@ -0,0 +1,559 @@
+/// This module contains the full-screen terminal UI, started with `my-jira tui`.
+///
+/// It is built on the same pages, actions and navigator as the line based UI: single keys
+/// and selected rows are passed to the page through the keymap, and the prompts are shown as modal
+/// line editors at the bottom of the screen. Raw mode is only turned on while waiting for a
+/// key, so the pages are drawn to stdout like in the line based UI.
+use anyhow::Result;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{
+        self, disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use unicode_width::UnicodeWidthStr;
+
+use crate::db::JiraDatabase;
+use crate::io_utils::InputSource;
+use crate::models::Action;
+use crate::navigator::Navigator;
+use crate::ui::{self, Keymap, PageKind, Palette, Prompts, Screen};
+
+const HELP: &str =
+    "[↑/↓] select | [enter] open | [esc] back | [:] command | [?] help | [ctrl-c] quit";
+
+/// What a key press means to the main loop
+#[derive(Debug, PartialEq, Eq)]
+enum Input {
+    SelectPrevious,
+    SelectNext,
+    SelectFirst,
+    SelectLast,
+    /// show the next or previous page of a long table
+    Scroll(isize),
+    /// open the selected row
+    Open,
+    /// go back to the previous page
+    Back,
+    /// a single key passed to the page, e.g. `c` to create an epic
+    Key(char),
+    /// the keys of a command passed to the page, e.g. `back`
+    Keys(String),
+    /// start typing a command line, e.g. an id or `u 12`, with the given first character
+    CommandLine(String),
+    /// start typing a command of the command palette, e.g. `status resolved`
+    CommandPalette,
+    Quit,
+    None,
+}
+
+/// What the terminal reports while waiting for input
+enum TerminalEvent {
+    Key(KeyEvent),
+    /// the terminal was resized, so the screen has to be drawn again
+    Resize,
+}
+
+/// Switches the terminal back to normal when dropped, also when the app panics.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        install_panic_hook();
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+fn restore_terminal() {
+    // errors are ignored, there is nothing left to do if the terminal can't be restored
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+}
+
+/// Restores the terminal before the panic message is printed, otherwise it would be lost
+/// on the alternate screen and the shell would be left in raw mode.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+}
+
+/// entry point of the full-screen UI
+pub fn run(db: Rc<JiraDatabase>, palette: Palette, keymap: Rc<Keymap>) -> Result<()> {
+    let _guard = TerminalGuard::enter()?;
+
+    let modal = Rc::new(ModalPrompt {
+        palette: palette.clone(),
+        error: RefCell::default(),
+    });
+    let prompts = Prompts::with_input(modal.clone(), palette.clone());
+    let mut nav = Navigator::with_prompts(db.clone(), prompts);
+    // the selected row of every page on the stack, so going back keeps the selection
+    let mut selections: Vec<usize> = vec![];
+    let mut message = String::new();
+    // the keys typed so far of a command with a longer key, e.g. `ba` of `back`
+    let mut pending = String::new();
+
+    while let Some(page) = nav.get_current_page() {
+        selections.resize(nav.get_page_count(), 0);
+        let selection = selections.last_mut().expect("a page is shown");
+
+        let ids = page.selectable_ids().unwrap_or_default();
+        *selection = (*selection).min(ids.len().saturating_sub(1));
+        let selected_id = ids.get(*selection).cloned();
+
+        // 1. render the page with the selected row highlighted, and the status bar below
+        execute!(io::stdout(), Clear(ClearType::All), MoveTo(0, 0))?;
+        let mut stdout = io::stdout();
+        let mut screen = Screen::new(&mut stdout)
+            .with_palette(palette.clone())
+            .with_keymap(keymap.clone())
+            .with_page_size(crate::page_size(&db))
+            .with_selected_row(selected_id.clone());
+        if let Err(error) = ui::draw_header(&db, &mut screen, &nav.breadcrumbs())
+            .and_then(|_| page.draw_page(&mut screen))
+        {
+            message = format!("Error rendering page: {error}");
+        }
+        let position = match ids.len() {
+            0 => String::new(),
+            count => format!("{}/{count} | ", *selection + 1),
+        };
+        draw_status_bar(&palette, &format!("{position}{HELP}"), &message)?;
+        message.clear();
+
+        // 2. turn the key into a command line for the page
+        let key = match read_event()? {
+            TerminalEvent::Key(key) => key,
+            TerminalEvent::Resize => continue,
+        };
+        let input = match collect_keys(&keymap, page.kind(), &mut pending, to_input(key)) {
+            Some(input) => input,
+            None => {
+                message = pending.clone();
+                continue;
+            }
+        };
+        let input = match input {
+            Input::SelectPrevious => {
+                *selection = selection.saturating_sub(1);
+                continue;
+            }
+            Input::SelectNext => {
+                *selection += 1;
+                continue;
+            }
+            Input::SelectFirst => {
+                *selection = 0;
+                continue;
+            }
+            Input::SelectLast => {
+                *selection = ids.len().saturating_sub(1);
+                continue;
+            }
+            Input::Scroll(pages) => {
+                if let Err(error) = nav.handle_action(Action::ScrollList { pages }) {
+                    message = format!("Error: {error}");
+                }
+                *selection = 0;
+                continue;
+            }
+            Input::Open => match selected_id.clone() {
+                Some(id) => id,
+                None => continue,
+            },
+            Input::Back => {
+                // the last page is only left with quit
+                if nav.get_page_count() > 1 {
+                    if let Err(error) = nav.handle_action(Action::NavigateToPreviousPage) {
+                        message = format!("Error: {error}");
+                    }
+                }
+                continue;
+            }
+            Input::Key(key) => key.to_string(),
+            Input::Keys(keys) => keys,
+            Input::CommandLine(initial) => match read_line(":", &initial, None)? {
+                Some(line) => line.trim().to_string(),
+                None => continue,
+            },
+            Input::CommandPalette => {
+                let kind = page.kind();
+                match read_line(":", "", Some(&|line| ui::complete(line, kind)))? {
+                    Some(line) => format!(":{}", line.trim()),
+                    None => continue,
+                }
+            }
+            Input::Quit => break,
+            Input::None => continue,
+        };
+
+        // 3. let the page handle the input, commands with an id like `u 12` in the backlog
+        // are applied to the selected row when only the key is typed
+        let mut result = keymap.handle_input(page.as_ref(), &input);
+        if let (Ok(None), Some(id)) = (&result, &selected_id) {
+            if !input.contains(' ') && !input.starts_with(':') {
+                result = keymap.handle_input(page.as_ref(), &format!("{input} {id}"));
+            }
+        }
+
+        // 4. let the navigator process the action, a rejected answer to a prompt is shown
+        // in the status bar as well
+        match result {
+            Ok(Some(action)) => {
+                if let Err(error) = nav.handle_action(action) {
+                    message = format!("Error: {error}");
+                } else if let Some(error) = modal.error.take() {
+                    message = format!("Error: {error}");
+                }
+            }
+            Ok(None) => message = format!("Unknown command '{input}'"),
+            Err(error) => message = format!("Error: {error}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the keys of a command while a longer key of the page starts with them, e.g. `b`
+/// and `ba` of `back`. Enter runs the keys typed so far and escape drops them. Uppercase keys
+/// start a command line unless a key of the page starts with them. Returns `None` while more
+/// keys are expected.
+fn collect_keys(
+    keymap: &Keymap,
+    page: PageKind,
+    pending: &mut String,
+    input: Input,
+) -> Option<Input> {
+    let key = match &input {
+        Input::Key(key) => Some(*key),
+        Input::CommandLine(line) => line
+            .chars()
+            .next()
+            .filter(|key| !key.is_ascii_digit() || !pending.is_empty()),
+        _ => None,
+    };
+    match (key, input) {
+        (Some(key), input) => {
+            let typed = format!("{pending}{key}");
+            if pending.is_empty() && keymap.keys_starting_with(page, &typed).next().is_none() {
+                return Some(input);
+            }
+            let complete = !keymap
+                .keys_starting_with(page, &typed)
+                .any(|bound| bound.len() > typed.len());
+            match complete {
+                true => {
+                    pending.clear();
+                    Some(Input::Keys(typed))
+                }
+                false => {
+                    *pending = typed;
+                    None
+                }
+            }
+        }
+        (None, Input::Open) if !pending.is_empty() => Some(Input::Keys(std::mem::take(pending))),
+        (None, Input::Back) if !pending.is_empty() => {
+            pending.clear();
+            Some(Input::None)
+        }
+        (None, input) => {
+            pending.clear();
+            Some(input)
+        }
+    }
+}
+
+fn to_input(key: KeyEvent) -> Input {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Input::Quit,
+        KeyCode::Up => Input::SelectPrevious,
+        KeyCode::Down => Input::SelectNext,
+        KeyCode::Home => Input::SelectFirst,
+        KeyCode::End => Input::SelectLast,
+        KeyCode::PageDown => Input::Scroll(1),
+        KeyCode::PageUp => Input::Scroll(-1),
+        KeyCode::Enter => Input::Open,
+        KeyCode::Esc | KeyCode::Backspace => Input::Back,
+        KeyCode::Char(':') => Input::CommandPalette,
+        // ids are typed on the command line, e.g. `12` or `WEB-12`
+        KeyCode::Char(c) if c.is_ascii_digit() || c.is_ascii_uppercase() => {
+            Input::CommandLine(c.to_string())
+        }
+        KeyCode::Char(c) => Input::Key(c),
+        _ => Input::None,
+    }
+}
+
+/// Waits for a key press or a resize of the terminal in raw mode
+fn read_event() -> Result<TerminalEvent> {
+    enable_raw_mode()?;
+    let event = loop {
+        match event::read() {
+            // only presses, some terminals also report the release of a key
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                break Ok(TerminalEvent::Key(key))
+            }
+            Ok(Event::Resize(..)) => break Ok(TerminalEvent::Resize),
+            Ok(_) => continue,
+            Err(error) => break Err(error),
+        }
+    };
+    disable_raw_mode()?;
+    Ok(event?)
+}
+
+/// Draws the status bar in the last line of the screen, the message on the left and the help on the right
+fn draw_status_bar(palette: &Palette, help: &str, message: &str) -> Result<()> {
+    let (width, height) = terminal::size()?;
+    let padding = usize::from(width).saturating_sub(message.width() + help.width() + 2);
+
+    let mut stdout = io::stdout();
+    execute!(stdout, MoveTo(0, height.saturating_sub(1)))?;
+    write!(
+        stdout,
+        "{} {message}{}{help} {}",
+        palette.selected,
+        " ".repeat(padding),
+        palette.reset
+    )?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// A modal prompt drawn above the status bar, there is no answer when it is cancelled with escape
+struct ModalPrompt {
+    palette: Palette,
+    /// the last rejected answer, until the status bar shows it
+    error: RefCell<Option<String>>,
+}
+
+impl InputSource for ModalPrompt {
+    fn read_line(&self, message: &str) -> Option<String> {
+        modal_prompt(&self.palette, message)
+    }
+
+    // the modal draws its own lines
+    fn show_separator(&self, _separator: &str) {}
+
+    fn show_error(&self, error: &str) {
+        self.error.replace(Some(error.to_string()));
+    }
+}
+
+fn modal_prompt(palette: &Palette, message: &str) -> Option<String> {
+    let answer = (|| -> Result<Option<String>> {
+        let (width, height) = terminal::size()?;
+        let line = "─".repeat(usize::from(width));
+
+        let mut stdout = io::stdout();
+        execute!(stdout, MoveTo(0, height.saturating_sub(4)))?;
+        write!(stdout, "{line}")?;
+        execute!(stdout, MoveTo(0, height.saturating_sub(2)))?;
+        write!(stdout, "{line}")?;
+        draw_status_bar(palette, "[enter] confirm | [esc] cancel", "")?;
+
+        read_line_at(height.saturating_sub(3), &format!("{message} "), "", None)
+    })();
+
+    answer.ok().flatten()
+}
+
+/// Returns the completions of a line typed so far
+type Completer<'a> = &'a dyn Fn(&str) -> Vec<String>;
+
+/// Reads a line in the status bar, `None` when it is cancelled with escape
+fn read_line(prefix: &str, initial: &str, completer: Option<Completer>) -> Result<Option<String>> {
+    let (_, height) = terminal::size()?;
+    read_line_at(height.saturating_sub(1), prefix, initial, completer)
+}
+
+/// Reads a line, the tab key completes it when there is a completer and lists the candidates
+/// in the line above
+fn read_line_at(
+    row: u16,
+    prefix: &str,
+    initial: &str,
+    completer: Option<Completer>,
+) -> Result<Option<String>> {
+    let mut line = initial.to_string();
+    let mut candidates: Vec<String> = vec![];
+    let mut stdout = io::stdout();
+    execute!(stdout, Show)?;
+
+    let result = loop {
+        if !candidates.is_empty() {
+            execute!(
+                stdout,
+                MoveTo(0, row.saturating_sub(1)),
+                Clear(ClearType::CurrentLine)
+            )?;
+            write!(stdout, "{}", candidates.join("  "))?;
+        }
+        execute!(stdout, MoveTo(0, row), Clear(ClearType::CurrentLine))?;
+        write!(stdout, "{prefix}{line}")?;
+        stdout.flush()?;
+
+        // the line is drawn again after a resize
+        let TerminalEvent::Key(key) = read_event()? else {
+            continue;
+        };
+        match key.code {
+            KeyCode::Enter => break Some(line),
+            KeyCode::Esc => break None,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break None,
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            KeyCode::Tab => {
+                if let Some(completer) = completer {
+                    candidates = completer(&line);
+                    line = complete_line(&line, &candidates);
+                }
+            }
+            KeyCode::Char(c) => line.push(c),
+            _ => {}
+        }
+    };
+
+    execute!(stdout, Hide)?;
+    Ok(result)
+}
+
+/// Completes the line to the candidate, or to the common start of several candidates
+fn complete_line(line: &str, candidates: &[String]) -> String {
+    match candidates {
+        [] => line.to_string(),
+        [candidate] => format!("{candidate} "),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.as_str(), |common, candidate| {
+                let length = common
+                    .char_indices()
+                    .zip(candidate.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or(common.len().min(candidate.len()), |((index, _), _)| index);
+                &common[..length]
+            });
+            if common.len() > line.len() {
+                common.to_string()
+            } else {
+                line.to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn to_command_should_map_keys() {
+        assert_eq!(to_input(key(KeyCode::Up)), Input::SelectPrevious);
+        assert_eq!(to_input(key(KeyCode::Down)), Input::SelectNext);
+        assert_eq!(to_input(key(KeyCode::Home)), Input::SelectFirst);
+        assert_eq!(to_input(key(KeyCode::End)), Input::SelectLast);
+        assert_eq!(to_input(key(KeyCode::PageDown)), Input::Scroll(1));
+        assert_eq!(to_input(key(KeyCode::PageUp)), Input::Scroll(-1));
+        assert_eq!(to_input(key(KeyCode::Enter)), Input::Open);
+        assert_eq!(to_input(key(KeyCode::Esc)), Input::Back);
+        assert_eq!(to_input(key(KeyCode::Char('c'))), Input::Key('c'));
+        assert_eq!(
+            to_input(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Input::Quit
+        );
+        assert_eq!(to_input(key(KeyCode::F(1))), Input::None);
+    }
+
+    #[test]
+    fn collect_keys_should_wait_for_longer_keys() {
+        let keymap =
+            Keymap::from_json(r#"{"previous": ["p", "back"], "help": ["?", "H"]}"#).unwrap();
+        let mut pending = String::new();
+        let mut collect = |input| collect_keys(&keymap, PageKind::Backlog, &mut pending, input);
+
+        assert_eq!(collect(Input::Key('b')), None);
+        assert_eq!(collect(Input::Key('a')), None);
+        assert_eq!(collect(Input::Key('c')), None);
+        assert_eq!(
+            collect(Input::Key('k')),
+            Some(Input::Keys("back".to_owned()))
+        );
+        // enter runs a key which is the start of a longer one, escape drops it
+        assert_eq!(collect(Input::Key('b')), None);
+        assert_eq!(collect(Input::Open), Some(Input::Keys("b".to_owned())));
+        assert_eq!(collect(Input::Key('b')), None);
+        assert_eq!(collect(Input::Back), Some(Input::None));
+        assert_eq!(collect(Input::Open), Some(Input::Open));
+        // single keys and keys which start no command are passed on
+        assert_eq!(collect(Input::Key('u')), Some(Input::Keys("u".to_owned())));
+        assert_eq!(collect(Input::Key('x')), Some(Input::Key('x')));
+        // uppercase keys are commands if they are bound, ids otherwise
+        assert_eq!(
+            collect(Input::CommandLine("H".to_owned())),
+            Some(Input::Keys("H".to_owned()))
+        );
+        assert_eq!(
+            collect(Input::CommandLine("W".to_owned())),
+            Some(Input::CommandLine("W".to_owned()))
+        );
+        assert_eq!(
+            collect(Input::CommandLine("1".to_owned())),
+            Some(Input::CommandLine("1".to_owned()))
+        );
+    }
+
+    #[test]
+    fn to_command_should_start_command_line_for_ids() {
+        assert_eq!(to_input(key(KeyCode::Char(':'))), Input::CommandPalette);
+        assert_eq!(
+            to_input(key(KeyCode::Char('1'))),
+            Input::CommandLine("1".to_owned())
+        );
+        assert_eq!(
+            to_input(key(KeyCode::Char('W'))),
+            Input::CommandLine("W".to_owned())
+        );
+    }
+
+    #[test]
+    fn complete_line_should_complete_the_common_start() {
+        let candidates = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            complete_line("de", &candidates(&["delete-story"])),
+            "delete-story "
+        );
+        assert_eq!(
+            complete_line("m", &candidates(&["move-to-top", "move-to-bottom"])),
+            "move-to-"
+        );
+        assert_eq!(complete_line("go", &candidates(&["goto", "go"])), "go");
+        assert_eq!(complete_line("x", &[]), "x");
+    }
+}
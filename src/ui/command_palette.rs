@@ -0,0 +1,336 @@
+/// This module contains the command palette: commands typed by name after a `:`, like
+/// `:status resolved`, `:goto 12` or `:delete-story`.
+///
+/// Names can be shortened as long as they are unambiguous, e.g. `:st res`. The TUI completes
+/// them with the tab key.
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::str::FromStr;
+
+use crate::models::{Action, Column, FilterChange, SortChange, Status, SORT_KEYS};
+
+use super::{Command, Page, PageKind};
+
+/// The status names completed after `:status`
+const STATUS_NAMES: [&str; 4] = ["open", "in-progress", "resolved", "closed"];
+/// The directions after the column of `:sort`
+const DIRECTIONS: [&str; 2] = ["asc", "desc"];
+/// The filters completed after `:filter`, the statuses and the ones without a status
+const FILTERS: [&str; 6] = ["open", "in-progress", "resolved", "closed", "any", "clear"];
+
+/// A command of the command palette
+#[derive(Debug, PartialEq, Eq)]
+pub enum PaletteCommand {
+    /// a command of the page by name, e.g. `:delete-story` or `:move-up 12`
+    Page(Command, Option<String>),
+    /// changes the status of the epic or story shown
+    Status(Status),
+    /// goes to an epic or story by id
+    GoTo(String),
+    /// moves the story shown to another epic
+    Move(String),
+    /// sorts the table of the page by the column, descending if true
+    Sort(Column, bool),
+    /// changes the filter of the tables
+    Filter(FilterChange),
+}
+
+/// The commands of the palette which are not commands of the page, with their arguments
+/// and descriptions, e.g. for the help page
+pub fn palette_commands(kind: PageKind) -> Vec<(&'static str, &'static str, &'static str)> {
+    let mut commands = vec![("goto", "<id>", "go to an epic or story")];
+    if matches!(kind, PageKind::EpicDetail | PageKind::StoryDetail) {
+        commands.push((
+            "status",
+            "<open|in-progress|resolved|closed>",
+            "change the status",
+        ));
+    }
+    if kind == PageKind::StoryDetail {
+        commands.push(("move", "<epic-id>", "move the story to another epic"));
+    }
+    if kind.table().is_some() {
+        commands.push((
+            "sort",
+            "<column> [asc|desc]",
+            "sort by id, name, status, priority, updated or due",
+        ));
+        commands.push((
+            "filter",
+            "<status|any|clear>",
+            "show one status, any status or everything",
+        ));
+    }
+    commands
+}
+
+/// All names which can be typed on the page
+fn names(kind: PageKind) -> Vec<&'static str> {
+    palette_commands(kind)
+        .into_iter()
+        .map(|(name, ..)| name)
+        .chain(kind.commands().iter().map(|command| command.name()))
+        .collect()
+}
+
+/// Finds the name the input is the start of, an exact match wins over longer names.
+fn find<'a>(input: &str, names: &[&'a str], what: &str) -> Result<&'a str> {
+    if let Some(name) = names.iter().find(|name| **name == input) {
+        return Ok(name);
+    }
+    let matches = names
+        .iter()
+        .filter(|name| name.starts_with(input))
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [name] => Ok(name),
+        [] => Err(anyhow!("Unknown {what} '{input}'!")),
+        _ => Err(anyhow!(
+            "Ambiguous {what} '{input}', did you mean {}?",
+            matches.iter().join(", ")
+        )),
+    }
+}
+
+/// Parses a line of the palette, without the leading `:`
+pub fn parse(line: &str, kind: PageKind) -> Result<PaletteCommand> {
+    let (name, argument) = match line.trim().split_once(' ') {
+        Some((name, argument)) => (name, Some(argument.trim().to_owned())),
+        None => (line.trim(), None),
+    };
+    let missing = |what: &str| anyhow!("Missing {what} for '{name}'!");
+
+    match find(name, &names(kind), "command")? {
+        "goto" => Ok(PaletteCommand::GoTo(argument.ok_or_else(|| missing("id"))?)),
+        "move" => Ok(PaletteCommand::Move(
+            argument.ok_or_else(|| missing("epic id"))?,
+        )),
+        "status" => {
+            let argument = argument.ok_or_else(|| missing("status"))?;
+            let status = find(&argument, &STATUS_NAMES, "status")?;
+            Ok(PaletteCommand::Status(Status::from_str(status)?))
+        }
+        "sort" => {
+            let argument = argument.ok_or_else(|| missing("column"))?;
+            let (column, direction) = match argument.split_once(' ') {
+                Some((column, direction)) => (column, Some(direction.trim())),
+                None => (argument.as_str(), None),
+            };
+            let names = SORT_KEYS.map(Column::name);
+            let column = Column::from_str(find(column, &names, "column")?)?;
+            let descending = match direction {
+                None => false,
+                Some(direction) => find(direction, &DIRECTIONS, "direction")? == "desc",
+            };
+            Ok(PaletteCommand::Sort(column, descending))
+        }
+        "filter" => {
+            let argument = argument.ok_or_else(|| missing("filter"))?;
+            let change = match find(&argument, &FILTERS, "filter")? {
+                "any" => FilterChange::SetStatus(None),
+                "clear" => FilterChange::Clear,
+                status => FilterChange::SetStatus(Some(Status::from_str(status)?)),
+            };
+            Ok(PaletteCommand::Filter(change))
+        }
+        name => {
+            let command = Command::from_name(name).expect("the names of the page are commands");
+            match (command.takes_id(), argument) {
+                (true, None) => Err(missing("id")),
+                (false, Some(_)) => Err(anyhow!("'{name}' doesn't take an argument!")),
+                (_, argument) => Ok(PaletteCommand::Page(command, argument)),
+            }
+        }
+    }
+}
+
+/// Maps a line of the palette onto the action of the page
+pub fn handle_input(page: &dyn Page, line: &str) -> Result<Option<Action>> {
+    match parse(line, page.kind())? {
+        PaletteCommand::Page(command, id) => match command.global_action() {
+            Some(action) => Ok(Some(action)),
+            None => page.handle_command(command, id.as_deref()),
+        },
+        PaletteCommand::Status(status) => {
+            Ok(page.item_id().map(|id| Action::UpdateStatus { id, status }))
+        }
+        PaletteCommand::GoTo(id) => Ok(Some(Action::GoTo { id })),
+        PaletteCommand::Move(epic_id) => Ok(page
+            .item_id()
+            .map(|story_id| Action::MoveStoryToEpic { story_id, epic_id })),
+        PaletteCommand::Sort(key, descending) => {
+            Ok(page.kind().table().map(|table| Action::SortTable {
+                table,
+                change: SortChange::Set { key, descending },
+            }))
+        }
+        PaletteCommand::Filter(change) => Ok(Some(Action::ChangeFilter { change })),
+    }
+}
+
+/// The completions of a line of the palette, without the leading `:`
+pub fn complete(line: &str, kind: PageKind) -> Vec<String> {
+    match line.split_once(' ') {
+        None => names(kind)
+            .into_iter()
+            .filter(|name| name.starts_with(line))
+            .map(str::to_owned)
+            .collect(),
+        Some((name, argument)) => match find(name, &names(kind), "command") {
+            Ok("status") => STATUS_NAMES
+                .iter()
+                .filter(|status| status.starts_with(argument.trim_start()))
+                .map(|status| format!("status {status}"))
+                .collect(),
+            Ok("filter") => FILTERS
+                .iter()
+                .filter(|filter| filter.starts_with(argument.trim_start()))
+                .map(|filter| format!("filter {filter}"))
+                .collect(),
+            Ok("sort") => match argument.trim_start().split_once(' ') {
+                None => SORT_KEYS
+                    .iter()
+                    .filter(|key| key.name().starts_with(argument.trim_start()))
+                    .map(|key| format!("sort {}", key.name()))
+                    .collect(),
+                Some((column, direction)) => DIRECTIONS
+                    .iter()
+                    .filter(|name| name.starts_with(direction.trim_start()))
+                    .map(|name| format!("sort {column} {name}"))
+                    .collect(),
+            },
+            _ => vec![],
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_should_accept_commands_by_name() {
+        assert_eq!(
+            parse("status resolved", PageKind::StoryDetail).unwrap(),
+            PaletteCommand::Status(Status::Resolved)
+        );
+        assert_eq!(
+            parse("goto WEB-12", PageKind::Home).unwrap(),
+            PaletteCommand::GoTo("WEB-12".to_owned())
+        );
+        assert_eq!(
+            parse("move 4", PageKind::StoryDetail).unwrap(),
+            PaletteCommand::Move("4".to_owned())
+        );
+        assert_eq!(
+            parse("delete-story", PageKind::StoryDetail).unwrap(),
+            PaletteCommand::Page(Command::DeleteStory, None)
+        );
+        assert_eq!(
+            parse("move-up 12", PageKind::Backlog).unwrap(),
+            PaletteCommand::Page(Command::MoveUp, Some("12".to_owned()))
+        );
+    }
+
+    #[test]
+    fn parse_should_accept_unambiguous_prefixes() {
+        assert_eq!(
+            parse("st in", PageKind::EpicDetail).unwrap(),
+            PaletteCommand::Status(Status::InProgress)
+        );
+        assert_eq!(
+            parse("g 3", PageKind::Upcoming).unwrap(),
+            PaletteCommand::GoTo("3".to_owned())
+        );
+        // `move` is a name of its own, `move-up` is only longer
+        assert_eq!(
+            parse("move-to-t 2", PageKind::Backlog).unwrap(),
+            PaletteCommand::Page(Command::MoveToTop, Some("2".to_owned()))
+        );
+    }
+
+    #[test]
+    fn parse_should_reject_invalid_commands() {
+        assert_eq!(
+            parse("move-to", PageKind::Backlog).unwrap_err().to_string(),
+            "Ambiguous command 'move-to', did you mean move-to-top, move-to-bottom?".to_owned()
+        );
+        assert_eq!(parse("teleport", PageKind::Home).is_err(), true);
+        // commands of other pages
+        assert_eq!(parse("status open", PageKind::Home).is_err(), true);
+        assert_eq!(parse("move 4", PageKind::EpicDetail).is_err(), true);
+        // missing and unexpected arguments
+        assert_eq!(parse("goto", PageKind::Home).is_err(), true);
+        assert_eq!(parse("status done", PageKind::EpicDetail).is_err(), true);
+        assert_eq!(parse("move-up", PageKind::Backlog).is_err(), true);
+        assert_eq!(parse("quit now", PageKind::Home).is_err(), true);
+        assert_eq!(parse("sort", PageKind::Home).is_err(), true);
+        assert_eq!(parse("sort progress", PageKind::Home).is_err(), true);
+        assert_eq!(parse("sort name up", PageKind::Home).is_err(), true);
+        assert_eq!(parse("sort name", PageKind::StoryDetail).is_err(), true);
+    }
+
+    #[test]
+    fn parse_should_accept_filters() {
+        assert_eq!(
+            parse("filter in", PageKind::Home).unwrap(),
+            PaletteCommand::Filter(FilterChange::SetStatus(Some(Status::InProgress)))
+        );
+        assert_eq!(
+            parse("fil any", PageKind::EpicDetail).unwrap(),
+            PaletteCommand::Filter(FilterChange::SetStatus(None))
+        );
+        assert_eq!(
+            parse("filter cl", PageKind::Home).unwrap_err().to_string(),
+            "Ambiguous filter 'cl', did you mean closed, clear?".to_owned()
+        );
+        assert_eq!(parse("filter", PageKind::Home).is_err(), true);
+        assert_eq!(parse("filter open", PageKind::Backlog).is_err(), true);
+        assert_eq!(complete("filter c", PageKind::Home).len(), 2);
+    }
+
+    #[test]
+    fn parse_should_accept_sort_columns_and_directions() {
+        assert_eq!(
+            parse("sort priority", PageKind::Home).unwrap(),
+            PaletteCommand::Sort(Column::Priority, false)
+        );
+        assert_eq!(
+            parse("sort up desc", PageKind::EpicDetail).unwrap(),
+            PaletteCommand::Sort(Column::Updated, true)
+        );
+        assert_eq!(
+            parse("sort du a", PageKind::EpicDetail).unwrap(),
+            PaletteCommand::Sort(Column::Due, false)
+        );
+    }
+
+    #[test]
+    fn complete_should_complete_names_and_statuses() {
+        assert_eq!(
+            complete("de", PageKind::StoryDetail),
+            vec!["delete-story".to_owned()]
+        );
+        assert_eq!(
+            complete("st", PageKind::EpicDetail),
+            vec!["status".to_owned()]
+        );
+        assert_eq!(
+            complete("status ", PageKind::EpicDetail).len(),
+            STATUS_NAMES.len()
+        );
+        assert_eq!(
+            complete("st re", PageKind::EpicDetail),
+            vec!["status resolved".to_owned()]
+        );
+        assert_eq!(complete("goto 1", PageKind::Home).is_empty(), true);
+        assert_eq!(
+            complete("sort p", PageKind::Home),
+            vec!["sort priority".to_owned()]
+        );
+        assert_eq!(
+            complete("sort name d", PageKind::Home),
+            vec!["sort name desc".to_owned()]
+        );
+    }
+}
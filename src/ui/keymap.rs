@@ -0,0 +1,704 @@
+/// This module maps the keys typed by the user to the commands of the pages.
+///
+/// Every page has a fixed list of commands, the keys of the commands can be changed in a
+/// JSON config file like `{"previous": ["p", "back"], "quit": ["q", "exit"]}`. A command can
+/// have several keys (aliases) and a key can be longer than one character. The first key of a
+/// command is shown in the help line of the page.
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::fs;
+use std::io::ErrorKind;
+
+use crate::models::{Action, FilterChange, Table};
+
+use super::{command_palette, Page};
+
+/// A command of a page, e.g. `[u] update epic`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Command {
+    Quit,
+    Previous,
+    CreateProject,
+    AllEpics,
+    CreateEpic,
+    Backlog,
+    Upcoming,
+    Releases,
+    Projects,
+    Archive,
+    Trash,
+    UpdateEpic,
+    SetDueDate,
+    ToggleDerivedStatus,
+    ToggleArchived,
+    DeleteEpic,
+    CreateStory,
+    UpdateStory,
+    ChangeType,
+    FixVersion,
+    Assign,
+    DeleteStory,
+    Estimate,
+    LogWork,
+    ToggleTimer,
+    MoveUp,
+    MoveDown,
+    MoveToTop,
+    MoveToBottom,
+    CreateVersion,
+    MarkAsReleased,
+    Restore,
+    NextPage,
+    PreviousPage,
+    Sort,
+    ReverseSort,
+    HideDone,
+    StatusFilter,
+    OnlyMine,
+    Home,
+    Forward,
+    Recent,
+    Help,
+}
+
+/// All commands with their name in the config file, their default key and description
+const COMMANDS: [(Command, &str, &str, &str); 43] = [
+    (Command::Quit, "quit", "q", "quit"),
+    (Command::Previous, "previous", "p", "previous"),
+    (
+        Command::CreateProject,
+        "create-project",
+        "c",
+        "create project",
+    ),
+    (Command::AllEpics, "all-epics", "a", "all epics"),
+    (Command::CreateEpic, "create-epic", "c", "create epic"),
+    (Command::Backlog, "backlog", "b", "backlog"),
+    (Command::Upcoming, "upcoming", "u", "upcoming"),
+    (Command::Releases, "releases", "r", "releases"),
+    (Command::Projects, "projects", "j", "projects"),
+    (Command::Archive, "archive", "a", "archive"),
+    (Command::Trash, "trash", "t", "trash"),
+    (Command::UpdateEpic, "update-epic", "u", "update epic"),
+    (Command::SetDueDate, "due-date", "t", "set due date"),
+    (
+        Command::ToggleDerivedStatus,
+        "derived-status",
+        "a",
+        "toggle derived status",
+    ),
+    (
+        Command::ToggleArchived,
+        "archive-epic",
+        "x",
+        "archive/unarchive",
+    ),
+    (Command::DeleteEpic, "delete-epic", "d", "delete epic"),
+    (Command::CreateStory, "create-story", "c", "create story"),
+    (Command::UpdateStory, "update-story", "u", "update story"),
+    (Command::ChangeType, "change-type", "y", "change type"),
+    (Command::FixVersion, "fix-version", "f", "fix version"),
+    (Command::Assign, "assign", "g", "assign to me/unassign"),
+    (Command::DeleteStory, "delete-story", "d", "delete story"),
+    (Command::Estimate, "estimate", "e", "estimate"),
+    (Command::LogWork, "log-work", "w", "log work"),
+    (Command::ToggleTimer, "timer", "s", "start/stop timer"),
+    (Command::MoveUp, "move-up", "u", "move up"),
+    (Command::MoveDown, "move-down", "d", "move down"),
+    (Command::MoveToTop, "move-to-top", "t", "move to top"),
+    (
+        Command::MoveToBottom,
+        "move-to-bottom",
+        "b",
+        "move to bottom",
+    ),
+    (
+        Command::CreateVersion,
+        "create-version",
+        "c",
+        "create version",
+    ),
+    (
+        Command::MarkAsReleased,
+        "mark-as-released",
+        "r",
+        "mark as released",
+    ),
+    (Command::Restore, "restore", "r", "restore item"),
+    (Command::NextPage, "next-page", ">", "next page"),
+    (Command::PreviousPage, "previous-page", "<", "previous page"),
+    (Command::Sort, "cycle-sort", "o", "sort by next column"),
+    (
+        Command::ReverseSort,
+        "reverse-sort",
+        "i",
+        "reverse sort order",
+    ),
+    (Command::HideDone, "hide-done", "z", "hide/show resolved"),
+    (
+        Command::StatusFilter,
+        "cycle-status",
+        "l",
+        "filter by status",
+    ),
+    (Command::OnlyMine, "only-mine", "m", "only mine/everyone's"),
+    (Command::Home, "home", "h", "home"),
+    (Command::Forward, "forward", "n", "forward"),
+    (Command::Recent, "recent", "v", "recently visited"),
+    (Command::Help, "help", "?", "help"),
+];
+
+impl Command {
+    fn entry(self) -> (Command, &'static str, &'static str, &'static str) {
+        *COMMANDS
+            .iter()
+            .find(|(command, ..)| *command == self)
+            .expect("every command is listed")
+    }
+
+    /// The name of the command in the config file, e.g. `update-epic`
+    pub fn name(self) -> &'static str {
+        self.entry().1
+    }
+
+    pub fn description(self) -> &'static str {
+        self.entry().3
+    }
+
+    pub fn from_name(name: &str) -> Option<Command> {
+        COMMANDS
+            .iter()
+            .find(|(_, command_name, ..)| *command_name == name)
+            .map(|(command, ..)| *command)
+    }
+
+    /// The action of a command which works the same on every page, e.g. help
+    pub fn global_action(self) -> Option<Action> {
+        match self {
+            Command::NextPage => Some(Action::ScrollList { pages: 1 }),
+            Command::PreviousPage => Some(Action::ScrollList { pages: -1 }),
+            Command::Home => Some(Action::NavigateToHome),
+            Command::Forward => Some(Action::NavigateForward),
+            Command::Recent => Some(Action::NavigateToRecent),
+            Command::HideDone => Some(Action::ChangeFilter {
+                change: FilterChange::ToggleHideDone,
+            }),
+            Command::StatusFilter => Some(Action::ChangeFilter {
+                change: FilterChange::NextStatus,
+            }),
+            Command::OnlyMine => Some(Action::ChangeFilter {
+                change: FilterChange::ToggleOnlyMine,
+            }),
+            Command::Help => Some(Action::NavigateToHelp),
+            _ => None,
+        }
+    }
+
+    /// Commands which are followed by the id of an item, e.g. `u 12` to move story 12 up
+    pub fn takes_id(self) -> bool {
+        matches!(
+            self,
+            Command::MoveUp
+                | Command::MoveDown
+                | Command::MoveToTop
+                | Command::MoveToBottom
+                | Command::Restore
+        )
+    }
+}
+
+/// The kinds of pages, each with its own commands
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PageKind {
+    Projects,
+    Home,
+    EpicDetail,
+    StoryDetail,
+    Backlog,
+    Upcoming,
+    Releases,
+    Release,
+    Trash,
+    Archive,
+    Recent,
+    Help,
+}
+
+const PAGE_KINDS: [PageKind; 12] = [
+    PageKind::Projects,
+    PageKind::Home,
+    PageKind::EpicDetail,
+    PageKind::StoryDetail,
+    PageKind::Backlog,
+    PageKind::Upcoming,
+    PageKind::Releases,
+    PageKind::Release,
+    PageKind::Trash,
+    PageKind::Archive,
+    PageKind::Recent,
+    PageKind::Help,
+];
+
+impl PageKind {
+    /// The commands of the page in the order of the help line
+    pub fn commands(self) -> &'static [Command] {
+        use Command::*;
+
+        match self {
+            PageKind::Projects => &[Quit, CreateProject, AllEpics, Home, Forward, Recent, Help],
+            PageKind::Home => &[
+                Quit,
+                CreateEpic,
+                Backlog,
+                Upcoming,
+                Releases,
+                Projects,
+                Archive,
+                Trash,
+                NextPage,
+                PreviousPage,
+                Sort,
+                ReverseSort,
+                HideDone,
+                StatusFilter,
+                OnlyMine,
+                Home,
+                Forward,
+                Recent,
+                Help,
+            ],
+            PageKind::EpicDetail => &[
+                Previous,
+                UpdateEpic,
+                ToggleDerivedStatus,
+                SetDueDate,
+                ToggleArchived,
+                DeleteEpic,
+                CreateStory,
+                NextPage,
+                PreviousPage,
+                Sort,
+                ReverseSort,
+                HideDone,
+                StatusFilter,
+                OnlyMine,
+                Home,
+                Forward,
+                Recent,
+                Help,
+            ],
+            PageKind::StoryDetail => &[
+                Previous,
+                UpdateStory,
+                ChangeType,
+                FixVersion,
+                Assign,
+                SetDueDate,
+                DeleteStory,
+                Estimate,
+                LogWork,
+                ToggleTimer,
+                Home,
+                Forward,
+                Recent,
+                Help,
+            ],
+            PageKind::Backlog => &[
+                Previous,
+                MoveUp,
+                MoveDown,
+                MoveToTop,
+                MoveToBottom,
+                Home,
+                Forward,
+                Recent,
+                Help,
+            ],
+            PageKind::Upcoming | PageKind::Archive => &[Previous, Home, Forward, Recent, Help],
+            PageKind::Releases => &[Previous, CreateVersion, Home, Forward, Recent, Help],
+            PageKind::Release => &[Previous, MarkAsReleased, Home, Forward, Recent, Help],
+            PageKind::Trash => &[Previous, Restore, Home, Forward, Recent, Help],
+            PageKind::Recent => &[Previous, Home, Forward, Help],
+            PageKind::Help => &[Previous, Home],
+        }
+    }
+
+    /// The name of the page, e.g. in the title of the help page
+    pub fn title(self) -> &'static str {
+        match self {
+            PageKind::Projects => "projects",
+            PageKind::Home => "epics",
+            PageKind::EpicDetail => "epic",
+            PageKind::StoryDetail => "story",
+            PageKind::Backlog => "backlog",
+            PageKind::Upcoming => "upcoming",
+            PageKind::Releases => "releases",
+            PageKind::Release => "release",
+            PageKind::Trash => "trash",
+            PageKind::Archive => "archive",
+            PageKind::Recent => "recent",
+            PageKind::Help => "help",
+        }
+    }
+
+    /// The table of the page whose sorting and columns can be changed
+    pub fn table(self) -> Option<Table> {
+        match self {
+            PageKind::Home => Some(Table::Epics),
+            PageKind::EpicDetail => Some(Table::Stories),
+            _ => None,
+        }
+    }
+
+    /// What else can be typed on the page, e.g. the id of a story
+    pub fn hint(self) -> Option<&'static str> {
+        match self {
+            PageKind::Projects => Some("[:key:] navigate to project"),
+            PageKind::Home | PageKind::Archive => Some("[:id:] navigate to epic"),
+            PageKind::EpicDetail | PageKind::Backlog | PageKind::Release => {
+                Some("[:id:] navigate to story")
+            }
+            PageKind::Upcoming | PageKind::Recent => Some("[:id:] navigate to epic or story"),
+            PageKind::Releases => Some("[:name:] navigate to release"),
+            PageKind::StoryDetail | PageKind::Trash | PageKind::Help => None,
+        }
+    }
+}
+
+/// A key bound to two commands of the same page
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Conflict {
+    pub page: PageKind,
+    pub key: String,
+    pub commands: (Command, Command),
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "key '{}' is bound to '{}' and '{}' on the {:?} page",
+            self.key,
+            self.commands.0.name(),
+            self.commands.1.name(),
+            self.page
+        )
+    }
+}
+
+/// The keys of all commands
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Keymap {
+    keys: HashMap<Command, Vec<String>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            keys: COMMANDS
+                .iter()
+                .map(|(command, _, key, _)| (*command, vec![key.to_string()]))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Loads the keymap from a JSON config file, the commands which are not configured keep
+    /// their default keys. The default keymap is used when the file does not exist.
+    pub fn load(file_path: &str) -> Result<Keymap> {
+        match fs::read_to_string(file_path) {
+            Ok(content) => Keymap::from_json(&content)
+                .map_err(|error| anyhow!("Invalid keymap {file_path}: {error}")),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Keymap::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn from_json(content: &str) -> Result<Keymap> {
+        let config: BTreeMap<String, Vec<String>> = serde_json::from_str(content)?;
+
+        let mut keymap = Keymap::default();
+        for (name, keys) in config {
+            let command =
+                Command::from_name(&name).ok_or_else(|| anyhow!("unknown command '{name}'"))?;
+            keymap.bind(command, keys)?;
+        }
+
+        let conflicts = keymap.conflicts();
+        if !conflicts.is_empty() {
+            return Err(anyhow!(
+                "{}",
+                conflicts
+                    .iter()
+                    .map(|conflict| conflict.to_string())
+                    .join("; ")
+            ));
+        }
+        Ok(keymap)
+    }
+
+    /// Replaces the keys of the command, the first key is shown in the help line.
+    pub fn bind(&mut self, command: Command, keys: Vec<String>) -> Result<()> {
+        if keys.is_empty() {
+            return Err(anyhow!("no key for command '{}'", command.name()));
+        }
+        for key in &keys {
+            // numbers are ids, a space separates a command from its id and `:` opens the
+            // command palette
+            if key.is_empty()
+                || key.contains(char::is_whitespace)
+                || key.starts_with(':')
+                || key.parse::<u32>().is_ok()
+            {
+                return Err(anyhow!(
+                    "invalid key '{key}' for command '{}'",
+                    command.name()
+                ));
+            }
+        }
+        self.keys.insert(command, keys);
+        Ok(())
+    }
+
+    pub fn keys(&self, command: Command) -> &[String] {
+        self.keys.get(&command).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// All keys bound to more than one command of a page
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = vec![];
+        for page in PAGE_KINDS {
+            let mut bound: HashMap<&str, Command> = HashMap::new();
+            for &command in page.commands() {
+                for key in self.keys(command) {
+                    match bound.get(key.as_str()) {
+                        Some(&other) if other != command => conflicts.push(Conflict {
+                            page,
+                            key: key.clone(),
+                            commands: (other, command),
+                        }),
+                        _ => {
+                            bound.insert(key, command);
+                        }
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// The command of the page bound to the input and the id following it, e.g. `u 12`.
+    pub fn command<'a>(
+        &self,
+        page: PageKind,
+        input: &'a str,
+    ) -> Option<(Command, Option<&'a str>)> {
+        let (key, id) = match input.split_once(' ') {
+            Some((key, id)) => (key, Some(id.trim())),
+            None => (input, None),
+        };
+
+        page.commands()
+            .iter()
+            .find(|&&command| self.keys(command).iter().any(|bound| bound == key))
+            .filter(|command| command.takes_id() == id.is_some())
+            .map(|&command| (command, id))
+    }
+
+    /// The keys of the commands of the page which start with the typed keys, e.g. `back`
+    /// for `ba`, so the TUI knows whether to wait for more keys
+    pub fn keys_starting_with<'a>(
+        &'a self,
+        page: PageKind,
+        typed: &'a str,
+    ) -> impl Iterator<Item = &'a str> {
+        page.commands()
+            .iter()
+            .flat_map(|&command| self.keys(command))
+            .map(String::as_str)
+            .filter(move |key| key.starts_with(typed))
+    }
+
+    /// Passes the input to the page, as a command if a key of the page is typed. Input
+    /// starting with `:` is a command of the command palette, e.g. `:status resolved`.
+    pub fn handle_input(&self, page: &dyn Page, input: &str) -> Result<Option<Action>> {
+        if let Some(line) = input.strip_prefix(':') {
+            return command_palette::handle_input(page, line);
+        }
+
+        match self.command(page.kind(), input) {
+            Some((command, id)) => match command.global_action() {
+                Some(action) => Ok(Some(action)),
+                None => page.handle_command(command, id),
+            },
+            None if input.is_empty() => Ok(None),
+            None => page.handle_other_input(input),
+        }
+    }
+
+    /// The help line of the page, e.g. `[p] previous | [u] update epic`, followed by the hint
+    /// for the other input like `[:id:] navigate to story`
+    pub fn help(&self, page: PageKind) -> Vec<String> {
+        page.commands()
+            .iter()
+            .map(|&command| {
+                let key = self.keys(command).first().map(String::as_str).unwrap_or("");
+                match command.takes_id() {
+                    true => format!("[{key} :id:] {}", command.description()),
+                    false => format!("[{key}] {}", command.description()),
+                }
+            })
+            .chain(page.hint().map(str::to_owned))
+            .collect()
+    }
+
+    /// All bindings by page, e.g. for `my-jira keys`
+    pub fn describe(&self) -> String {
+        PAGE_KINDS
+            .iter()
+            .map(|&page| {
+                let bindings = page
+                    .commands()
+                    .iter()
+                    .map(|&command| {
+                        format!(
+                            "  {:<22}{:<24}{}",
+                            command.name(),
+                            command.description(),
+                            self.keys(command).join(", ")
+                        )
+                    })
+                    .join("\n");
+                format!("{page:?}:\n{bindings}")
+            })
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_command_should_have_unique_name() {
+        let names = COMMANDS.iter().map(|(_, name, ..)| name).unique().count();
+        assert_eq!(names, COMMANDS.len());
+        assert_eq!(Command::from_name("update-epic"), Some(Command::UpdateEpic));
+        assert_eq!(Command::UpdateEpic.name(), "update-epic");
+    }
+
+    #[test]
+    fn default_keymap_should_not_have_conflicts() {
+        assert_eq!(Keymap::default().conflicts(), vec![]);
+    }
+
+    #[test]
+    fn command_should_match_keys_of_page() {
+        let keymap = Keymap::default();
+
+        assert_eq!(
+            keymap.command(PageKind::Home, "u"),
+            Some((Command::Upcoming, None))
+        );
+        assert_eq!(
+            keymap.command(PageKind::EpicDetail, "u"),
+            Some((Command::UpdateEpic, None))
+        );
+        assert_eq!(
+            keymap.command(PageKind::Backlog, "u  12 "),
+            Some((Command::MoveUp, Some("12")))
+        );
+        // moving a story needs an id, the other commands don't take one
+        assert_eq!(keymap.command(PageKind::Backlog, "u"), None);
+        assert_eq!(keymap.command(PageKind::EpicDetail, "u 12"), None);
+        assert_eq!(keymap.command(PageKind::EpicDetail, "12"), None);
+    }
+
+    #[test]
+    fn from_json_should_support_aliases_and_multi_character_keys() {
+        let keymap = Keymap::from_json(r#"{"previous": ["back", "p"], "quit": ["exit"]}"#).unwrap();
+
+        assert_eq!(
+            keymap.command(PageKind::StoryDetail, "back"),
+            Some((Command::Previous, None))
+        );
+        assert_eq!(
+            keymap.command(PageKind::StoryDetail, "p"),
+            Some((Command::Previous, None))
+        );
+        assert_eq!(keymap.command(PageKind::Home, "q"), None);
+        assert_eq!(
+            keymap.command(PageKind::Home, "exit"),
+            Some((Command::Quit, None))
+        );
+        assert_eq!(
+            keymap.help(PageKind::Trash)[0],
+            "[back] previous".to_owned()
+        );
+    }
+
+    #[test]
+    fn from_json_should_reject_conflicts_and_invalid_keys() {
+        let error = Keymap::from_json(r#"{"update-epic": ["d"]}"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "key 'd' is bound to 'update-epic' and 'delete-epic' on the EpicDetail page".to_owned()
+        );
+
+        // the same key on different pages is fine
+        assert_eq!(Keymap::from_json(r#"{"trash": ["x"]}"#).is_ok(), true);
+
+        assert_eq!(Keymap::from_json(r#"{"teleport": ["t"]}"#).is_err(), true);
+        assert_eq!(Keymap::from_json(r#"{"quit": []}"#).is_err(), true);
+        assert_eq!(Keymap::from_json(r#"{"quit": ["12"]}"#).is_err(), true);
+        assert_eq!(Keymap::from_json(r#"{"quit": ["q q"]}"#).is_err(), true);
+        assert_eq!(Keymap::from_json(r#"{"quit": [":q"]}"#).is_err(), true);
+        assert_eq!(Keymap::from_json("[]").is_err(), true);
+    }
+
+    #[test]
+    fn load_should_use_default_keymap_without_file() {
+        assert_eq!(
+            Keymap::load("./data/no-such-keymap.json").unwrap(),
+            Keymap::default()
+        );
+    }
+
+    #[test]
+    fn help_should_show_first_key_and_hint() {
+        let keymap = Keymap::default();
+
+        assert_eq!(
+            keymap.help(PageKind::Backlog),
+            vec![
+                "[p] previous".to_owned(),
+                "[u :id:] move up".to_owned(),
+                "[d :id:] move down".to_owned(),
+                "[t :id:] move to top".to_owned(),
+                "[b :id:] move to bottom".to_owned(),
+                "[h] home".to_owned(),
+                "[n] forward".to_owned(),
+                "[v] recently visited".to_owned(),
+                "[?] help".to_owned(),
+                "[:id:] navigate to story".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn describe_should_list_all_keys_by_page() {
+        let keymap = Keymap::from_json(r#"{"previous": ["p", "back"]}"#).unwrap();
+        let description = keymap.describe();
+
+        assert_eq!(description.starts_with("Projects:\n"), true);
+        assert_eq!(
+            description
+                .lines()
+                .any(|line| line == format!("  {:<22}{:<24}p, back", "previous", "previous")),
+            true
+        );
+    }
+}
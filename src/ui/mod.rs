@@ -1,6 +1,12 @@
 /// This modules glues the underlaying modules
+mod command_palette;
+mod keymap;
 mod pages;
 mod prompts;
+mod theme;
 
+pub use command_palette::complete;
+pub use keymap::{Command, Keymap, PageKind};
 pub use pages::*;
 pub use prompts::*;
+pub use theme::{ColorSupport, Palette, Theme};
@@ -1,114 +1,438 @@
 ///
 /// Notes:
 /// Refactor: duplicate code in handle inputs.
-/// Refactor: tests for screen output, they don't generate error when not doing anything.
 mod page_helpers;
+mod pagination;
+mod screen;
+mod table;
 
+use crate::dates::{format_duration, now, today, total_duration};
 use crate::db::JiraDatabase;
-use crate::io_utils::{DFT, GREEN, RED};
-use crate::models::Action;
+use crate::io_utils::current_user_name;
+use crate::models::{
+    Action, DBState, Filter, RankMove, SortChange, StatusCounts, Table, TrashedItem, UserSettings,
+    ID,
+};
+use crate::ui::{command_palette, Command, PageKind, Palette};
 use anyhow::anyhow;
 use anyhow::Result;
 use itertools::Itertools;
-use page_helpers::get_column_string;
+use page_helpers::{get_column_string, get_date_string, get_progress_string};
+pub use pagination::Pagination;
+pub use screen::Screen;
 use std::any::Any;
+use std::io::Write;
 use std::rc::Rc;
+use table::{Layout, Row};
+use unicode_width::UnicodeWidthStr;
 
-const HEADER_COLOR: &str = GREEN;
-const QUERY_COLOR: &str = RED;
-
-const TERMINAL_WIDTH: usize = 100;
-const ID_WIDTH: usize = 8;
+const ID_WIDTH: usize = 10;
 const NAME_WIDTH: usize = 20;
 const STATUS_WIDTH: usize = 12;
+const DUE_WIDTH: usize = 10;
+const DATE_TIME_WIDTH: usize = 16;
+const DURATION_WIDTH: usize = 10;
+const PROGRESS_WIDTH: usize = 20;
+const COMMAND_WIDTH: usize = 22;
 
 pub trait Page {
-    fn draw_page(&self) -> Result<()>;
-    fn handle_input(&self, input: &str) -> Result<Option<Action>>;
+    /// Draws the page, e.g. to stdout or to a buffer in the tests
+    fn draw_page(&self, screen: &mut Screen) -> Result<()>;
+    /// The kind of the page, which decides its commands
+    fn kind(&self) -> PageKind;
+    /// Handles a command of the page, `id` is the id typed after the key of commands like `u 12`.
+    fn handle_command(&self, command: Command, id: Option<&str>) -> Result<Option<Action>>;
+    /// Handles an input which is not a command of the page, e.g. the id of an item
+    fn handle_other_input(&self, _input: &str) -> Result<Option<Action>> {
+        Ok(None)
+    }
+    /// The name of the page in the breadcrumbs above the page, e.g. `Epic 6 Bugfixes`
+    fn breadcrumb(&self) -> String {
+        let title = self.kind().title();
+        title[..1].to_uppercase() + &title[1..]
+    }
+    /// Shows the next or previous pages of a long table, see `Pagination`
+    fn scroll(&self, _pages: isize) {}
+    /// The id of the epic or story shown, which the commands of the command palette change
+    fn item_id(&self) -> Option<ID> {
+        None
+    }
+    /// The ids of the table rows which can be selected in the TUI, in the order they are drawn.
+    /// Selecting a row passes its id to `handle_input`.
+    fn selectable_ids(&self) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+    #[allow(dead_code)]
     fn as_any(&self) -> &dyn Any;
 }
 
+pub struct ProjectsPage {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for ProjectsPage {
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette { header, reset, .. } = screen.palette.clone();
+        let terminal_width = screen.width;
+        let name_width: usize = terminal_width - ID_WIDTH - ID_WIDTH - 3;
+
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            " PROJECTS ",
+            width = terminal_width
+        )?;
+        writeln!(
+            screen,
+            "{: ^id_width$}|{: ^name_width$}| {: ^epics_width$}{dft}",
+            "key",
+            "name",
+            "epics",
+            id_width = ID_WIDTH,
+            name_width = name_width,
+            epics_width = ID_WIDTH,
+            dft = reset
+        )?;
+
+        // print out the projects sorted by key
+        let db_state = self.db.read_db()?;
+        for project in db_state.projects.iter().sorted_by_key(|p| &p.key) {
+            let epic_count = db_state
+                .epics
+                .values()
+                .filter(|epic| epic.project.as_ref() == Some(&project.key))
+                .count();
+            writeln!(
+                screen,
+                "{:<id_width$}{header}|{reset}{}{header}|{reset} {}",
+                project.key,
+                get_column_string(&project.name, name_width),
+                epic_count,
+                id_width = ID_WIDTH,
+            )?;
+        }
+
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::Projects
+    }
+
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
+        match command {
+            Command::Quit => Ok(Some(Action::Exit)),
+            Command::CreateProject => Ok(Some(Action::CreateProject)),
+            Command::AllEpics => Ok(Some(Action::NavigateToProject { project_key: None })),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+
+        Ok(db_state
+            .projects
+            .iter()
+            .find(|project| project.key.eq_ignore_ascii_case(input))
+            .map(|project| Action::NavigateToProject {
+                project_key: Some(project.key.clone()),
+            })
+            .or_else(|| go_to(&db_state, input)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Goes to the epic or story with the id or key typed on any page, `None` if there is none
+fn go_to(db_state: &DBState, input: &str) -> Option<Action> {
+    let id = db_state.resolve_id(input)?;
+    let exists = db_state.epics.contains_key(&id) || db_state.epic_id_of_story(id).is_some();
+    exists.then(|| Action::GoTo {
+        id: input.to_owned(),
+    })
+}
+
 pub struct HomePage {
     pub db: Rc<JiraDatabase>,
+    /// key of the project whose epics are shown, `None` shows all epics
+    pub project: Option<String>,
+    pub pagination: Pagination,
+}
+
+/// Joins the breadcrumbs, the first ones are left out if the line is wider than the screen
+fn breadcrumb_line(breadcrumbs: &[String], width: usize) -> String {
+    (0..breadcrumbs.len())
+        .map(|start| match start {
+            0 => breadcrumbs.join(" > "),
+            _ => format!("… > {}", breadcrumbs[start..].join(" > ")),
+        })
+        .find(|line| line.width() <= width)
+        .unwrap_or_else(|| get_column_string(breadcrumbs.last().unwrap(), width))
+}
+
+/// Draws the header shown above every page, the breadcrumbs of the pages on the way to the page
+/// and e.g. the running timer
+pub fn draw_header(db: &JiraDatabase, screen: &mut Screen, breadcrumbs: &[String]) -> Result<()> {
+    let Palette { query, reset, .. } = screen.palette.clone();
+    let db_state = db.read_db()?;
+
+    if !breadcrumbs.is_empty() {
+        let line = breadcrumb_line(breadcrumbs, screen.width);
+        writeln!(screen, "{query}{line}{reset}")?;
+    }
+
+    if let Some(timer) = &db_state.active_timer {
+        let story_name = db_state
+            .stories
+            .get(&timer.story_id)
+            .map(|story| story.name.as_str())
+            .unwrap_or("");
+        writeln!(
+            screen,
+            "{query}timer running: story {} {} - {} ({}){reset}",
+            db_state.display_id(timer.story_id),
+            story_name,
+            format_duration(timer.elapsed(now())),
+            timer.author,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The colour of a table row, overdue items are highlighted and the selected row is reversed
+fn get_row_color(palette: &Palette, is_overdue: bool, is_selected: bool) -> String {
+    let color = if is_overdue {
+        &palette.overdue
+    } else {
+        &palette.reset
+    };
+    match is_selected {
+        true => format!("{color}{}", palette.selected),
+        false => color.to_string(),
+    }
 }
 
-fn print_query(text: &str) -> Result<()> {
-    let text = text.to_string();
+/// Prints the commands of the page with their keys, wrapped at the width of the screen
+fn print_help(screen: &mut Screen, kind: PageKind) -> Result<()> {
+    let mut lines: Vec<String> = vec![];
+    for item in screen.keymap.help(kind) {
+        match lines.last_mut() {
+            Some(line) if line.width() + " | ".len() + item.width() <= screen.width => {
+                line.push_str(" | ");
+                line.push_str(&item);
+            }
+            _ => lines.push(item),
+        }
+    }
 
-    let before: String = format!("[{QUERY_COLOR}");
-    let after = format!("{DFT}]");
+    for line in lines {
+        print_query(screen, &line)?;
+    }
+    Ok(())
+}
 
-    let text = text.replace('[', &before);
-    let text = text.replace(']', &after);
+fn print_query(screen: &mut Screen, text: &str) -> Result<()> {
+    let text = screen.palette.highlight_keys(text);
 
-    println!("{text}");
+    writeln!(screen, "{text}")?;
 
     Ok(())
 }
 
+/// The settings of the current user, e.g. how the tables are sorted and filtered
+fn user_settings(db_state: &DBState) -> UserSettings {
+    db_state.settings_of(&current_user_name())
+}
+
+/// Whether the story is assigned to the user
+fn is_assigned(db_state: &DBState, story_id: ID, user: &str) -> bool {
+    db_state
+        .stories
+        .get(&story_id)
+        .is_some_and(|story| story.assignee.as_deref() == Some(user))
+}
+
+/// The title of a table, with the filter if it hides items
+fn table_title(title: &str, filter: &Filter) -> String {
+    match filter.describe() {
+        Some(filter) => format!(" {title} [filter: {filter}] "),
+        None => format!(" {title} "),
+    }
+}
+
+impl HomePage {
+    /// The rows of the epics shown on the page, filtered and sorted like the settings say
+    fn epic_rows(&self, db_state: &DBState, settings: &UserSettings) -> Vec<Row> {
+        let user = current_user_name();
+        let epic_ids = db_state
+            .epics
+            .iter()
+            .filter(|(_, epic)| !epic.archived)
+            .filter(|(_, epic)| self.project.is_none() || epic.project == self.project)
+            .filter(|(_, epic)| {
+                // epics are mine if one of their stories is
+                settings.filter.matches(&epic.status, || {
+                    epic.stories
+                        .iter()
+                        .any(|&story_id| is_assigned(db_state, story_id, &user))
+                })
+            })
+            .map(|(&id, _)| id)
+            .sorted()
+            .collect::<Vec<_>>();
+        let mut rows = table::epic_rows(db_state, &epic_ids, today());
+        table::sort(&mut rows, &settings.table(Table::Epics));
+        rows
+    }
+}
+
 impl Page for HomePage {
-    fn draw_page(&self) -> Result<()> {
-        let name_width: usize = TERMINAL_WIDTH - ID_WIDTH - STATUS_WIDTH - 3;
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette {
+            header,
+            query,
+            reset,
+            ..
+        } = screen.palette.clone();
+        let terminal_width = screen.width;
 
-        println!(
+        let db_state = self.db.database.read_db()?;
+        let settings = user_settings(&db_state);
+        let layout = Layout::new(&settings.table(Table::Epics), terminal_width);
+        let title = match db_state
+            .projects
+            .iter()
+            .find(|project| Some(&project.key) == self.project.as_ref())
+        {
+            Some(project) => format!("EPICS - {} {}", project.key, project.name),
+            None => "EPICS".to_string(),
+        };
+        let title = table_title(&title, &settings.filter);
+
+        writeln!(
+            screen,
             "{}{:-^width$}",
-            HEADER_COLOR,
-            " EPICS ",
-            width = TERMINAL_WIDTH
-        );
-        println!(
-            "{: ^id_width$}|{: ^name_width$}| {: ^status_width$}{dft}",
-            "id",
-            "name",
-            "status",
-            id_width = ID_WIDTH,
-            name_width = name_width,
-            status_width = STATUS_WIDTH,
-            dft = DFT
-        );
+            header,
+            title,
+            width = terminal_width
+        )?;
+        layout.write_header(screen)?;
+
+        let rows = self.epic_rows(&db_state, &settings);
+        let epic_ids = rows.iter().map(|row| row.id).collect::<Vec<_>>();
+
+        // the summary is about all epics, not only the ones on the page
+        let mut summary = StatusCounts::default();
+        let mut finished_epics = vec![];
+
+        for &id in &epic_ids {
+            let counts = db_state.epic_status_counts(id);
+            summary.open += counts.open;
+            summary.in_progress += counts.in_progress;
+            summary.resolved += counts.resolved;
+            summary.closed += counts.closed;
+
+            if counts.is_complete() && db_state.epics[&id].status.is_unresolved() {
+                finished_epics.push(db_state.display_id(id));
+            }
+        }
 
-        // print out epics using get_column_string(). also make sure the epics are sorted by id
-        let db_state = self.db.database.read_db()?;
-        let epics_iter = db_state.epics.iter().sorted_by_key(|x| x.0);
+        for row in self.pagination.rows(&rows, screen.page_size) {
+            layout.write_row(screen, row)?;
+        }
 
-        for (&id, epic) in epics_iter {
-            println!(
-                "{:<id_width$}{HEADER_COLOR}|{DFT}{}{HEADER_COLOR}|{DFT} {}",
-                id,
-                get_column_string(&epic.name, name_width),
-                get_column_string(format!("{}", epic.status).as_str(), STATUS_WIDTH),
-                id_width = ID_WIDTH,
-            );
+        if let Some(indicator) = self.pagination.indicator(rows.len()) {
+            writeln!(screen, "{query}{indicator}{reset}")?;
         }
 
-        println!();
-        println!();
+        writeln!(screen)?;
+        writeln!(
+            screen,
+            "stories: {} open | {} in progress | {} resolved | {} closed",
+            summary.open, summary.in_progress, summary.resolved, summary.closed
+        )?;
+
+        // all stories of these epics are done, so they can probably be resolved
+        if !finished_epics.is_empty() {
+            writeln!(
+                screen,
+                "{query}all stories done, consider resolving epic(s): {}{reset}",
+                finished_epics.join(", ")
+            )?;
+        }
+
+        writeln!(screen)?;
 
-        print_query("[q] quit | [c] create epic | [:id:] navigate to epic")
+        print_help(screen, self.kind())
     }
 
-    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
-        // match against the user input and return the corresponding action. If the user input was invalid return None.
-        match input {
-            "" => Ok(None),
-            "q" => Ok(Some(Action::Exit)),
-            "c" => Ok(Some(Action::CreateEpic)),
-            _ => {
-                if input.parse::<u32>().is_err() {
-                    return Ok(None);
-                }
+    fn kind(&self) -> PageKind {
+        PageKind::Home
+    }
 
-                let epic_id = input.parse::<u32>()?;
-                let db_state = self.db.database.read_db()?;
+    fn breadcrumb(&self) -> String {
+        self.project.clone().unwrap_or_else(|| "Home".to_owned())
+    }
 
-                if db_state.epics.get(&epic_id).is_none() {
-                    return Ok(None);
-                }
-                Ok(Some(Action::NavigateToEpicDetail { epic_id }))
-            }
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
+        match command {
+            Command::Quit => Ok(Some(Action::Exit)),
+            Command::CreateEpic => Ok(Some(Action::CreateEpic {
+                project_key: self.project.clone(),
+            })),
+            Command::Backlog => Ok(Some(Action::NavigateToBacklog)),
+            Command::Upcoming => Ok(Some(Action::NavigateToUpcoming)),
+            Command::Projects => Ok(Some(Action::NavigateToProjects)),
+            Command::Releases => Ok(Some(Action::NavigateToReleases)),
+            Command::Archive => Ok(Some(Action::NavigateToArchive)),
+            Command::Trash => Ok(Some(Action::NavigateToTrash)),
+            Command::Sort => Ok(Some(Action::SortTable {
+                table: Table::Epics,
+                change: SortChange::NextKey,
+            })),
+            Command::ReverseSort => Ok(Some(Action::SortTable {
+                table: Table::Epics,
+                change: SortChange::Reverse,
+            })),
+            _ => Ok(None),
         }
     }
 
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.database.read_db()?;
+
+        let Some(epic_id) = db_state.resolve_id(input) else {
+            return Ok(None);
+        };
+        if !db_state.epics.contains_key(&epic_id) {
+            return Ok(go_to(&db_state, input));
+        }
+        Ok(Some(Action::NavigateToEpicDetail { epic_id }))
+    }
+
+    fn scroll(&self, pages: isize) {
+        self.pagination.scroll(pages);
+    }
+
+    fn selectable_ids(&self) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        let rows = self.epic_rows(&db_state, &user_settings(&db_state));
+        Ok(self
+            .pagination
+            .visible_rows(&rows)
+            .iter()
+            .map(|row| db_state.display_id(row.id))
+            .collect())
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -117,114 +441,241 @@ impl Page for HomePage {
 pub struct EpicDetail {
     pub epic_id: u32,
     pub db: Rc<JiraDatabase>,
+    pub pagination: Pagination,
+}
+
+impl EpicDetail {
+    /// The rows of the stories of the epic, filtered and sorted like the settings say
+    fn story_rows(&self, db_state: &DBState, settings: &UserSettings) -> Result<Vec<Row>> {
+        let user = current_user_name();
+        let epic = db_state
+            .epics
+            .get(&self.epic_id)
+            .ok_or_else(|| anyhow!("could not find epic!"))?;
+        let story_ids = epic
+            .stories
+            .iter()
+            .filter(|id| {
+                db_state.stories.get(id).is_some_and(|story| {
+                    settings
+                        .filter
+                        .matches(&story.status, || is_assigned(db_state, **id, &user))
+                })
+            })
+            .sorted()
+            .copied()
+            .collect::<Vec<_>>();
+        let mut rows = table::story_rows(db_state, &story_ids, today());
+        table::sort(&mut rows, &settings.table(Table::Stories));
+        Ok(rows)
+    }
 }
 
 impl Page for EpicDetail {
-    fn draw_page(&self) -> Result<()> {
-        let name_width: usize = TERMINAL_WIDTH - ID_WIDTH - STATUS_WIDTH - 3;
-        let description_width = TERMINAL_WIDTH - ID_WIDTH - STATUS_WIDTH - NAME_WIDTH - 4;
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette {
+            header,
+            query,
+            overdue,
+            reset,
+            ..
+        } = screen.palette.clone();
+        let terminal_width = screen.width;
+        let description_width =
+            terminal_width - ID_WIDTH - STATUS_WIDTH - NAME_WIDTH - DUE_WIDTH - 6;
 
         let db_state = self.db.read_db()?;
         let epic = db_state
             .epics
             .get(&self.epic_id)
             .ok_or_else(|| anyhow!("could not find epic!"))?;
+        let today = today();
 
-        println!(
+        writeln!(
+            screen,
             "{}{:-^width$}",
-            HEADER_COLOR,
-            " EPIC ",
-            width = TERMINAL_WIDTH,
-        );
-        println!(
-            "{: ^id_width$}|{: ^name_width$}|{: ^description_width$}| {: ^status_width$}{dft}",
+            header,
+            if epic.archived {
+                " EPIC (ARCHIVED) "
+            } else {
+                " EPIC "
+            },
+            width = terminal_width,
+        )?;
+        writeln!(screen, "{: ^id_width$}|{: ^name_width$}|{: ^description_width$}| {: ^status_width$}| {: ^due_width$}{dft}",
             "id",
             "name",
             "description",
             "status",
+            "due",
             id_width = ID_WIDTH,
             name_width = NAME_WIDTH,
             description_width = description_width,
             status_width = STATUS_WIDTH,
-            dft = DFT
-        );
+            due_width = DUE_WIDTH,
+            dft = reset
+        )?;
 
         // print out epic details using get_column_string()
-        println!(
-            "{:<id_width$}{HEADER_COLOR}|{DFT}{}{HEADER_COLOR}|{DFT}{}{HEADER_COLOR}|{DFT} {}",
-            &self.epic_id,
+        let row_color = if epic.is_overdue(today) {
+            &overdue
+        } else {
+            &reset
+        };
+        writeln!(screen, "{row_color}{:<id_width$}{header}|{row_color}{}{header}|{row_color}{}{header}|{row_color} {}{header}|{row_color} {}{reset}",
+            db_state.display_id(self.epic_id),
             get_column_string(&epic.name, NAME_WIDTH),
             get_column_string(&epic.description, description_width),
             get_column_string(format!("{}", epic.status).as_str(), STATUS_WIDTH),
+            get_date_string(epic.due_date, DUE_WIDTH),
             id_width = ID_WIDTH
-        );
+        )?;
+
+        // print out the time tracking totals of all stories in the epic
+        let epic_stories = epic
+            .stories
+            .iter()
+            .filter_map(|id| db_state.stories.get(id))
+            .collect::<Vec<_>>();
+        writeln!(
+            screen,
+            "original estimate: {} | remaining estimate: {} | time spent: {}",
+            format_duration(total_duration(
+                epic_stories
+                    .iter()
+                    .filter_map(|story| story.original_estimate)
+            )),
+            format_duration(total_duration(
+                epic_stories
+                    .iter()
+                    .filter_map(|story| story.remaining_estimate)
+            )),
+            format_duration(total_duration(
+                epic_stories.iter().map(|story| story.time_spent())
+            )),
+        )?;
+
+        let counts = db_state.epic_status_counts(self.epic_id);
+        writeln!(
+            screen,
+            "progress: {} | status: {}",
+            get_progress_string(counts.done(), counts.total(), PROGRESS_WIDTH),
+            if epic.derived_status {
+                "derived from stories"
+            } else {
+                "manual"
+            }
+        )?;
+        if counts.is_complete() && epic.status.is_unresolved() && !epic.derived_status {
+            writeln!(
+                screen,
+                "{query}all stories done, consider resolving this epic with [u]{reset}"
+            )?;
+        }
 
-        println!();
+        writeln!(screen)?;
 
-        println!(
+        let settings = user_settings(&db_state);
+        writeln!(
+            screen,
             "{}{:-^width$}",
-            HEADER_COLOR,
-            " STORIES ",
-            width = TERMINAL_WIDTH
-        );
-        println!(
-            "{: ^id_width$}|{: ^name_width$}|{: ^status_width$} {dft}",
-            "id",
-            "name",
-            "status",
-            id_width = ID_WIDTH,
-            name_width = name_width,
-            status_width = STATUS_WIDTH,
-            dft = DFT
-        );
+            header,
+            table_title("STORIES", &settings.filter),
+            width = terminal_width
+        )?;
+        let layout = Layout::new(&settings.table(Table::Stories), terminal_width);
+        layout.write_header(screen)?;
+
+        let rows = self.story_rows(&db_state, &settings)?;
+        for row in self.pagination.rows(&rows, screen.page_size) {
+            layout.write_row(screen, row)?;
+        }
+        if let Some(indicator) = self.pagination.indicator(rows.len()) {
+            writeln!(screen, "{query}{indicator}{reset}")?;
+        }
 
-        let stories = &db_state.stories;
-        // print out stories using get_column_string(). also make sure the stories are sorted by id
-        let stories_iter = stories.iter().sorted_by_key(|x| x.0);
-        for (&id, story) in stories_iter {
-            // filter out the items for the given epic
-            if epic.stories.contains(&id) {
-                println!(
-                    "{:<id_width$}{HEADER_COLOR}|{DFT}{}{HEADER_COLOR}|{DFT} {}",
-                    id,
-                    get_column_string(&story.name, name_width),
-                    get_column_string(format!("{}", story.status).as_str(), STATUS_WIDTH),
-                    id_width = ID_WIDTH,
-                );
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::EpicDetail
+    }
+
+    fn breadcrumb(&self) -> String {
+        let epic_id = self.epic_id;
+        match self.db.read_db() {
+            Ok(db_state) => {
+                let name = db_state.epics.get(&epic_id).map_or("", |epic| &epic.name);
+                format!("Epic {} {}", db_state.display_id(epic_id), name)
+                    .trim_end()
+                    .to_owned()
             }
+            Err(_) => format!("Epic {epic_id}"),
         }
+    }
+
+    fn item_id(&self) -> Option<ID> {
+        Some(self.epic_id)
+    }
 
-        println!();
-        println!();
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
+        let epic_id = self.epic_id;
 
-        print_query("[p] previous | [u] update epic | [d] delete epic | [c] create story | [:id:] navigate to story")
+        match command {
+            Command::Previous => Ok(Some(Action::NavigateToPreviousPage)),
+            Command::UpdateEpic => Ok(Some(Action::UpdateEpicStatus { epic_id })),
+            Command::SetDueDate => Ok(Some(Action::UpdateEpicDueDate { epic_id })),
+            Command::ToggleDerivedStatus => Ok(Some(Action::ToggleEpicDerivedStatus { epic_id })),
+            Command::ToggleArchived => Ok(Some(Action::ToggleEpicArchived { epic_id })),
+            Command::DeleteEpic => Ok(Some(Action::DeleteEpic { epic_id })),
+            Command::CreateStory => Ok(Some(Action::CreateStory { epic_id })),
+            Command::Sort => Ok(Some(Action::SortTable {
+                table: Table::Stories,
+                change: SortChange::NextKey,
+            })),
+            Command::ReverseSort => Ok(Some(Action::SortTable {
+                table: Table::Stories,
+                change: SortChange::Reverse,
+            })),
+            _ => Ok(None),
+        }
     }
 
-    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
-        // match against the user input and return the corresponding action. If the user input was invalid return None.
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
         let epic_id = self.epic_id;
+        let db_state = self.db.database.read_db()?;
 
-        match input {
-            "" => Ok(None),
-            "p" => Ok(Some(Action::NavigateToPreviousPage)),
-            "u" => Ok(Some(Action::UpdateEpicStatus { epic_id })),
-            "d" => Ok(Some(Action::DeleteEpic { epic_id })),
-            "c" => Ok(Some(Action::CreateStory { epic_id })),
-            _ => {
-                if input.parse::<u32>().is_err() {
-                    return Ok(None);
-                }
+        let Some(story_id) = db_state.resolve_id(input) else {
+            return Ok(None);
+        };
+        // stories of other epics are opened below their own epic
+        let is_story_of_epic = db_state
+            .epics
+            .get(&epic_id)
+            .is_some_and(|epic| epic.stories.contains(&story_id));
+        if !is_story_of_epic || !db_state.stories.contains_key(&story_id) {
+            return Ok(go_to(&db_state, input));
+        }
 
-                let story_id = input.parse::<u32>()?;
-                let db_state = self.db.database.read_db()?;
+        Ok(Some(Action::NavigateToStoryDetail { epic_id, story_id }))
+    }
 
-                if db_state.stories.get(&story_id).is_none() {
-                    return Ok(None);
-                }
+    fn scroll(&self, pages: isize) {
+        self.pagination.scroll(pages);
+    }
 
-                Ok(Some(Action::NavigateToStoryDetail { epic_id, story_id }))
-            }
-        }
+    fn selectable_ids(&self) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        let rows = self.story_rows(&db_state, &user_settings(&db_state))?;
+        Ok(self
+            .pagination
+            .visible_rows(&rows)
+            .iter()
+            .map(|row| db_state.display_id(row.id))
+            .collect())
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -239,173 +690,1859 @@ pub struct StoryDetail {
 }
 
 impl Page for StoryDetail {
-    fn draw_page(&self) -> Result<()> {
-        let description_width = TERMINAL_WIDTH - ID_WIDTH - STATUS_WIDTH - NAME_WIDTH - 4;
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette {
+            header,
+            overdue,
+            reset,
+            ..
+        } = screen.palette.clone();
+        let terminal_width = screen.width;
+        let description_width =
+            terminal_width - ID_WIDTH - STATUS_WIDTH - NAME_WIDTH - DUE_WIDTH - 6;
         let db_state = self.db.read_db()?;
         let story = db_state
             .stories
             .get(&self.story_id)
             .ok_or_else(|| anyhow!("could not find story!"))?;
 
-        println!(
+        writeln!(
+            screen,
             "{}{:-^width$}",
-            HEADER_COLOR,
+            header,
             " STORY ",
-            width = TERMINAL_WIDTH
-        );
-        println!(
-            "{: ^id_width$}|{: ^name_width$}|{: ^description_width$}| {: ^status_width$}{dft}",
+            width = terminal_width
+        )?;
+        writeln!(screen, "{: ^id_width$}|{: ^name_width$}|{: ^description_width$}| {: ^status_width$}| {: ^due_width$}{dft}",
             "id",
             "name",
             "description",
             "status",
+            "due",
             id_width = ID_WIDTH,
             name_width = NAME_WIDTH,
             description_width = description_width,
             status_width = STATUS_WIDTH,
-            dft = DFT
-        );
+            due_width = DUE_WIDTH,
+            dft = reset
+        )?;
 
         // print out story details using get_column_string()
-        println!(
-            "{:<id_width$}{HEADER_COLOR}|{DFT}{}{HEADER_COLOR}|{DFT}{}{HEADER_COLOR}|{DFT} {}",
-            &self.story_id,
+        let row_color = if story.is_overdue(today()) {
+            &overdue
+        } else {
+            &reset
+        };
+        writeln!(screen, "{row_color}{:<id_width$}{header}|{row_color}{}{header}|{row_color}{}{header}|{row_color} {}{header}|{row_color} {}{reset}",
+            db_state.display_id(self.story_id),
             get_column_string(&story.name, NAME_WIDTH),
             get_column_string(&story.description, description_width),
             get_column_string(format!("{}", story.status).as_str(), STATUS_WIDTH),
+            get_date_string(story.due_date, DUE_WIDTH),
             id_width = ID_WIDTH,
-        );
+        )?;
+        writeln!(
+            screen,
+            "type: {} | fix version: {} | assignee: {}",
+            story.story_type,
+            story.fix_version.as_deref().unwrap_or("-"),
+            story.assignee.as_deref().unwrap_or("-")
+        )?;
+
+        writeln!(screen)?;
+
+        // print out the time tracking of the story
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            " WORK LOG ",
+            width = terminal_width
+        )?;
+        writeln!(
+            screen,
+            "original estimate: {} | remaining estimate: {} | time spent: {}{reset}",
+            story
+                .original_estimate
+                .map(format_duration)
+                .unwrap_or("-".to_string()),
+            story
+                .remaining_estimate
+                .map(format_duration)
+                .unwrap_or("-".to_string()),
+            format_duration(story.time_spent()),
+        )?;
+        let note_width = terminal_width - DATE_TIME_WIDTH - NAME_WIDTH - DURATION_WIDTH - 3;
+        writeln!(screen, "{header}{: ^date_width$}|{: ^author_width$}|{: ^duration_width$}|{: ^note_width$}{reset}",
+            "started",
+            "author",
+            "spent",
+            "note",
+            date_width = DATE_TIME_WIDTH,
+            author_width = NAME_WIDTH,
+            duration_width = DURATION_WIDTH,
+        )?;
+        for work_log in story.work_logs.iter().sorted_by_key(|log| log.started) {
+            writeln!(
+                screen,
+                "{}{header}|{reset}{}{header}|{reset}{}{header}|{reset}{}",
+                get_column_string(
+                    &work_log.started.format("%Y-%m-%d %H:%M").to_string(),
+                    DATE_TIME_WIDTH
+                ),
+                get_column_string(&work_log.author, NAME_WIDTH),
+                get_column_string(&format_duration(work_log.duration), DURATION_WIDTH),
+                get_column_string(&work_log.note, note_width),
+            )?;
+        }
+
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::StoryDetail
+    }
 
-        println!();
-        println!();
+    fn breadcrumb(&self) -> String {
+        let story_id = self.story_id;
+        match self.db.read_db() {
+            Ok(db_state) => format!("Story {}", db_state.display_id(story_id)),
+            Err(_) => format!("Story {story_id}"),
+        }
+    }
 
-        print_query("[p] previous | [u] update story | [d] delete story")
+    fn item_id(&self) -> Option<ID> {
+        Some(self.story_id)
     }
 
-    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
-        // match against the user input and return the corresponding action. If the user input was invalid return None.
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
         let epic_id = self.epic_id;
         let story_id = self.story_id;
 
-        match input {
-            "" => Ok(None),
-            "p" => Ok(Some(Action::NavigateToPreviousPage)),
-            "u" => Ok(Some(Action::UpdateStoryStatus { story_id })),
-            "d" => Ok(Some(Action::DeleteStory { epic_id, story_id })),
+        match command {
+            Command::Previous => Ok(Some(Action::NavigateToPreviousPage)),
+            Command::UpdateStory => Ok(Some(Action::UpdateStoryStatus { story_id })),
+            Command::SetDueDate => Ok(Some(Action::UpdateStoryDueDate { story_id })),
+            Command::Estimate => Ok(Some(Action::UpdateStoryEstimate { story_id })),
+            Command::LogWork => Ok(Some(Action::LogWork { story_id })),
+            Command::ToggleTimer => Ok(Some(Action::ToggleTimer { story_id })),
+            Command::ChangeType => Ok(Some(Action::UpdateStoryType { story_id })),
+            Command::FixVersion => Ok(Some(Action::UpdateStoryFixVersion { story_id })),
+            Command::Assign => Ok(Some(Action::ToggleStoryAssignee { story_id })),
+            Command::DeleteStory => Ok(Some(Action::DeleteStory { epic_id, story_id })),
             _ => Ok(None),
         }
     }
 
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+        Ok(go_to(&db_state, input))
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::db::test_utils::MockDB;
-    use crate::models::{Epic, Story};
+pub struct BacklogPage {
+    pub db: Rc<JiraDatabase>,
+}
 
-    mod home_page {
-        use super::*;
+impl BacklogPage {
+    /// The ids of the unresolved stories in rank order
+    fn story_ids(db_state: &DBState) -> Vec<ID> {
+        db_state
+            .ranked_story_ids()
+            .into_iter()
+            .filter(|id| {
+                db_state
+                    .stories
+                    .get(id)
+                    .is_some_and(|story| story.status.is_unresolved())
+            })
+            .collect()
+    }
+}
 
-        #[test]
-        fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+impl Page for BacklogPage {
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette { header, reset, .. } = screen.palette.clone();
+        let terminal_width = screen.width;
+        let name_width: usize = terminal_width - 2 * ID_WIDTH - NAME_WIDTH - STATUS_WIDTH - 4;
 
-            let page = HomePage { db };
-            assert_eq!(page.draw_page().is_ok(), true);
-        }
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            " BACKLOG ",
+            width = terminal_width
+        )?;
+        writeln!(screen, "{: ^id_width$}|{: ^id_width$}|{: ^name_width$}|{: ^epic_width$}| {: ^status_width$}{dft}",
+            "rank",
+            "id",
+            "name",
+            "epic",
+            "status",
+            id_width = ID_WIDTH,
+            name_width = name_width,
+            epic_width = NAME_WIDTH,
+            status_width = STATUS_WIDTH,
+            dft = reset
+        )?;
 
-        #[test]
-        fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+        // print out the unresolved stories in rank order
+        let db_state = self.db.read_db()?;
+        for (rank, id) in Self::story_ids(&db_state).into_iter().enumerate() {
+            let story = &db_state.stories[&id];
+            let epic_name = db_state
+                .epic_id_of_story(id)
+                .and_then(|epic_id| db_state.epics.get(&epic_id))
+                .map(|epic| epic.name.as_str())
+                .unwrap_or("");
+            let row_color = get_row_color(
+                &screen.palette,
+                false,
+                screen.is_selected_row(&db_state.display_id(id)),
+            );
 
-            let page = HomePage { db };
-            assert_eq!(page.handle_input("").is_ok(), true);
+            writeln!(screen, "{row_color}{:<id_width$}{header}|{row_color}{:<id_width$}{header}|{row_color}{}{header}|{row_color}{}{header}|{row_color} {}{reset}",
+                rank + 1,
+                db_state.display_id(id),
+                get_column_string(&story.name, name_width),
+                get_column_string(epic_name, NAME_WIDTH),
+                get_column_string(format!("{}", story.status).as_str(), STATUS_WIDTH),
+                id_width = ID_WIDTH,
+            )?;
         }
 
-        #[test]
-        fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
-
-            let epic = Epic::new("".to_owned(), "".to_owned());
+        writeln!(screen)?;
+        writeln!(screen)?;
 
-            let epic_id = db.create_epic(epic).unwrap();
+        print_help(screen, self.kind())
+    }
 
-            let page = HomePage { db };
+    fn kind(&self) -> PageKind {
+        PageKind::Backlog
+    }
 
-            let q = "q";
-            let c = "c";
-            let valid_epic_id = epic_id.to_string();
-            let invalid_epic_id = "999";
-            let junk_input = "j983f2j";
-            let junk_input_with_valid_prefix = "q983f2j";
-            let input_with_trailing_white_spaces = "q\n";
+    fn handle_command(&self, command: Command, id: Option<&str>) -> Result<Option<Action>> {
+        let direction = match command {
+            Command::Previous => return Ok(Some(Action::NavigateToPreviousPage)),
+            Command::MoveUp => RankMove::Up,
+            Command::MoveDown => RankMove::Down,
+            Command::MoveToTop => RankMove::Top,
+            Command::MoveToBottom => RankMove::Bottom,
+            _ => return Ok(None),
+        };
 
-            assert_eq!(page.handle_input(q).unwrap(), Some(Action::Exit));
-            assert_eq!(page.handle_input(c).unwrap(), Some(Action::CreateEpic));
-            assert_eq!(
-                page.handle_input(&valid_epic_id).unwrap(),
-                Some(Action::NavigateToEpicDetail { epic_id: 1 })
-            );
-            assert_eq!(page.handle_input(invalid_epic_id).unwrap(), None);
-            assert_eq!(page.handle_input(junk_input).unwrap(), None);
-            assert_eq!(
-                page.handle_input(junk_input_with_valid_prefix).unwrap(),
-                None
-            );
-            assert_eq!(
-                page.handle_input(input_with_trailing_white_spaces).unwrap(),
-                None
-            );
+        let db_state = self.db.read_db()?;
+        match id.and_then(|id| db_state.resolve_id(id)) {
+            Some(story_id) if db_state.stories.contains_key(&story_id) => {
+                Ok(Some(Action::MoveStoryInBacklog {
+                    story_id,
+                    direction,
+                }))
+            }
+            _ => Ok(None),
         }
     }
 
-    mod epic_detail_page {
-        use super::*;
-
-        #[test]
-        fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
-            let epic_id = db
-                .create_epic(Epic::new("".to_owned(), "".to_owned()))
-                .unwrap();
-
-            let page = EpicDetail { epic_id, db };
-            assert_eq!(page.draw_page().is_ok(), true);
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+        let Some(story_id) = db_state.resolve_id(input) else {
+            return Ok(None);
+        };
+        if !db_state.stories.contains_key(&story_id) {
+            return Ok(go_to(&db_state, input));
         }
 
-        #[test]
-        fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
-            let epic_id = db
-                .create_epic(Epic::new("".to_owned(), "".to_owned()))
-                .unwrap();
-
-            let page = EpicDetail { epic_id, db };
-            assert_eq!(page.handle_input("").is_ok(), true);
+        match db_state.epic_id_of_story(story_id) {
+            Some(epic_id) => Ok(Some(Action::NavigateToStoryDetail { epic_id, story_id })),
+            None => Ok(None),
         }
+    }
+
+    fn selectable_ids(&self) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        Ok(Self::story_ids(&db_state)
+            .into_iter()
+            .map(|id| db_state.display_id(id))
+            .collect())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct UpcomingPage {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for UpcomingPage {
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette {
+            header,
+            overdue,
+            reset,
+            ..
+        } = screen.palette.clone();
+        let terminal_width = screen.width;
+        const TYPE_WIDTH: usize = 6;
+        let name_width: usize =
+            terminal_width - DUE_WIDTH - TYPE_WIDTH - ID_WIDTH - STATUS_WIDTH - 6;
+
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            " UPCOMING ",
+            width = terminal_width
+        )?;
+        writeln!(screen, "{: ^due_width$}|{: ^type_width$}|{: ^id_width$}|{: ^name_width$}| {: ^status_width$}{dft}",
+            "due",
+            "type",
+            "id",
+            "name",
+            "status",
+            due_width = DUE_WIDTH,
+            type_width = TYPE_WIDTH,
+            id_width = ID_WIDTH,
+            name_width = name_width,
+            status_width = STATUS_WIDTH,
+            dft = reset
+        )?;
+
+        // print out all unresolved epics and stories with a due date, the nearest due date first
+        let db_state = self.db.read_db()?;
+        let epics_iter = db_state
+            .epics
+            .iter()
+            .filter(|(_, epic)| epic.status.is_unresolved())
+            .filter_map(|(&id, epic)| {
+                epic.due_date
+                    .map(|due_date| (due_date, "epic", id, &epic.name, &epic.status))
+            });
+        let stories_iter = db_state
+            .stories
+            .iter()
+            .filter(|(_, story)| story.status.is_unresolved())
+            .filter_map(|(&id, story)| {
+                story
+                    .due_date
+                    .map(|due_date| (due_date, "story", id, &story.name, &story.status))
+            });
+        let today = today();
+
+        for (due_date, item_type, id, name, status) in epics_iter
+            .chain(stories_iter)
+            .sorted_by_key(|(due_date, _, id, _, _)| (*due_date, *id))
+        {
+            let row_color = if due_date < today { &overdue } else { &reset };
+            writeln!(screen, "{row_color}{}{header}|{row_color}{:<type_width$}{header}|{row_color}{:<id_width$}{header}|{row_color}{}{header}|{row_color} {}{reset}",
+                get_date_string(Some(due_date), DUE_WIDTH),
+                item_type,
+                db_state.display_id(id),
+                get_column_string(name, name_width),
+                get_column_string(format!("{}", status).as_str(), STATUS_WIDTH),
+                type_width = TYPE_WIDTH,
+                id_width = ID_WIDTH,
+            )?;
+        }
+
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::Upcoming
+    }
+
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
+        match command {
+            Command::Previous => Ok(Some(Action::NavigateToPreviousPage)),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+        let Some(id) = db_state.resolve_id(input) else {
+            return Ok(None);
+        };
+
+        if db_state.epics.contains_key(&id) {
+            return Ok(Some(Action::NavigateToEpicDetail { epic_id: id }));
+        }
+        match db_state.epic_id_of_story(id) {
+            Some(epic_id) => Ok(Some(Action::NavigateToStoryDetail {
+                epic_id,
+                story_id: id,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct ReleasesPage {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for ReleasesPage {
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette { header, reset, .. } = screen.palette.clone();
+        let terminal_width = screen.width;
+        let name_width: usize = terminal_width - DUE_WIDTH - STATUS_WIDTH - NAME_WIDTH - 5;
+
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            " RELEASES ",
+            width = terminal_width
+        )?;
+        writeln!(
+            screen,
+            "{: ^name_width$}|{: ^date_width$}| {: ^status_width$}| {: ^progress_width$}{dft}",
+            "version",
+            "release",
+            "status",
+            "progress",
+            name_width = name_width,
+            date_width = DUE_WIDTH,
+            status_width = STATUS_WIDTH,
+            progress_width = NAME_WIDTH,
+            dft = reset
+        )?;
+
+        // print out the versions, the next release first
+        let db_state = self.db.read_db()?;
+        let versions_iter = db_state
+            .versions
+            .iter()
+            .sorted_by_key(|version| (version.released, version.release_date));
+
+        for version in versions_iter {
+            let (done, total) = db_state.version_progress(&version.name);
+            writeln!(
+                screen,
+                "{}{header}|{reset}{}{header}|{reset} {}{header}|{reset} {}",
+                get_column_string(&version.name, name_width),
+                get_date_string(version.release_date, DUE_WIDTH),
+                get_column_string(
+                    if version.released {
+                        "RELEASED"
+                    } else {
+                        "UNRELEASED"
+                    },
+                    STATUS_WIDTH
+                ),
+                get_column_string(&format!("{done}/{total} done"), NAME_WIDTH),
+            )?;
+        }
+
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::Releases
+    }
+
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
+        match command {
+            Command::Previous => Ok(Some(Action::NavigateToPreviousPage)),
+            Command::CreateVersion => Ok(Some(Action::CreateVersion)),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+
+        Ok(db_state
+            .versions
+            .iter()
+            .find(|version| version.name == input)
+            .map(|version| Action::NavigateToRelease {
+                version: version.name.clone(),
+            })
+            .or_else(|| go_to(&db_state, input)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct ReleasePage {
+    pub version: String,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for ReleasePage {
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette { header, reset, .. } = screen.palette.clone();
+        let terminal_width = screen.width;
+        const TYPE_WIDTH: usize = 6;
+        let name_width: usize = terminal_width - ID_WIDTH - TYPE_WIDTH - STATUS_WIDTH - 4;
+
+        let db_state = self.db.read_db()?;
+        let version = db_state
+            .versions
+            .iter()
+            .find(|version| version.name == self.version)
+            .ok_or_else(|| anyhow!("could not find version!"))?;
+        let (done, total) = db_state.version_progress(&version.name);
+
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            format!(" RELEASE {} ", version.name),
+            width = terminal_width
+        )?;
+        writeln!(
+            screen,
+            "release date: {} | {} | {done}/{total} done{reset}",
+            version
+                .release_date
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or("-".to_string()),
+            if version.released {
+                "RELEASED"
+            } else {
+                "UNRELEASED"
+            },
+        )?;
+
+        writeln!(screen)?;
+
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            " STORIES ",
+            width = terminal_width
+        )?;
+        writeln!(
+            screen,
+            "{: ^id_width$}|{: ^type_width$}|{: ^name_width$}| {: ^status_width$}{dft}",
+            "id",
+            "type",
+            "name",
+            "status",
+            id_width = ID_WIDTH,
+            type_width = TYPE_WIDTH,
+            name_width = name_width,
+            status_width = STATUS_WIDTH,
+            dft = reset
+        )?;
+
+        // print out the stories of the version sorted by id
+        let stories_iter = db_state
+            .stories
+            .iter()
+            .filter(|(_, story)| story.fix_version.as_ref() == Some(&version.name))
+            .sorted_by_key(|x| x.0);
+        for (&id, story) in stories_iter {
+            writeln!(
+                screen,
+                "{:<id_width$}{header}|{reset}{}{header}|{reset}{}{header}|{reset} {}",
+                db_state.display_id(id),
+                get_column_string(&story.story_type.to_string(), TYPE_WIDTH),
+                get_column_string(&story.name, name_width),
+                get_column_string(format!("{}", story.status).as_str(), STATUS_WIDTH),
+                id_width = ID_WIDTH,
+            )?;
+        }
+
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::Release
+    }
+
+    fn breadcrumb(&self) -> String {
+        format!("Release {}", self.version)
+    }
+
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
+        match command {
+            Command::Previous => Ok(Some(Action::NavigateToPreviousPage)),
+            Command::MarkAsReleased => Ok(Some(Action::ReleaseVersion {
+                version: self.version.clone(),
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+
+        let Some(story_id) = db_state.resolve_id(input) else {
+            return Ok(None);
+        };
+        let in_version = db_state
+            .stories
+            .get(&story_id)
+            .is_some_and(|story| story.fix_version.as_ref() == Some(&self.version));
+        match db_state.epic_id_of_story(story_id) {
+            Some(epic_id) if in_version => {
+                Ok(Some(Action::NavigateToStoryDetail { epic_id, story_id }))
+            }
+            _ => Ok(go_to(&db_state, input)),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct TrashPage {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for TrashPage {
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette { header, reset, .. } = screen.palette.clone();
+        let terminal_width = screen.width;
+        const TYPE_WIDTH: usize = 6;
+        let name_width: usize = terminal_width - ID_WIDTH - TYPE_WIDTH - DATE_TIME_WIDTH - 4;
+
+        let db_state = self.db.read_db()?;
+        let title = match db_state.trash_retention_days {
+            Some(days) => format!(" TRASH - kept for {days} days "),
+            None => " TRASH ".to_string(),
+        };
+
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            title,
+            width = terminal_width
+        )?;
+        writeln!(
+            screen,
+            "{: ^id_width$}|{: ^type_width$}|{: ^name_width$}| {: ^date_width$}{dft}",
+            "id",
+            "type",
+            "name",
+            "deleted",
+            id_width = ID_WIDTH,
+            type_width = TYPE_WIDTH,
+            name_width = name_width,
+            date_width = DATE_TIME_WIDTH,
+            dft = reset
+        )?;
+
+        // print out the deleted items, the most recently deleted first
+        for item in db_state.trash.iter().rev() {
+            let item_type = match item.item {
+                TrashedItem::Epic { .. } => "EPIC",
+                TrashedItem::Story { .. } => "STORY",
+            };
+            let row_color = get_row_color(
+                &screen.palette,
+                false,
+                screen.is_selected_row(&item.display_id()),
+            );
+            writeln!(
+                screen,
+                "{row_color}{}{header}|{row_color}{}{header}|{row_color}{}{header}|{row_color} {}{reset}",
+                get_column_string(&item.display_id(), ID_WIDTH),
+                get_column_string(item_type, TYPE_WIDTH),
+                get_column_string(item.name(), name_width),
+                item.deleted_at.format("%Y-%m-%d %H:%M"),
+            )?;
+        }
+
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::Trash
+    }
+
+    fn handle_command(&self, command: Command, id: Option<&str>) -> Result<Option<Action>> {
+        match (command, id) {
+            (Command::Previous, _) => Ok(Some(Action::NavigateToPreviousPage)),
+            (Command::Restore, Some(id)) => {
+                let db_state = self.db.read_db()?;
+                Ok(db_state
+                    .resolve_trash_id(id)
+                    .map(|id| Action::RestoreItem { id }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+        Ok(go_to(&db_state, input))
+    }
+
+    fn selectable_ids(&self) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        Ok(db_state
+            .trash
+            .iter()
+            .rev()
+            .map(|item| item.display_id())
+            .collect())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct ArchivePage {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for ArchivePage {
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette { header, reset, .. } = screen.palette.clone();
+        let terminal_width = screen.width;
+        let name_width: usize = terminal_width - ID_WIDTH - STATUS_WIDTH - DUE_WIDTH - 5;
+
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            " ARCHIVED EPICS ",
+            width = terminal_width
+        )?;
+        writeln!(
+            screen,
+            "{: ^id_width$}|{: ^name_width$}| {: ^status_width$}| {: ^due_width$}{dft}",
+            "id",
+            "name",
+            "status",
+            "due",
+            id_width = ID_WIDTH,
+            name_width = name_width,
+            status_width = STATUS_WIDTH,
+            due_width = DUE_WIDTH,
+            dft = reset
+        )?;
+
+        let db_state = self.db.read_db()?;
+        let epics_iter = db_state
+            .epics
+            .iter()
+            .filter(|(_, epic)| epic.archived)
+            .sorted_by_key(|x| x.0);
+
+        for (&id, epic) in epics_iter {
+            writeln!(
+                screen,
+                "{:<id_width$}{header}|{reset}{}{header}|{reset} {}{header}|{reset} {}",
+                db_state.display_id(id),
+                get_column_string(&epic.name, name_width),
+                get_column_string(format!("{}", epic.status).as_str(), STATUS_WIDTH),
+                get_date_string(epic.due_date, DUE_WIDTH),
+                id_width = ID_WIDTH,
+            )?;
+        }
+
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::Archive
+    }
+
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
+        match command {
+            Command::Previous => Ok(Some(Action::NavigateToPreviousPage)),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+
+        let Some(epic_id) = db_state.resolve_id(input) else {
+            return Ok(None);
+        };
+        match db_state.epics.get(&epic_id) {
+            Some(epic) if epic.archived => Ok(Some(Action::NavigateToEpicDetail { epic_id })),
+            _ => Ok(go_to(&db_state, input)),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Lists the epics and stories the user visited last
+pub struct RecentPage {
+    pub db: Rc<JiraDatabase>,
+    /// the user whose visits are shown
+    pub user: String,
+}
+
+impl RecentPage {
+    /// The recently visited items which still exist, the last visited first
+    fn recent_ids(&self, db_state: &DBState) -> Vec<ID> {
+        db_state
+            .settings_of(&self.user)
+            .recent
+            .into_iter()
+            .filter(|id| db_state.epics.contains_key(id) || db_state.stories.contains_key(id))
+            .collect()
+    }
+}
+
+impl Page for RecentPage {
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette { header, reset, .. } = screen.palette.clone();
+        const TYPE_WIDTH: usize = 6;
+        let terminal_width = screen.width;
+        let name_width: usize = terminal_width - ID_WIDTH - TYPE_WIDTH - STATUS_WIDTH - 4;
+
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            " RECENTLY VISITED ",
+            width = terminal_width
+        )?;
+        writeln!(
+            screen,
+            "{: ^id_width$}|{: ^type_width$}|{: ^name_width$}| {: ^status_width$}{dft}",
+            "id",
+            "type",
+            "name",
+            "status",
+            id_width = ID_WIDTH,
+            type_width = TYPE_WIDTH,
+            name_width = name_width,
+            status_width = STATUS_WIDTH,
+            dft = reset
+        )?;
+
+        let db_state = self.db.read_db()?;
+        for id in self.recent_ids(&db_state) {
+            let (item_type, name, status) = match db_state.epics.get(&id) {
+                Some(epic) => ("EPIC".to_owned(), &epic.name, &epic.status),
+                None => {
+                    let story = &db_state.stories[&id];
+                    (story.story_type.to_string(), &story.name, &story.status)
+                }
+            };
+            let row_color = get_row_color(
+                &screen.palette,
+                false,
+                screen.is_selected_row(&db_state.display_id(id)),
+            );
+            writeln!(
+                screen,
+                "{row_color}{:<id_width$}{header}|{row_color}{}{header}|{row_color}{}{header}|{row_color} {}{reset}",
+                db_state.display_id(id),
+                get_column_string(&item_type, TYPE_WIDTH),
+                get_column_string(name, name_width),
+                get_column_string(&status.to_string(), STATUS_WIDTH),
+                id_width = ID_WIDTH,
+            )?;
+        }
+
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::Recent
+    }
+
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
+        match command {
+            Command::Previous => Ok(Some(Action::NavigateToPreviousPage)),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_other_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+        Ok(go_to(&db_state, input))
+    }
+
+    fn selectable_ids(&self) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        Ok(self
+            .recent_ids(&db_state)
+            .into_iter()
+            .map(|id| db_state.display_id(id))
+            .collect())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Lists the commands of a page with all their keys, and the commands of the command palette
+pub struct HelpPage {
+    /// the page the help is shown for
+    pub page: PageKind,
+}
+
+impl Page for HelpPage {
+    fn draw_page(&self, screen: &mut Screen) -> Result<()> {
+        let Palette { header, reset, .. } = screen.palette.clone();
+        let terminal_width = screen.width;
+        let description_width: usize = terminal_width - NAME_WIDTH - COMMAND_WIDTH - 2;
+
+        writeln!(
+            screen,
+            "{}{:-^width$}",
+            header,
+            format!(" HELP: {} ", self.page.title().to_uppercase()),
+            width = terminal_width
+        )?;
+        writeln!(
+            screen,
+            "{: ^keys_width$}|{: ^command_width$}|{: ^description_width$}{reset}",
+            "keys",
+            "command",
+            "description",
+            keys_width = NAME_WIDTH,
+            command_width = COMMAND_WIDTH,
+        )?;
+
+        for &command in self.page.commands() {
+            writeln!(
+                screen,
+                "{}{header}|{reset}{}{header}|{reset}{}",
+                get_column_string(&screen.keymap.keys(command).join(", "), NAME_WIDTH),
+                get_column_string(command.name(), COMMAND_WIDTH),
+                get_column_string(command.description(), description_width),
+            )?;
+        }
+        if let Some(hint) = self.page.hint() {
+            writeln!(screen, "{hint}")?;
+        }
+        writeln!(screen)?;
+
+        writeln!(
+            screen,
+            "{}{:-^width$}{reset}",
+            header,
+            " COMMAND PALETTE ",
+            width = terminal_width
+        )?;
+        let palette_commands = command_palette::palette_commands(self.page)
+            .into_iter()
+            .map(|(name, argument, description)| (format!(":{name} {argument}"), description))
+            .chain([(
+                ":<command> [id]".to_owned(),
+                "any command above by name, e.g. :help",
+            )]);
+        for (command, description) in palette_commands {
+            writeln!(
+                screen,
+                "{}{header}|{reset}{}",
+                get_column_string(&command, NAME_WIDTH + COMMAND_WIDTH + 1),
+                get_column_string(description, description_width),
+            )?;
+        }
+        writeln!(
+            screen,
+            "names can be shortened, e.g. :st res, and are completed with [tab] in the TUI"
+        )?;
+        writeln!(
+            screen,
+            "an id like 12 or WEB-12 goes to that epic or story from every page"
+        )?;
+
+        writeln!(screen)?;
+        writeln!(screen)?;
+
+        print_help(screen, self.kind())
+    }
+
+    fn kind(&self) -> PageKind {
+        PageKind::Help
+    }
+
+    fn handle_command(&self, command: Command, _id: Option<&str>) -> Result<Option<Action>> {
+        match command {
+            Command::Previous => Ok(Some(Action::NavigateToPreviousPage)),
+            _ => Ok(None),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+    use crate::models::{Epic, Story};
+
+    use crate::ui::Keymap;
+
+    /// draws the page like on a terminal 100 columns wide, without colours
+    fn draw(page: &dyn Page) -> Result<String> {
+        let mut buffer = vec![];
+        let mut screen = Screen::new(&mut buffer).with_width(100).with_color(false);
+        page.draw_page(&mut screen)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    /// handles the input with the default keys
+    fn handle_input(page: &dyn Page, input: &str) -> Result<Option<Action>> {
+        Keymap::default().handle_input(page, input)
+    }
+
+    #[test]
+    fn draw_header_should_not_throw_error() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.start_timer(story_id, "".to_owned(), now()).unwrap();
+
+        let mut buffer = vec![];
+        assert_eq!(
+            draw_header(&db, &mut Screen::new(&mut buffer), &["Home".to_owned()]).is_ok(),
+            true
+        );
+    }
+
+    #[test]
+    fn draw_header_should_show_the_key_of_the_timed_story() {
+        let db = JiraDatabase {
+            database: Box::new(MockDB::new()),
+        };
+        db.create_project(crate::models::Project::new("WEB".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut epic = Epic::new("".to_owned(), "".to_owned());
+        epic.project = Some("WEB".to_owned());
+        let epic_id = db.create_epic(epic).unwrap();
+        let story_id = db
+            .create_story(Story::new("Pay".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.start_timer(story_id, "".to_owned(), now()).unwrap();
+
+        let mut buffer = vec![];
+        draw_header(&db, &mut Screen::new(&mut buffer), &[]).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output.contains("timer running: story WEB-2 Pay"), true);
+    }
+
+    mod projects_page {
+        use super::*;
+        use crate::models::Project;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            db.create_project(Project::new("WEB".to_owned(), "Website".to_owned()))
+                .unwrap();
+
+            let page = ProjectsPage { db };
+            assert_eq!(draw(&page).is_ok(), true);
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            db.create_project(Project::new("WEB".to_owned(), "Website".to_owned()))
+                .unwrap();
+
+            let page = ProjectsPage { db };
+
+            assert_eq!(handle_input(&page, "q").unwrap(), Some(Action::Exit));
+            assert_eq!(
+                handle_input(&page, "c").unwrap(),
+                Some(Action::CreateProject)
+            );
+            assert_eq!(
+                handle_input(&page, "a").unwrap(),
+                Some(Action::NavigateToProject { project_key: None })
+            );
+            assert_eq!(
+                handle_input(&page, "web").unwrap(),
+                Some(Action::NavigateToProject {
+                    project_key: Some("WEB".to_owned())
+                })
+            );
+            assert_eq!(handle_input(&page, "APP").unwrap(), None);
+            assert_eq!(handle_input(&page, "").unwrap(), None);
+        }
+    }
+
+    mod home_page {
+        use super::*;
+        use crate::models::{Column, FilterChange, Status};
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+            assert_eq!(draw(&page).is_ok(), true);
+        }
+
+        #[test]
+        fn selectable_ids_should_list_the_shown_epics() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let epic_1 = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let epic_2 = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let archived = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.update_epic_status(archived, Status::Closed).unwrap();
+            db.set_epic_archived(archived, true).unwrap();
+
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+            assert_eq!(
+                page.selectable_ids().unwrap(),
+                vec![epic_1.to_string(), epic_2.to_string()]
+            );
+
+            // the selected row is drawn highlighted
+            let mut buffer = vec![];
+            let mut screen = Screen::new(&mut buffer).with_selected_row(Some(epic_2.to_string()));
+            page.draw_page(&mut screen).unwrap();
+            let output = String::from_utf8(buffer).unwrap();
+            assert_eq!(
+                output.contains(&format!("{}{epic_2} ", Palette::default().selected)),
+                true
+            );
+        }
+
+        #[test]
+        fn draw_page_should_show_the_columns_of_the_user() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let epic_id = db
+                .create_epic(Epic::new("Checkout".to_owned(), "".to_owned()))
+                .unwrap();
+            db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.update_user_settings(&current_user_name(), |settings| {
+                settings.update_table(Table::Epics, |table| {
+                    table.columns = vec!["name:20".parse().unwrap(), "priority".parse().unwrap()];
+                    table.change_sort(SortChange::Set {
+                        key: Column::Priority,
+                        descending: true,
+                    });
+                })
+            })
+            .unwrap();
+
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+            let output = draw(&page).unwrap();
+            assert_eq!(output.contains("        name        | priority ▼\n"), true);
+            assert_eq!(output.contains("Checkout            | 1         \n"), true);
+            assert_eq!(output.contains("0/1 done"), false);
+        }
+
+        #[test]
+        fn draw_page_should_show_the_filter_in_the_title() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let epic_id = db
+                .create_epic(Epic::new("Checkout".to_owned(), "".to_owned()))
+                .unwrap();
+            db.update_epic_status(epic_id, Status::Resolved).unwrap();
+            db.update_user_settings(&current_user_name(), |settings| {
+                settings.filter.change(FilterChange::ToggleHideDone)
+            })
+            .unwrap();
+
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+            let output = draw(&page).unwrap();
+            assert_eq!(output.contains("- EPICS [filter: unresolved] -"), true);
+            assert_eq!(output.contains("Checkout"), false);
+        }
+
+        #[test]
+        fn draw_page_with_finished_epic_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.update_story_status(story_id, Status::Resolved).unwrap();
+
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+            assert_eq!(draw(&page).is_ok(), true);
+        }
+
+        #[test]
+        fn handle_input_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+            assert_eq!(handle_input(&page, "").is_ok(), true);
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic = Epic::new("".to_owned(), "".to_owned());
+
+            let epic_id = db.create_epic(epic).unwrap();
+
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+
+            let q = "q";
+            let c = "c";
+            let valid_epic_id = epic_id.to_string();
+            let invalid_epic_id = "999";
+            let junk_input = "j983f2j";
+            let junk_input_with_valid_prefix = "q983f2j";
+            let input_with_trailing_white_spaces = "q\n";
+
+            assert_eq!(handle_input(&page, q).unwrap(), Some(Action::Exit));
+            assert_eq!(
+                handle_input(&page, c).unwrap(),
+                Some(Action::CreateEpic { project_key: None })
+            );
+            assert_eq!(
+                handle_input(&page, "j").unwrap(),
+                Some(Action::NavigateToProjects)
+            );
+            assert_eq!(
+                handle_input(&page, "r").unwrap(),
+                Some(Action::NavigateToReleases)
+            );
+            assert_eq!(
+                handle_input(&page, "b").unwrap(),
+                Some(Action::NavigateToBacklog)
+            );
+            assert_eq!(
+                handle_input(&page, "u").unwrap(),
+                Some(Action::NavigateToUpcoming)
+            );
+            assert_eq!(
+                handle_input(&page, &valid_epic_id).unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id: 1 })
+            );
+            assert_eq!(handle_input(&page, invalid_epic_id).unwrap(), None);
+            assert_eq!(handle_input(&page, junk_input).unwrap(), None);
+            assert_eq!(
+                handle_input(&page, junk_input_with_valid_prefix).unwrap(),
+                None
+            );
+            assert_eq!(
+                handle_input(&page, input_with_trailing_white_spaces).unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn draw_page_should_show_one_page_of_epics() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            for name in ["Alpha", "Bravo", "Charlie", "Delta", "Echo"] {
+                db.create_epic(Epic::new(name.to_owned(), "".to_owned()))
+                    .unwrap();
+            }
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+            let draw_page = |page: &HomePage| {
+                let mut buffer = vec![];
+                let mut screen = Screen::new(&mut buffer)
+                    .with_width(100)
+                    .with_color(false)
+                    .with_page_size(Some(2));
+                page.draw_page(&mut screen).unwrap();
+                String::from_utf8(buffer).unwrap()
+            };
+
+            let output = draw_page(&page);
+            assert_eq!(output.contains("Alpha"), true);
+            assert_eq!(output.contains("Charlie"), false);
+            assert_eq!(output.contains("showing 1-2 of 5"), true);
+            // the summary is about all epics
+            assert_eq!(output.contains("0 open"), true);
+
+            page.scroll(2);
+            let output = draw_page(&page);
+            assert_eq!(output.contains("Echo"), true);
+            assert_eq!(output.contains("Alpha"), false);
+            assert_eq!(output.contains("showing 5-5 of 5"), true);
+            assert_eq!(page.selectable_ids().unwrap(), vec!["5".to_owned()]);
+
+            // epics on other pages can still be opened by id
+            assert_eq!(
+                handle_input(&page, "1").unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id: 1 })
+            );
+            assert_eq!(
+                handle_input(&page, ">").unwrap(),
+                Some(Action::ScrollList { pages: 1 })
+            );
+            assert_eq!(
+                handle_input(&page, "<").unwrap(),
+                Some(Action::ScrollList { pages: -1 })
+            );
+        }
+
+        #[test]
+        fn handle_input_should_go_to_stories() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+
+            assert_eq!(
+                handle_input(&page, &story_id.to_string()).unwrap(),
+                Some(Action::GoTo {
+                    id: story_id.to_string()
+                })
+            );
+        }
+    }
+
+    mod home_page_with_project {
+        use super::*;
+        use crate::models::Project;
+
+        #[test]
+        fn handle_input_should_accept_project_keys() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            db.create_project(Project::new("WEB".to_owned(), "Website".to_owned()))
+                .unwrap();
+            let mut epic = Epic::new("".to_owned(), "".to_owned());
+            epic.project = Some("WEB".to_owned());
+            let epic_id = db.create_epic(epic).unwrap();
+
+            let page = HomePage {
+                db,
+                project: Some("WEB".to_owned()),
+                pagination: Pagination::default(),
+            };
+
+            assert_eq!(draw(&page).is_ok(), true);
+            assert_eq!(
+                handle_input(&page, "c").unwrap(),
+                Some(Action::CreateEpic {
+                    project_key: Some("WEB".to_owned())
+                })
+            );
+            assert_eq!(
+                handle_input(&page, "WEB-1").unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id })
+            );
+            assert_eq!(handle_input(&page, "WEB-2").unwrap(), None);
+        }
+    }
+
+    mod epic_detail_page {
+        use super::*;
+
+        #[test]
+        fn selectable_ids_should_list_the_stories_of_the_epic() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let other_epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.create_story(Story::new("".to_owned(), "".to_owned()), other_epic_id)
+                .unwrap();
+
+            let page = EpicDetail {
+                epic_id,
+                db,
+                pagination: Pagination::default(),
+            };
+            assert_eq!(page.selectable_ids().unwrap(), vec![story_id.to_string()]);
+        }
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = EpicDetail {
+                epic_id,
+                db,
+                pagination: Pagination::default(),
+            };
+            assert_eq!(draw(&page).is_ok(), true);
+        }
+
+        #[test]
+        fn handle_input_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = EpicDetail {
+                epic_id,
+                db,
+                pagination: Pagination::default(),
+            };
+            assert_eq!(handle_input(&page, "").is_ok(), true);
+        }
+
+        #[test]
+        fn draw_page_should_throw_error_for_invalid_epic_id() {
+            let db: Rc<JiraDatabase> = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let page = EpicDetail {
+                epic_id: 999,
+                db,
+                pagination: Pagination::default(),
+            };
+            assert_eq!(draw(&page).is_err(), true);
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = EpicDetail {
+                epic_id,
+                db,
+                pagination: Pagination::default(),
+            };
+
+            let p = "p";
+            let u = "u";
+            let d = "d";
+            let c = "c";
+            let invalid_story_id = "999";
+            let junk_input = "j983f2j";
+            let junk_input_with_valid_prefix = "p983f2j";
+            let input_with_trailing_white_spaces = "p\n";
+
+            assert_eq!(
+                handle_input(&page, p).unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                handle_input(&page, u).unwrap(),
+                Some(Action::UpdateEpicStatus { epic_id: 1 })
+            );
+            assert_eq!(
+                handle_input(&page, d).unwrap(),
+                Some(Action::DeleteEpic { epic_id: 1 })
+            );
+            assert_eq!(
+                handle_input(&page, "t").unwrap(),
+                Some(Action::UpdateEpicDueDate { epic_id: 1 })
+            );
+            assert_eq!(
+                handle_input(&page, "a").unwrap(),
+                Some(Action::ToggleEpicDerivedStatus { epic_id: 1 })
+            );
+            assert_eq!(
+                handle_input(&page, c).unwrap(),
+                Some(Action::CreateStory { epic_id: 1 })
+            );
+            assert_eq!(
+                handle_input(&page, &story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail {
+                    epic_id: 1,
+                    story_id: 2
+                })
+            );
+            assert_eq!(handle_input(&page, invalid_story_id).unwrap(), None);
+            assert_eq!(handle_input(&page, junk_input).unwrap(), None);
+            assert_eq!(
+                handle_input(&page, junk_input_with_valid_prefix).unwrap(),
+                None
+            );
+            assert_eq!(
+                handle_input(&page, input_with_trailing_white_spaces).unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn handle_input_should_go_to_stories_of_other_epics() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let other_epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let other_story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), other_epic_id)
+                .unwrap();
+
+            let page = EpicDetail {
+                epic_id,
+                db,
+                pagination: Pagination::default(),
+            };
+
+            // the story isn't opened with the epic id of this page
+            assert_eq!(
+                handle_input(&page, &other_story_id.to_string()).unwrap(),
+                Some(Action::GoTo {
+                    id: other_story_id.to_string()
+                })
+            );
+            assert_eq!(
+                handle_input(&page, &other_epic_id.to_string()).unwrap(),
+                Some(Action::GoTo {
+                    id: other_epic_id.to_string()
+                })
+            );
+        }
+    }
+
+    mod story_detail_page {
+        use super::*;
+        use crate::models::Status;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+            assert_eq!(draw(&page).is_ok(), true);
+        }
+
+        #[test]
+        fn handle_input_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+            assert_eq!(handle_input(&page, "").is_ok(), true);
+        }
+
+        #[test]
+        fn draw_page_should_throw_error_for_invalid_story_id() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let _ = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id: 999,
+                db,
+            };
+            assert_eq!(draw(&page).is_err(), true);
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+
+            let p = "p";
+            let u = "u";
+            let d = "d";
+            let some_number = "1";
+            let junk_input = "j983f2j";
+            let junk_input_with_valid_prefix = "p983f2j";
+            let input_with_trailing_white_spaces = "p\n";
+
+            assert_eq!(
+                handle_input(&page, p).unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                handle_input(&page, u).unwrap(),
+                Some(Action::UpdateStoryStatus { story_id })
+            );
+            assert_eq!(
+                handle_input(&page, d).unwrap(),
+                Some(Action::DeleteStory { epic_id, story_id })
+            );
+            assert_eq!(
+                handle_input(&page, "t").unwrap(),
+                Some(Action::UpdateStoryDueDate { story_id })
+            );
+            assert_eq!(
+                handle_input(&page, "e").unwrap(),
+                Some(Action::UpdateStoryEstimate { story_id })
+            );
+            assert_eq!(
+                handle_input(&page, "w").unwrap(),
+                Some(Action::LogWork { story_id })
+            );
+            assert_eq!(
+                handle_input(&page, "s").unwrap(),
+                Some(Action::ToggleTimer { story_id })
+            );
+            assert_eq!(
+                handle_input(&page, "y").unwrap(),
+                Some(Action::UpdateStoryType { story_id })
+            );
+            assert_eq!(
+                handle_input(&page, "f").unwrap(),
+                Some(Action::UpdateStoryFixVersion { story_id })
+            );
+            assert_eq!(
+                handle_input(&page, some_number).unwrap(),
+                Some(Action::GoTo {
+                    id: some_number.to_owned()
+                })
+            );
+            assert_eq!(handle_input(&page, "999").unwrap(), None);
+            assert_eq!(handle_input(&page, junk_input).unwrap(), None);
+            assert_eq!(
+                handle_input(&page, junk_input_with_valid_prefix).unwrap(),
+                None
+            );
+            assert_eq!(
+                handle_input(&page, input_with_trailing_white_spaces).unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn handle_input_should_accept_help_and_palette_commands() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+
+            assert_eq!(
+                handle_input(&page, "?").unwrap(),
+                Some(Action::NavigateToHelp)
+            );
+            assert_eq!(
+                handle_input(&page, ":help").unwrap(),
+                Some(Action::NavigateToHelp)
+            );
+            assert_eq!(
+                handle_input(&page, ":status resolved").unwrap(),
+                Some(Action::UpdateStatus {
+                    id: story_id,
+                    status: Status::Resolved
+                })
+            );
+            assert_eq!(
+                handle_input(&page, ":move WEB-3").unwrap(),
+                Some(Action::MoveStoryToEpic {
+                    story_id,
+                    epic_id: "WEB-3".to_owned()
+                })
+            );
+            assert_eq!(
+                handle_input(&page, ":goto 7").unwrap(),
+                Some(Action::GoTo { id: "7".to_owned() })
+            );
+            assert_eq!(
+                handle_input(&page, ":delete").unwrap(),
+                Some(Action::DeleteStory { epic_id, story_id })
+            );
+            assert_eq!(handle_input(&page, ":teleport").is_err(), true);
+        }
+    }
+
+    mod backlog_page {
+        use super::*;
+        use crate::models::Status;
 
         #[test]
-        fn draw_page_should_throw_error_for_invalid_epic_id() {
-            let db: Rc<JiraDatabase> = Rc::new(JiraDatabase {
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase {
                 database: Box::new(MockDB::new()),
             });
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let _ = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
 
-            let page = EpicDetail { epic_id: 999, db };
-            assert_eq!(page.draw_page().is_err(), true);
+            let page = BacklogPage { db };
+            assert_eq!(draw(&page).is_ok(), true);
         }
 
         #[test]
@@ -420,80 +2557,107 @@ mod tests {
             let story_id = db
                 .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
                 .unwrap();
+            db.update_story_status(story_id, Status::InProgress)
+                .unwrap();
 
-            let page = EpicDetail { epic_id, db };
-
-            let p = "p";
-            let u = "u";
-            let d = "d";
-            let c = "c";
-            let invalid_story_id = "999";
-            let junk_input = "j983f2j";
-            let junk_input_with_valid_prefix = "p983f2j";
-            let input_with_trailing_white_spaces = "p\n";
+            let page = BacklogPage { db };
 
             assert_eq!(
-                page.handle_input(p).unwrap(),
+                handle_input(&page, "p").unwrap(),
                 Some(Action::NavigateToPreviousPage)
             );
             assert_eq!(
-                page.handle_input(u).unwrap(),
-                Some(Action::UpdateEpicStatus { epic_id: 1 })
+                handle_input(&page, &story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail { epic_id, story_id })
             );
             assert_eq!(
-                page.handle_input(d).unwrap(),
-                Some(Action::DeleteEpic { epic_id: 1 })
+                handle_input(&page, &format!("u {story_id}")).unwrap(),
+                Some(Action::MoveStoryInBacklog {
+                    story_id,
+                    direction: RankMove::Up
+                })
             );
             assert_eq!(
-                page.handle_input(c).unwrap(),
-                Some(Action::CreateStory { epic_id: 1 })
+                handle_input(&page, &format!("d {story_id}")).unwrap(),
+                Some(Action::MoveStoryInBacklog {
+                    story_id,
+                    direction: RankMove::Down
+                })
             );
             assert_eq!(
-                page.handle_input(&story_id.to_string()).unwrap(),
-                Some(Action::NavigateToStoryDetail {
-                    epic_id: 1,
-                    story_id: 2
+                handle_input(&page, &format!("t {story_id}")).unwrap(),
+                Some(Action::MoveStoryInBacklog {
+                    story_id,
+                    direction: RankMove::Top
                 })
             );
-            assert_eq!(page.handle_input(invalid_story_id).unwrap(), None);
-            assert_eq!(page.handle_input(junk_input).unwrap(), None);
             assert_eq!(
-                page.handle_input(junk_input_with_valid_prefix).unwrap(),
-                None
+                handle_input(&page, &format!("b {story_id}")).unwrap(),
+                Some(Action::MoveStoryInBacklog {
+                    story_id,
+                    direction: RankMove::Bottom
+                })
             );
+            assert_eq!(handle_input(&page, "u").unwrap(), None);
+            assert_eq!(handle_input(&page, "u 999").unwrap(), None);
+            assert_eq!(handle_input(&page, "999").unwrap(), None);
+            assert_eq!(handle_input(&page, "x 2").unwrap(), None);
+            assert_eq!(handle_input(&page, "j983f2j").unwrap(), None);
+        }
+
+        #[test]
+        fn selectable_ids_should_be_the_unresolved_stories_in_rank_order() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let first_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let resolved_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let last_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.update_story_status(resolved_id, Status::Resolved)
+                .unwrap();
+            db.move_story_in_backlog(last_id, RankMove::Top).unwrap();
+
+            let page = BacklogPage { db };
             assert_eq!(
-                page.handle_input(input_with_trailing_white_spaces).unwrap(),
-                None
+                page.selectable_ids().unwrap(),
+                vec![last_id.to_string(), first_id.to_string()]
             );
         }
     }
 
-    mod story_detail_page {
+    mod upcoming_page {
         use super::*;
+        use chrono::NaiveDate;
 
         #[test]
         fn draw_page_should_not_throw_error() {
             let db = Rc::new(JiraDatabase {
                 database: Box::new(MockDB::new()),
             });
-
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
                 .unwrap();
             let story_id = db
                 .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
                 .unwrap();
+            db.update_story_due_date(story_id, NaiveDate::from_ymd_opt(2000, 1, 1))
+                .unwrap();
 
-            let page = StoryDetail {
-                epic_id,
-                story_id,
-                db,
-            };
-            assert_eq!(page.draw_page().is_ok(), true);
+            let page = UpcomingPage { db };
+            assert_eq!(draw(&page).is_ok(), true);
         }
 
         #[test]
-        fn handle_input_should_not_throw_error() {
+        fn handle_input_should_return_the_correct_actions() {
             let db = Rc::new(JiraDatabase {
                 database: Box::new(MockDB::new()),
             });
@@ -505,33 +2669,59 @@ mod tests {
                 .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
                 .unwrap();
 
-            let page = StoryDetail {
-                epic_id,
-                story_id,
-                db,
-            };
-            assert_eq!(page.handle_input("").is_ok(), true);
+            let page = UpcomingPage { db };
+
+            assert_eq!(
+                handle_input(&page, "p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                handle_input(&page, &epic_id.to_string()).unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id })
+            );
+            assert_eq!(
+                handle_input(&page, &story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail { epic_id, story_id })
+            );
+            assert_eq!(handle_input(&page, "999").unwrap(), None);
+            assert_eq!(handle_input(&page, "j983f2j").unwrap(), None);
         }
+    }
+
+    mod release_pages {
+        use super::*;
+        use crate::models::Version;
 
         #[test]
-        fn draw_page_should_throw_error_for_invalid_story_id() {
+        fn draw_page_should_not_throw_error() {
             let db = Rc::new(JiraDatabase {
                 database: Box::new(MockDB::new()),
             });
-
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
                 .unwrap();
-            let _ = db
+            let story_id = db
                 .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
                 .unwrap();
+            db.create_version(Version::new("1.0".to_owned(), None))
+                .unwrap();
+            db.update_story_fix_version(story_id, Some("1.0".to_owned()))
+                .unwrap();
 
-            let page = StoryDetail {
-                epic_id,
-                story_id: 999,
+            let page = ReleasesPage { db: db.clone() };
+            assert_eq!(draw(&page).is_ok(), true);
+
+            let page = ReleasePage {
+                version: "1.0".to_owned(),
+                db: db.clone(),
+            };
+            assert_eq!(draw(&page).is_ok(), true);
+
+            let page = ReleasePage {
+                version: "2.0".to_owned(),
                 db,
             };
-            assert_eq!(page.draw_page().is_err(), true);
+            assert_eq!(draw(&page).is_err(), true);
         }
 
         #[test]
@@ -539,50 +2729,355 @@ mod tests {
             let db = Rc::new(JiraDatabase {
                 database: Box::new(MockDB::new()),
             });
-
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
                 .unwrap();
             let story_id = db
                 .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
                 .unwrap();
+            let other_story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.create_version(Version::new("1.0".to_owned(), None))
+                .unwrap();
+            db.update_story_fix_version(story_id, Some("1.0".to_owned()))
+                .unwrap();
 
-            let page = StoryDetail {
-                epic_id,
-                story_id,
+            let page = ReleasesPage { db: db.clone() };
+            assert_eq!(
+                handle_input(&page, "p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                handle_input(&page, "c").unwrap(),
+                Some(Action::CreateVersion)
+            );
+            assert_eq!(
+                handle_input(&page, "1.0").unwrap(),
+                Some(Action::NavigateToRelease {
+                    version: "1.0".to_owned()
+                })
+            );
+            assert_eq!(handle_input(&page, "2.0").unwrap(), None);
+
+            let page = ReleasePage {
+                version: "1.0".to_owned(),
                 db,
             };
+            assert_eq!(
+                handle_input(&page, "r").unwrap(),
+                Some(Action::ReleaseVersion {
+                    version: "1.0".to_owned()
+                })
+            );
+            assert_eq!(
+                handle_input(&page, &story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail { epic_id, story_id })
+            );
+            // stories of other versions are opened below their epic
+            assert_eq!(
+                handle_input(&page, &other_story_id.to_string()).unwrap(),
+                Some(Action::GoTo {
+                    id: other_story_id.to_string()
+                })
+            );
+        }
+    }
 
-            let p = "p";
-            let u = "u";
-            let d = "d";
-            let some_number = "1";
-            let junk_input = "j983f2j";
-            let junk_input_with_valid_prefix = "p983f2j";
-            let input_with_trailing_white_spaces = "p\n";
+    mod trash_and_archive_pages {
+        use super::*;
+        use crate::models::Status;
+
+        #[test]
+        fn trash_page_should_restore_items() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.delete_story(epic_id, story_id).unwrap();
+
+            let page = TrashPage { db };
+            assert_eq!(draw(&page).is_ok(), true);
 
             assert_eq!(
-                page.handle_input(p).unwrap(),
+                handle_input(&page, "p").unwrap(),
                 Some(Action::NavigateToPreviousPage)
             );
             assert_eq!(
-                page.handle_input(u).unwrap(),
-                Some(Action::UpdateStoryStatus { story_id })
+                handle_input(&page, &format!("r {story_id}")).unwrap(),
+                Some(Action::RestoreItem { id: story_id })
+            );
+            // only items in the trash can be restored
+            assert_eq!(handle_input(&page, &format!("r {epic_id}")).unwrap(), None);
+            assert_eq!(handle_input(&page, &story_id.to_string()).unwrap(), None);
+            assert_eq!(handle_input(&page, "").unwrap(), None);
+            assert_eq!(page.selectable_ids().unwrap(), vec![story_id.to_string()]);
+        }
+
+        #[test]
+        fn archive_page_should_only_list_archived_epics() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let archived_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.update_epic_status(archived_id, Status::Closed).unwrap();
+            db.set_epic_archived(archived_id, true).unwrap();
+            let open_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = ArchivePage { db: db.clone() };
+            assert_eq!(draw(&page).is_ok(), true);
+
+            assert_eq!(
+                handle_input(&page, &archived_id.to_string()).unwrap(),
+                Some(Action::NavigateToEpicDetail {
+                    epic_id: archived_id
+                })
             );
             assert_eq!(
-                page.handle_input(d).unwrap(),
-                Some(Action::DeleteStory { epic_id, story_id })
+                handle_input(&page, &open_id.to_string()).unwrap(),
+                Some(Action::GoTo {
+                    id: open_id.to_string()
+                })
             );
-            assert_eq!(page.handle_input(some_number).unwrap(), None);
-            assert_eq!(page.handle_input(junk_input).unwrap(), None);
+            assert_eq!(handle_input(&page, "999").unwrap(), None);
             assert_eq!(
-                page.handle_input(junk_input_with_valid_prefix).unwrap(),
-                None
+                handle_input(&page, "p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+            assert_eq!(draw(&page).is_ok(), true);
+            assert_eq!(
+                handle_input(&page, "a").unwrap(),
+                Some(Action::NavigateToArchive)
             );
             assert_eq!(
-                page.handle_input(input_with_trailing_white_spaces).unwrap(),
-                None
+                handle_input(&page, "t").unwrap(),
+                Some(Action::NavigateToTrash)
+            );
+        }
+    }
+
+    #[test]
+    fn breadcrumb_line_should_leave_out_the_first_pages_if_too_wide() {
+        let breadcrumbs = ["Home", "Epic 6 Bugfixes", "Story 5"].map(str::to_owned);
+
+        assert_eq!(
+            breadcrumb_line(&breadcrumbs, 100),
+            "Home > Epic 6 Bugfixes > Story 5".to_owned()
+        );
+        assert_eq!(
+            breadcrumb_line(&breadcrumbs, 30),
+            "… > Epic 6 Bugfixes > Story 5".to_owned()
+        );
+        assert_eq!(breadcrumb_line(&breadcrumbs, 5), "St...".to_owned());
+    }
+
+    mod recent_page {
+        use super::*;
+
+        #[test]
+        fn draw_page_should_list_existing_items_last_visited_first() {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let epic_id = db
+                .create_epic(Epic::new("Bugfixes".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("Login".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.update_user_settings("alice", |settings| {
+                settings.visit(epic_id);
+                settings.visit(999);
+                settings.visit(story_id);
+            })
+            .unwrap();
+
+            let page = RecentPage {
+                db,
+                user: "alice".to_owned(),
+            };
+            let output = draw(&page).unwrap();
+
+            assert_eq!(output.contains("RECENTLY VISITED"), true);
+            assert_eq!(
+                output.find("Login").unwrap() < output.find("Bugfixes").unwrap(),
+                true
+            );
+            assert_eq!(output.contains("999"), false);
+            assert_eq!(
+                handle_input(&page, &epic_id.to_string()).unwrap(),
+                Some(Action::GoTo {
+                    id: epic_id.to_string()
+                })
+            );
+            assert_eq!(
+                handle_input(&page, "h").unwrap(),
+                Some(Action::NavigateToHome)
+            );
+            assert_eq!(
+                handle_input(&page, "n").unwrap(),
+                Some(Action::NavigateForward)
+            );
+        }
+    }
+
+    mod help_page {
+        use super::*;
+
+        #[test]
+        fn draw_page_should_list_commands_with_all_keys() {
+            let page = HelpPage {
+                page: PageKind::EpicDetail,
+            };
+            let output = draw(&page).unwrap();
+
+            assert_eq!(output.contains(" HELP: EPIC "), true);
+            assert_eq!(output.contains("delete-epic"), true);
+            assert_eq!(
+                output.contains(":status <open|in-progress|resolved|closed>"),
+                true
+            );
+            assert_eq!(output.contains(":move"), false);
+            assert_eq!(output.contains("[:id:] navigate to story"), true);
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let page = HelpPage {
+                page: PageKind::Home,
+            };
+
+            assert_eq!(
+                handle_input(&page, "p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(handle_input(&page, "q").unwrap(), None);
+            assert_eq!(handle_input(&page, ":goto 1").is_ok(), true);
+        }
+    }
+
+    mod snapshots {
+        use super::*;
+        use crate::models::{Status, WorkLog};
+        use chrono::NaiveDate;
+
+        /// Compares the output with the golden file in `src/ui/pages/snapshots`.
+        /// Run the tests with `UPDATE_SNAPSHOTS=1` to write the golden files after a change.
+        fn assert_snapshot(name: &str, output: &str) {
+            let path = format!(
+                "{}/src/ui/pages/snapshots/{name}.txt",
+                env!("CARGO_MANIFEST_DIR")
             );
+            if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+                std::fs::write(&path, output).unwrap();
+            }
+            let expected = std::fs::read_to_string(&path)
+                .unwrap_or_else(|_| panic!("missing snapshot {path}, run with UPDATE_SNAPSHOTS=1"));
+            assert_eq!(output, expected, "snapshot {name} differs");
+        }
+
+        /// a small board: an epic with an open, a resolved and a logged story
+        fn create_board() -> (Rc<JiraDatabase>, u32, u32) {
+            let db = Rc::new(JiraDatabase {
+                database: Box::new(MockDB::new()),
+            });
+            let date = NaiveDate::from_ymd_opt(2099, 1, 31).unwrap();
+
+            let epic_id = db
+                .create_epic(Epic::new(
+                    "Checkout".to_owned(),
+                    "Payment and order confirmation".to_owned(),
+                ))
+                .unwrap();
+            db.update_epic_due_date(epic_id, Some(date)).unwrap();
+            db.create_epic(Epic::new("Søk og filtrering".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let story_id = db
+                .create_story(
+                    Story::new(
+                        "Pay with credit card".to_owned(),
+                        "Accept Visa and Mastercard".to_owned(),
+                    ),
+                    epic_id,
+                )
+                .unwrap();
+            db.update_story_estimate(story_id, Some(480), Some(240))
+                .unwrap();
+            db.log_work(
+                story_id,
+                WorkLog {
+                    author: "alice".to_owned(),
+                    started: date.and_hms_opt(9, 30, 0).unwrap(),
+                    duration: 240,
+                    note: "card form".to_owned(),
+                },
+            )
+            .unwrap();
+            db.update_story_status(story_id, Status::InProgress)
+                .unwrap();
+
+            let resolved_id = db
+                .create_story(
+                    Story::new("Order confirmation mail".to_owned(), "".to_owned()),
+                    epic_id,
+                )
+                .unwrap();
+            db.update_story_status(resolved_id, Status::Resolved)
+                .unwrap();
+
+            (db, epic_id, story_id)
+        }
+
+        #[test]
+        fn home_page() {
+            let (db, _, _) = create_board();
+            let page = HomePage {
+                db,
+                project: None,
+                pagination: Pagination::default(),
+            };
+
+            assert_snapshot("home_page", &draw(&page).unwrap());
+        }
+
+        #[test]
+        fn epic_detail() {
+            let (db, epic_id, _) = create_board();
+            let page = EpicDetail {
+                epic_id,
+                db,
+                pagination: Pagination::default(),
+            };
+
+            assert_snapshot("epic_detail", &draw(&page).unwrap());
+        }
+
+        #[test]
+        fn story_detail() {
+            let (db, epic_id, story_id) = create_board();
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+
+            assert_snapshot("story_detail", &draw(&page).unwrap());
         }
     }
 }
@@ -1,17 +1,94 @@
+use chrono::NaiveDate;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Width used when the terminal size can't be determined, e.g. when the output is piped
+const DEFAULT_TERMINAL_WIDTH: usize = 100;
+/// The fixed columns of the widest table need this much space, narrower terminals wrap lines
+const MIN_TERMINAL_WIDTH: usize = 80;
+
+/// The current width of the terminal. It is queried on every call,
+/// so the layout follows a resize when the page is drawn again.
+pub fn terminal_width() -> usize {
+    let width = match terminal_size::terminal_size() {
+        Some((terminal_size::Width(width), _)) => usize::from(width),
+        None => std::env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.parse().ok())
+            .unwrap_or(DEFAULT_TERMINAL_WIDTH),
+    };
+    width.max(MIN_TERMINAL_WIDTH)
+}
+
+/// Height used when the terminal size can't be determined, e.g. when the output is piped
+const DEFAULT_TERMINAL_HEIGHT: usize = 40;
+/// The lines around the table of a page, e.g. the title, the header and the help lines
+const RESERVED_LINES: usize = 16;
+/// Tables show at least this many rows, also in very small terminals
+const MIN_PAGE_SIZE: usize = 5;
+
+/// The rows of a table which fit into the terminal together with the rest of the page,
+/// queried on every call like the width.
+pub fn terminal_page_size() -> usize {
+    let height = match terminal_size::terminal_size() {
+        Some((_, terminal_size::Height(height))) => usize::from(height),
+        None => std::env::var("LINES")
+            .ok()
+            .and_then(|lines| lines.parse().ok())
+            .unwrap_or(DEFAULT_TERMINAL_HEIGHT),
+    };
+    height.saturating_sub(RESERVED_LINES).max(MIN_PAGE_SIZE)
+}
+
+/// Pads or truncates the text to exactly `width` terminal columns. The display width
+/// is used instead of the byte length, so wide characters (e.g. CJK) keep the table
+/// aligned, and the text is only cut between grapheme clusters (e.g. accents, emoji).
 pub fn get_column_string(text: &str, width: usize) -> String {
-    let text_width = text.len();
+    let text_width = text.width();
 
     match width {
         0 => "".to_string(),
         1 => ".".to_string(),
         2 => "..".to_string(),
         3 => "...".to_string(),
-        _ if width == text_width => text.to_string(),
-        _ if width > text_width => format!("{:width$}", text, width = width).to_string(),
-        _ => format!("{:.<width$}", &text[0..width - 3], width = width).to_string(),
+        _ if width >= text_width => format!("{text}{}", " ".repeat(width - text_width)),
+        _ => {
+            let mut truncated = String::new();
+            let mut truncated_width = 0;
+            for grapheme in text.graphemes(true) {
+                let grapheme_width = grapheme.width();
+                if truncated_width + grapheme_width > width - 3 {
+                    break;
+                }
+                truncated.push_str(grapheme);
+                truncated_width += grapheme_width;
+            }
+            // a wide character which doesn't fit anymore is replaced by an additional dot
+            format!("{truncated}{}", ".".repeat(width - truncated_width))
+        }
     }
 }
 
+pub fn get_date_string(date: Option<NaiveDate>, width: usize) -> String {
+    match date {
+        None => get_column_string("", width),
+        Some(date) => get_column_string(&date.format("%Y-%m-%d").to_string(), width),
+    }
+}
+
+/// Renders a progress bar like `######---- 6/10 done`, padded to the given width
+pub fn get_progress_string(done: usize, total: usize, width: usize) -> String {
+    let label = format!(" {done}/{total} done");
+    let bar_width = width.saturating_sub(label.len());
+    let filled = match total {
+        0 => 0,
+        _ => (bar_width * done.min(total)) / total,
+    };
+    let bar = format!("{}{}", "#".repeat(filled), "-".repeat(bar_width - filled));
+
+    get_column_string(&format!("{bar}{label}"), width)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,4 +127,68 @@ mod tests {
         assert_eq!(get_column_string(text3, width), "testme".to_owned());
         assert_eq!(get_column_string(text4, width), "tes...".to_owned());
     }
+
+    #[test]
+    fn get_column_string_should_handle_non_ascii_text() {
+        // accented characters are one column wide, but several bytes long
+        assert_eq!(get_column_string("café", 6), "café  ".to_owned());
+        assert_eq!(get_column_string("Éléphant", 6), "Élé...".to_owned());
+
+        // combining accents belong to the previous character
+        assert_eq!(
+            get_column_string("re\u{301}sume\u{301}", 5),
+            "re\u{301}...".to_owned()
+        );
+
+        // CJK characters are two columns wide
+        assert_eq!(get_column_string("日本語", 8), "日本語  ".to_owned());
+        assert_eq!(
+            get_column_string("日本語テキスト", 8),
+            "日本....".to_owned()
+        );
+        assert_eq!(
+            get_column_string("日本語テキスト", 9),
+            "日本語...".to_owned()
+        );
+
+        // emoji with modifiers are not split
+        assert_eq!(get_column_string("👍🏽👍🏽👍🏽", 6), "👍🏽👍🏽👍🏽".to_owned());
+        assert_eq!(get_column_string("👍🏽👍🏽👍🏽", 5), "👍🏽...".to_owned());
+        assert_eq!(get_column_string("a👩‍👩‍👧bcd", 6), "a👩‍👩‍👧bcd".to_owned());
+        assert_eq!(get_column_string("ab👩‍👩‍👧bcd", 6), "ab....".to_owned());
+        assert_eq!(get_column_string("a👩‍👩‍👧bcd", 5), "a....".to_owned());
+    }
+
+    #[test]
+    fn terminal_width_should_not_be_narrower_than_the_tables() {
+        assert_eq!(terminal_width() >= MIN_TERMINAL_WIDTH, true);
+    }
+
+    #[test]
+    fn test_get_date_string() {
+        let date = NaiveDate::from_ymd_opt(2026, 11, 1);
+
+        assert_eq!(get_date_string(None, 10), "          ".to_owned());
+        assert_eq!(get_date_string(date, 10), "2026-11-01".to_owned());
+        assert_eq!(get_date_string(date, 12), "2026-11-01  ".to_owned());
+        assert_eq!(get_date_string(date, 7), "2026...".to_owned());
+    }
+
+    #[test]
+    fn test_get_progress_string() {
+        assert_eq!(
+            get_progress_string(0, 0, 18),
+            "--------- 0/0 done".to_owned()
+        );
+        assert_eq!(
+            get_progress_string(3, 6, 18),
+            "####----- 3/6 done".to_owned()
+        );
+        assert_eq!(
+            get_progress_string(7, 7, 19),
+            "########## 7/7 done".to_owned()
+        );
+        assert_eq!(get_progress_string(1, 3, 9), " 1/3 done".to_owned());
+        assert_eq!(get_progress_string(1, 3, 7), " 1/3...".to_owned());
+    }
 }
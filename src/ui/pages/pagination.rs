@@ -0,0 +1,103 @@
+use std::cell::Cell;
+
+/// The page of a long table which is shown, e.g. of the epics of a large board.
+///
+/// The page is changed with `&self`, so the pages keep their pagination while they are on
+/// the stack of the navigator. The end of the table is only known when it is drawn, so
+/// scrolling past it is undone by the next draw.
+#[derive(Debug, Default)]
+pub struct Pagination {
+    /// index of the page shown, starting at 0
+    page: Cell<usize>,
+    /// rows per page when the table was drawn last, 0 before it has been drawn
+    page_size: Cell<usize>,
+}
+
+impl Pagination {
+    /// The rows on the page shown, the page is moved back if the table got shorter
+    pub fn rows<'a, T>(&self, rows: &'a [T], page_size: usize) -> &'a [T] {
+        let page_size = page_size.max(1);
+        let last_page = rows.len().saturating_sub(1) / page_size;
+        self.page.set(self.page.get().min(last_page));
+        self.page_size.set(page_size);
+
+        let start = self.page.get() * page_size;
+        &rows[start..(start + page_size).min(rows.len())]
+    }
+
+    /// The rows drawn last, all rows before the table has been drawn
+    pub fn visible_rows<'a, T>(&self, rows: &'a [T]) -> &'a [T] {
+        match self.page_size.get() {
+            0 => rows,
+            page_size => self.rows(rows, page_size),
+        }
+    }
+
+    /// Where the page is in the table, e.g. `showing 21-40 of 312`, `None` if all rows fit
+    pub fn indicator(&self, total: usize) -> Option<String> {
+        let page_size = self.page_size.get();
+        if page_size == 0 || total <= page_size {
+            return None;
+        }
+
+        let start = self.page.get() * page_size;
+        Some(format!(
+            "showing {}-{} of {total}",
+            start + 1,
+            (start + page_size).min(total)
+        ))
+    }
+
+    /// Moves the number of pages forward, or back if negative
+    pub fn scroll(&self, pages: isize) {
+        self.page.set(self.page.get().saturating_add_signed(pages));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_should_show_the_page() {
+        let rows = (1..=45).collect::<Vec<_>>();
+        let pagination = Pagination::default();
+
+        assert_eq!(pagination.visible_rows(&rows).len(), 45);
+        assert_eq!(pagination.indicator(45), None);
+
+        assert_eq!(pagination.rows(&rows, 20), &rows[..20]);
+        assert_eq!(
+            pagination.indicator(45),
+            Some("showing 1-20 of 45".to_owned())
+        );
+
+        pagination.scroll(2);
+        assert_eq!(pagination.rows(&rows, 20), &rows[40..]);
+        assert_eq!(
+            pagination.indicator(45),
+            Some("showing 41-45 of 45".to_owned())
+        );
+        assert_eq!(pagination.visible_rows(&rows), &rows[40..]);
+    }
+
+    #[test]
+    fn rows_should_stay_within_the_table() {
+        let rows = (1..=45).collect::<Vec<_>>();
+        let pagination = Pagination::default();
+
+        // scrolling past the end shows the last page
+        pagination.scroll(5);
+        assert_eq!(pagination.rows(&rows, 20), &rows[40..]);
+        pagination.scroll(-1);
+        assert_eq!(pagination.rows(&rows, 20), &rows[20..40]);
+
+        pagination.scroll(-5);
+        assert_eq!(pagination.rows(&rows, 20), &rows[..20]);
+
+        // the table got shorter
+        pagination.scroll(2);
+        assert_eq!(pagination.rows(&rows[..10], 20), &rows[..10]);
+        assert_eq!(pagination.rows(&Vec::<i32>::new(), 20).is_empty(), true);
+    }
+}
@@ -0,0 +1,162 @@
+use std::io::{self, Write};
+
+use super::page_helpers::{terminal_page_size, terminal_width};
+use crate::ui::{Keymap, Palette};
+use std::rc::Rc;
+
+/// The target a page is drawn to: any writer, e.g. stdout or a buffer in the tests,
+/// together with the size of the screen and the row selected in the TUI.
+pub struct Screen<'a> {
+    out: &'a mut dyn Write,
+    pub width: usize,
+    /// the rows of long tables shown at once, the rest is on the next pages
+    pub page_size: usize,
+    /// with colour turned off, the ANSI escape sequences are removed from the output
+    pub color: bool,
+    /// the escape codes of the colour theme
+    pub palette: Palette,
+    /// the keys shown in the help lines
+    pub keymap: Rc<Keymap>,
+    /// id of the table row selected with the arrow keys in the TUI
+    pub selected_row: Option<String>,
+    escape: EscapeState,
+}
+
+/// Where the writer is within an ANSI escape sequence like `\x1B[38;2;255;0;0m`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum EscapeState {
+    Text,
+    Escape,
+    ControlSequence,
+}
+
+impl<'a> Screen<'a> {
+    /// A screen as wide as the terminal, with the colours of the default theme
+    pub fn new(out: &'a mut dyn Write) -> Self {
+        Screen {
+            out,
+            width: terminal_width(),
+            page_size: terminal_page_size(),
+            color: true,
+            palette: Palette::default(),
+            keymap: Rc::new(Keymap::default()),
+            selected_row: None,
+            escape: EscapeState::Text,
+        }
+    }
+
+    // a fixed width keeps the output of the snapshot tests independent of the terminal
+    #[allow(dead_code)]
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Shows the number of rows configured by the user, instead of the rows fitting the terminal
+    pub fn with_page_size(mut self, page_size: Option<usize>) -> Self {
+        if let Some(page_size) = page_size {
+            self.page_size = page_size.max(1);
+        }
+        self
+    }
+
+    // the snapshot tests turn the colours off without a palette
+    #[allow(dead_code)]
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Draws with the palette, without colours any escape codes left in the output are removed
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.color = palette.has_colors();
+        self.palette = palette;
+        self
+    }
+
+    pub fn with_keymap(mut self, keymap: Rc<Keymap>) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    pub fn with_selected_row(mut self, id: Option<String>) -> Self {
+        self.selected_row = id;
+        self
+    }
+
+    pub fn is_selected_row(&self, id: &str) -> bool {
+        self.selected_row.as_deref() == Some(id)
+    }
+}
+
+impl Write for Screen<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.color {
+            return self.out.write(buf);
+        }
+
+        // the state is kept between the writes, a sequence may be split over several of them
+        let mut text = Vec::with_capacity(buf.len());
+        for &byte in buf {
+            self.escape = match (self.escape, byte) {
+                (EscapeState::Text, 0x1B) => EscapeState::Escape,
+                (EscapeState::Text, _) => {
+                    text.push(byte);
+                    EscapeState::Text
+                }
+                (EscapeState::Escape, b'[') => EscapeState::ControlSequence,
+                (EscapeState::Escape, _) => EscapeState::Text,
+                // the final byte of a control sequence is in the range `@` to `~`
+                (EscapeState::ControlSequence, 0x40..=0x7E) => EscapeState::Text,
+                (EscapeState::ControlSequence, _) => EscapeState::ControlSequence,
+            };
+        }
+        self.out.write_all(&text)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_should_keep_colors() {
+        let mut buffer = vec![];
+        let mut screen = Screen::new(&mut buffer).with_color(true);
+        write!(screen, "\x1B[7mselected\x1B[0m").unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "\x1B[7mselected\x1B[0m".to_owned()
+        );
+    }
+
+    #[test]
+    fn screen_should_remove_colors() {
+        let mut buffer = vec![];
+        let mut screen = Screen::new(&mut buffer).with_color(false);
+        write!(screen, "\x1B[38;2;255;0;0mred\x1B[0m | ").unwrap();
+        // an escape sequence split over two writes
+        screen.write_all(b"\x1B[38;2").unwrap();
+        screen.write_all(b";20;198;13mgr\xC3\xBCn\x1B[0m").unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "red | grün".to_owned());
+    }
+
+    #[test]
+    fn screen_should_know_the_selected_row() {
+        let mut buffer = vec![];
+        let screen = Screen::new(&mut buffer)
+            .with_width(120)
+            .with_selected_row(Some("WEB-1".to_owned()));
+
+        assert_eq!(screen.width, 120);
+        assert_eq!(screen.is_selected_row("WEB-1"), true);
+        assert_eq!(screen.is_selected_row("1"), false);
+    }
+}
@@ -0,0 +1,368 @@
+use super::page_helpers::{get_column_string, get_date_string, get_progress_string};
+use super::{get_row_color, Screen};
+use super::{DATE_TIME_WIDTH, DUE_WIDTH, ID_WIDTH, PROGRESS_WIDTH, STATUS_WIDTH};
+use crate::models::{Column, ColumnSetting, DBState, Status, TableSettings, ID};
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::Write;
+
+const PRIORITY_WIDTH: usize = 10;
+/// The name gets the space left by the other columns, but never less than this
+const MIN_NAME_WIDTH: usize = 10;
+
+/// The values of an epic or story shown in a table row
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub id: ID,
+    display_id: String,
+    name: String,
+    status: Status,
+    /// the backlog rank, the best rank of its stories for an epic
+    priority: Option<usize>,
+    updated: Option<NaiveDateTime>,
+    due: Option<NaiveDate>,
+    /// stories done and total, only for epics
+    progress: Option<(usize, usize)>,
+    overdue: bool,
+}
+
+/// The backlog rank of every story, starting at 1
+fn ranks(db_state: &DBState) -> HashMap<ID, usize> {
+    db_state
+        .ranked_story_ids()
+        .into_iter()
+        .enumerate()
+        .map(|(index, id)| (id, index + 1))
+        .collect()
+}
+
+/// The rows of the epics, which must exist
+pub fn epic_rows(db_state: &DBState, ids: &[ID], today: NaiveDate) -> Vec<Row> {
+    let ranks = ranks(db_state);
+    ids.iter()
+        .map(|&id| Row::epic(db_state, &ranks, id, today))
+        .collect()
+}
+
+/// The rows of the stories, which must exist
+pub fn story_rows(db_state: &DBState, ids: &[ID], today: NaiveDate) -> Vec<Row> {
+    let ranks = ranks(db_state);
+    ids.iter()
+        .map(|&id| Row::story(db_state, &ranks, id, today))
+        .collect()
+}
+
+impl Row {
+    fn epic(db_state: &DBState, ranks: &HashMap<ID, usize>, id: ID, today: NaiveDate) -> Self {
+        let epic = &db_state.epics[&id];
+        let counts = db_state.epic_status_counts(id);
+        Row {
+            id,
+            display_id: db_state.display_id(id),
+            name: epic.name.clone(),
+            status: epic.status.clone(),
+            priority: epic
+                .stories
+                .iter()
+                .filter_map(|story_id| ranks.get(story_id).copied())
+                .min(),
+            updated: epic.updated_at,
+            due: epic.due_date,
+            progress: Some((counts.done(), counts.total())),
+            overdue: epic.is_overdue(today),
+        }
+    }
+
+    fn story(db_state: &DBState, ranks: &HashMap<ID, usize>, id: ID, today: NaiveDate) -> Self {
+        let story = &db_state.stories[&id];
+        Row {
+            id,
+            display_id: db_state.display_id(id),
+            name: story.name.clone(),
+            status: story.status.clone(),
+            priority: ranks.get(&id).copied(),
+            updated: story.updated_at,
+            due: story.due_date,
+            progress: None,
+            overdue: story.is_overdue(today),
+        }
+    }
+
+    /// Compares by the column, rows without a value come last in either order
+    fn compare(&self, other: &Row, column: Column, descending: bool) -> Ordering {
+        fn none_last<T: Ord>(a: &Option<T>, b: &Option<T>, descending: bool) -> Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) if descending => b.cmp(a),
+                (Some(a), Some(b)) => a.cmp(b),
+                (a, b) => a.is_none().cmp(&b.is_none()),
+            }
+        }
+
+        let ordering = match column {
+            Column::Id => self.id.cmp(&other.id),
+            Column::Name => self.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            Column::Status => self.status.cmp(&other.status),
+            Column::Priority => return none_last(&self.priority, &other.priority, descending),
+            Column::Updated => return none_last(&self.updated, &other.updated, descending),
+            Column::Due => return none_last(&self.due, &other.due, descending),
+            Column::Progress => Ordering::Equal,
+        };
+        match descending {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    }
+}
+
+/// Sorts the rows like the settings say, rows with the same value are sorted by id
+pub fn sort(rows: &mut [Row], settings: &TableSettings) {
+    rows.sort_by(|a, b| {
+        a.compare(b, settings.sort, settings.descending)
+            .then(a.id.cmp(&b.id))
+    });
+}
+
+/// The columns of a table with their widths for the terminal width
+pub struct Layout {
+    columns: Vec<(Column, usize)>,
+    sort: Option<(Column, bool)>,
+}
+
+impl Layout {
+    pub fn new(settings: &TableSettings, terminal_width: usize) -> Self {
+        let width = |setting: &ColumnSetting| match setting.column {
+            Column::Id => ID_WIDTH,
+            Column::Name => MIN_NAME_WIDTH,
+            Column::Status => STATUS_WIDTH,
+            Column::Priority => PRIORITY_WIDTH,
+            Column::Updated => DATE_TIME_WIDTH,
+            Column::Due => DUE_WIDTH,
+            Column::Progress => PROGRESS_WIDTH,
+        };
+
+        // the name takes the space which is left if its width isn't set
+        let used: usize = settings
+            .columns
+            .iter()
+            .filter(|setting| setting.column != Column::Name || setting.width.is_some())
+            .map(|setting| {
+                setting.width.unwrap_or_else(|| width(setting))
+                    + leading_space(setting.column).len()
+            })
+            .sum();
+        let separators = settings.columns.len().saturating_sub(1);
+        let name_width = terminal_width
+            .saturating_sub(used + separators)
+            .max(MIN_NAME_WIDTH);
+
+        let columns = settings
+            .columns
+            .iter()
+            .map(|setting| {
+                let default = match setting.column {
+                    Column::Name => name_width,
+                    _ => width(setting),
+                };
+                (setting.column, setting.width.unwrap_or(default))
+            })
+            .collect();
+
+        // tables sorted like nobody changed anything don't need a hint
+        let sort = match (settings.sort, settings.descending) {
+            (Column::Id, false) => None,
+            (sort, descending) => Some((sort, descending)),
+        };
+        Layout { columns, sort }
+    }
+
+    /// Prints the names of the columns, the sorted column is marked with an arrow
+    pub fn write_header(&self, screen: &mut Screen) -> Result<()> {
+        let reset = screen.palette.reset.clone();
+        let mut line = String::new();
+        for (index, &(column, width)) in self.columns.iter().enumerate() {
+            let name = match self.sort {
+                Some((sort, descending)) if sort == column => {
+                    format!("{} {}", column.name(), if descending { "▼" } else { "▲" })
+                }
+                _ => column.name().to_owned(),
+            };
+            let name = match name.chars().count() > width {
+                true => get_column_string(&name, width),
+                false => format!("{name: ^width$}"),
+            };
+            if index > 0 {
+                line.push('|');
+            }
+            line.push_str(leading_space(column));
+            line.push_str(&name);
+        }
+        writeln!(screen, "{line}{reset}")?;
+        Ok(())
+    }
+
+    /// Prints the row, highlighted if it is overdue or selected
+    pub fn write_row(&self, screen: &mut Screen, row: &Row) -> Result<()> {
+        let header = screen.palette.header.clone();
+        let reset = screen.palette.reset.clone();
+        let row_color = get_row_color(
+            &screen.palette,
+            row.overdue,
+            screen.is_selected_row(&row.display_id),
+        );
+
+        let mut line = String::new();
+        for (index, &(column, width)) in self.columns.iter().enumerate() {
+            let cell = match column {
+                Column::Id => get_column_string(&row.display_id, width),
+                Column::Name => get_column_string(&row.name, width),
+                Column::Status => get_column_string(&row.status.to_string(), width),
+                Column::Priority => get_column_string(
+                    &row.priority
+                        .map(|rank| rank.to_string())
+                        .unwrap_or_default(),
+                    width,
+                ),
+                Column::Updated => get_column_string(
+                    &row.updated
+                        .map(|updated| updated.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default(),
+                    width,
+                ),
+                Column::Due => get_date_string(row.due, width),
+                Column::Progress => match row.progress {
+                    Some((done, total)) => get_progress_string(done, total, width),
+                    None => get_column_string("", width),
+                },
+            };
+            if index > 0 {
+                line.push_str(&format!("{header}|"));
+            }
+            line.push_str(&format!("{row_color}{}{cell}", leading_space(column)));
+        }
+        writeln!(screen, "{line}{reset}")?;
+        Ok(())
+    }
+}
+
+/// The id and name are the only columns without a space after the separator
+fn leading_space(column: Column) -> &'static str {
+    match column {
+        Column::Id | Column::Name => "",
+        _ => " ",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Epic, Story, Table};
+
+    fn db_state() -> DBState {
+        let mut db_state = DBState::default();
+        for (id, name, status) in [
+            (1, "Bravo", Status::Closed),
+            (2, "alpha", Status::Open),
+            (3, "Charlie", Status::InProgress),
+        ] {
+            let mut story = Story::new(name.to_owned(), "".to_owned());
+            story.status = status;
+            db_state.stories.insert(id, story);
+        }
+        db_state.backlog = vec![3, 1, 2];
+
+        let mut epic = Epic::new("Epic".to_owned(), "".to_owned());
+        epic.stories = vec![1, 2];
+        db_state.epics.insert(4, epic);
+        db_state
+    }
+
+    fn sorted_ids(key: Column, descending: bool) -> Vec<ID> {
+        let db_state = db_state();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut rows = story_rows(&db_state, &[1, 2, 3], today);
+        let settings = TableSettings {
+            sort: key,
+            descending,
+            ..TableSettings::new(Table::Stories)
+        };
+        sort(&mut rows, &settings);
+        rows.into_iter().map(|row| row.id).collect()
+    }
+
+    #[test]
+    fn sort_should_use_the_sort_key() {
+        assert_eq!(sorted_ids(Column::Id, false), vec![1, 2, 3]);
+        assert_eq!(sorted_ids(Column::Id, true), vec![3, 2, 1]);
+        assert_eq!(sorted_ids(Column::Name, false), vec![2, 1, 3]);
+        assert_eq!(sorted_ids(Column::Status, false), vec![2, 3, 1]);
+        assert_eq!(sorted_ids(Column::Priority, false), vec![3, 1, 2]);
+        assert_eq!(sorted_ids(Column::Priority, true), vec![2, 1, 3]);
+        // rows with the same value keep the id order
+        assert_eq!(sorted_ids(Column::Due, false), vec![1, 2, 3]);
+        assert_eq!(sorted_ids(Column::Updated, true), vec![1, 2, 3]);
+
+        // rows without a value come last in both orders
+        let db_state = db_state();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut rows = story_rows(&db_state, &[1, 2, 3], today);
+        for row in rows.iter_mut() {
+            row.due = match row.id {
+                1 => NaiveDate::from_ymd_opt(2024, 2, 1),
+                3 => NaiveDate::from_ymd_opt(2024, 3, 1),
+                _ => None,
+            };
+        }
+        let mut settings = TableSettings {
+            sort: Column::Due,
+            descending: false,
+            ..TableSettings::new(Table::Stories)
+        };
+        sort(&mut rows, &settings);
+        assert_eq!(
+            rows.iter().map(|row| row.id).collect::<Vec<_>>(),
+            vec![1, 3, 2]
+        );
+        settings.descending = true;
+        sort(&mut rows, &settings);
+        assert_eq!(
+            rows.iter().map(|row| row.id).collect::<Vec<_>>(),
+            vec![3, 1, 2]
+        );
+    }
+
+    #[test]
+    fn epic_priority_should_be_the_best_rank_of_its_stories() {
+        let db_state = db_state();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        assert_eq!(epic_rows(&db_state, &[4], today)[0].priority, Some(2));
+    }
+
+    #[test]
+    fn layout_should_give_the_name_the_space_left() {
+        let settings = TableSettings::new(Table::Epics);
+        let layout = Layout::new(&settings, 100);
+        assert_eq!(
+            layout.columns,
+            vec![
+                (Column::Id, 10),
+                (Column::Name, 41),
+                (Column::Status, 12),
+                (Column::Progress, 20),
+                (Column::Due, 10),
+            ]
+        );
+
+        let settings = TableSettings {
+            columns: vec!["name:30".parse().unwrap(), "priority".parse().unwrap()],
+            ..TableSettings::new(Table::Stories)
+        };
+        let layout = Layout::new(&settings, 100);
+        assert_eq!(
+            layout.columns,
+            vec![(Column::Name, 30), (Column::Priority, PRIORITY_WIDTH)]
+        );
+    }
+}
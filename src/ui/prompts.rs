@@ -3,101 +3,134 @@
 /// Change log:
 /// entery of name and description is on same line (still remove unwrap?)
 /// removed bugs in Y/n and input of name and description
-use std::io;
-use std::io::Write;
+use std::rc::Rc;
+
+use chrono::NaiveDate;
 
 use crate::{
-    io_utils::{get_user_input, DFT, RED},
-    models::{Epic, Status, Story},
+    dates::{now, parse_date, parse_duration, today},
+    io_utils::{current_user_name, InputSource, Keyboard},
+    models::{Epic, Minutes, Project, Status, Story, StoryType, Version, WorkLog},
+    ui::Palette,
 };
 
-const QUERY_COLOR: &str = RED;
 const SEPERATOR_LINE_WIDTH: usize = 100;
 
+/// the original and the remaining estimate of a story
+pub type Estimates = (Option<Minutes>, Option<Minutes>);
+
 pub struct Prompts {
-    pub create_epic: Box<dyn Fn() -> Epic>,
-    pub create_story: Box<dyn Fn() -> Story>,
+    /// `None` when the prompt was cancelled
+    pub create_project: Box<dyn Fn() -> Option<Project>>,
+    /// `None` when the prompt was cancelled
+    pub create_epic: Box<dyn Fn() -> Option<Epic>>,
+    /// `None` when the prompt was cancelled
+    pub create_story: Box<dyn Fn() -> Option<Story>>,
     pub delete_epic: Box<dyn Fn() -> bool>,
     pub delete_story: Box<dyn Fn() -> bool>,
     pub update_status: Box<dyn Fn() -> Option<Status>>,
+    pub update_story_type: Box<dyn Fn() -> Option<StoryType>>,
+    /// `None` when the input was invalid, `Some(None)` to clear the fix version
+    pub update_fix_version: Box<dyn Fn() -> Option<Option<String>>>,
+    /// `None` when the input was invalid
+    pub create_version: Box<dyn Fn() -> Option<Version>>,
+    /// `None` when the input was invalid, `Some(None)` to clear the due date
+    pub update_due_date: Box<dyn Fn() -> Option<Option<NaiveDate>>>,
+    /// `None` when the input was invalid
+    pub update_estimate: Box<dyn Fn() -> Option<Estimates>>,
+    pub log_work: Box<dyn Fn() -> Option<WorkLog>>,
 }
 
+/// reads the answer to a prompt message, e.g. a line typed on the keyboard
+pub type PromptInput = Rc<dyn InputSource>;
+/// a prompt asking its questions with the given input
+type PromptFn<T> = fn(&dyn InputSource, &Palette) -> T;
+
 impl Prompts {
     pub fn new() -> Self {
+        Self::with_input(Rc::new(Keyboard::default()), Palette::default())
+    }
+
+    /// Prompts which read their answers from the given input, e.g. the modal prompts of the TUI,
+    /// and show their separators with the palette
+    pub fn with_input(input: PromptInput, palette: Palette) -> Self {
         Self {
-            create_epic: Box::new(create_epic_prompt),
-            create_story: Box::new(create_story_prompt),
-            delete_epic: Box::new(delete_epic_prompt),
-            delete_story: Box::new(delete_story_prompt),
-            update_status: Box::new(update_status_prompt),
+            create_project: prompt(&input, &palette, create_project_prompt),
+            create_epic: prompt(&input, &palette, create_epic_prompt),
+            create_story: prompt(&input, &palette, create_story_prompt),
+            delete_epic: prompt(&input, &palette, delete_epic_prompt),
+            delete_story: prompt(&input, &palette, delete_story_prompt),
+            update_status: prompt(&input, &palette, update_status_prompt),
+            update_story_type: prompt(&input, &palette, update_story_type_prompt),
+            update_fix_version: prompt(&input, &palette, update_fix_version_prompt),
+            create_version: prompt(&input, &palette, create_version_prompt),
+            update_due_date: prompt(&input, &palette, update_due_date_prompt),
+            update_estimate: prompt(&input, &palette, update_estimate_prompt),
+            log_work: prompt(&input, &palette, log_work_prompt),
         }
     }
 }
 
-fn get_keyboard_input(message: &str) -> String {
-    let text = message.to_string();
+fn prompt<T: 'static>(
+    input: &PromptInput,
+    palette: &Palette,
+    prompt: PromptFn<T>,
+) -> Box<dyn Fn() -> T> {
+    let input = Rc::clone(input);
+    let palette = palette.clone();
+    Box::new(move || prompt(input.as_ref(), &palette))
+}
 
-    let before: String = format!("[{QUERY_COLOR}");
-    let after = format!("{DFT}]");
+fn print_separator(input: &dyn InputSource, palette: &Palette) {
+    input.show_separator(&format!(
+        "{}{:-<width$}{}",
+        palette.query,
+        "",
+        palette.reset,
+        width = SEPERATOR_LINE_WIDTH
+    ));
+}
 
-    let text = text.replace('[', &before);
-    let text = text.replace(']', &after);
+fn print_error(input: &dyn InputSource, error: &anyhow::Error) {
+    input.show_error(&error.to_string());
+}
 
-    print!("{text}");
-    io::stdout().flush().unwrap();
-    get_user_input().trim().to_string()
+fn create_project_prompt(input: &dyn InputSource, palette: &Palette) -> Option<Project> {
+    print_separator(input, palette);
+    let key = input.read_line("Project Key (e.g. WEB):")?;
+    let name = input.read_line("Project Name        :")?;
+    Some(Project::new(key, name))
 }
 
-fn create_epic_prompt() -> Epic {
-    println!(
-        "{QUERY_COLOR}{:-<width$}{DFT}",
-        "",
-        width = SEPERATOR_LINE_WIDTH
-    );
-    let name = get_keyboard_input("Epic Name       :");
-    let description = get_keyboard_input("Epic Description:");
-    Epic::new(name, description)
+fn create_epic_prompt(input: &dyn InputSource, palette: &Palette) -> Option<Epic> {
+    print_separator(input, palette);
+    let name = input.read_line("Epic Name       :")?;
+    let description = input.read_line("Epic Description:")?;
+    Some(Epic::new(name, description))
 }
 
-fn create_story_prompt() -> Story {
-    println!(
-        "{QUERY_COLOR}{:-<width$}{DFT}",
-        "",
-        width = SEPERATOR_LINE_WIDTH
-    );
-    let name = get_keyboard_input("Story Name       :");
-    let description = get_keyboard_input("Story Description:");
-    Story::new(name, description)
+fn create_story_prompt(input: &dyn InputSource, palette: &Palette) -> Option<Story> {
+    print_separator(input, palette);
+    let name = input.read_line("Story Name       :")?;
+    let description = input.read_line("Story Description:")?;
+    Some(Story::new(name, description))
 }
 
-fn delete_epic_prompt() -> bool {
-    println!(
-        "{QUERY_COLOR}{:-<width$}{DFT}",
-        "",
-        width = SEPERATOR_LINE_WIDTH
-    );
-    get_keyboard_input("Are you sure you want to delete this epic? All stories in this epic will also be deleted [Y/n]:")== "Y"
+fn delete_epic_prompt(input: &dyn InputSource, palette: &Palette) -> bool {
+    print_separator(input, palette);
+    input.prompt("Are you sure you want to delete this epic? All stories in this epic will also be moved to the trash [Y/n]:")== "Y"
 }
 
-fn delete_story_prompt() -> bool {
-    println!(
-        "{QUERY_COLOR}{:-<width$}{DFT}",
-        "",
-        width = SEPERATOR_LINE_WIDTH
-    );
-    get_keyboard_input("Are you sure you want to delete this story? [Y/n]:") == "Y"
+fn delete_story_prompt(input: &dyn InputSource, palette: &Palette) -> bool {
+    print_separator(input, palette);
+    input.prompt("Are you sure you want to delete this story? [Y/n]:") == "Y"
 }
 
-fn update_status_prompt() -> Option<Status> {
-    println!(
-        "{QUERY_COLOR}{:-<width$}{DFT}",
-        "",
-        width = SEPERATOR_LINE_WIDTH
-    );
-    match get_keyboard_input(
-        "New Status ([1] - OPEN, [2] - IN-PROGRESS, [3] - RESOLVED, [4] - CLOSED):",
-    )
-    .as_str()
+fn update_status_prompt(input: &dyn InputSource, palette: &Palette) -> Option<Status> {
+    print_separator(input, palette);
+    match input
+        .prompt("New Status ([1] - OPEN, [2] - IN-PROGRESS, [3] - RESOLVED, [4] - CLOSED):")
+        .as_str()
     {
         "1" => Some(Status::Open),
         "2" => Some(Status::InProgress),
@@ -107,10 +140,203 @@ fn update_status_prompt() -> Option<Status> {
     }
 }
 
+fn update_story_type_prompt(input: &dyn InputSource, palette: &Palette) -> Option<StoryType> {
+    print_separator(input, palette);
+    match input
+        .prompt("New Type ([1] - STORY, [2] - BUG, [3] - TASK):")
+        .as_str()
+    {
+        "1" => Some(StoryType::Story),
+        "2" => Some(StoryType::Bug),
+        "3" => Some(StoryType::Task),
+        _ => None,
+    }
+}
+
+fn update_fix_version_prompt(input: &dyn InputSource, palette: &Palette) -> Option<Option<String>> {
+    print_separator(input, palette);
+    match input.prompt("Fix Version (or [-] to clear):").as_str() {
+        "" => None,
+        "-" => Some(None),
+        version => Some(Some(version.to_string())),
+    }
+}
+
+fn create_version_prompt(input: &dyn InputSource, palette: &Palette) -> Option<Version> {
+    print_separator(input, palette);
+    let name = input.prompt("Version Name                   :");
+    let release_date = input.prompt("Release Date (empty if unknown):");
+
+    if release_date.is_empty() {
+        return Some(Version::new(name, None));
+    }
+    match parse_date(&release_date, today()) {
+        Ok(release_date) => Some(Version::new(name, Some(release_date))),
+        Err(error) => {
+            print_error(input, &error);
+            None
+        }
+    }
+}
+
+fn update_due_date_prompt(input: &dyn InputSource, palette: &Palette) -> Option<Option<NaiveDate>> {
+    print_separator(input, palette);
+    let due_date = input.prompt("Due Date (e.g. 2026-11-01, +3d, next friday or [-] to clear):");
+    if due_date == "-" {
+        return Some(None);
+    }
+    match parse_date(&due_date, today()) {
+        Ok(date) => Some(Some(date)),
+        Err(error) => {
+            print_error(input, &error);
+            None
+        }
+    }
+}
+
+fn update_estimate_prompt(input: &dyn InputSource, palette: &Palette) -> Option<Estimates> {
+    print_separator(input, palette);
+    let original = input.prompt("Original Estimate (e.g. 2d 4h or [-] to clear):");
+    if original == "-" {
+        return Some((None, None));
+    }
+    let remaining = input.prompt("Remaining Estimate (empty for original estimate):");
+
+    let estimates = parse_duration(&original).and_then(|original| {
+        let remaining = match remaining.as_str() {
+            "" => original,
+            remaining => parse_duration(remaining)?,
+        };
+        Ok((Some(original), Some(remaining)))
+    });
+    match estimates {
+        Ok(estimates) => Some(estimates),
+        Err(error) => {
+            print_error(input, &error);
+            None
+        }
+    }
+}
+
+fn log_work_prompt(input: &dyn InputSource, palette: &Palette) -> Option<WorkLog> {
+    print_separator(input, palette);
+    let default_author = current_user_name();
+    let author = input.prompt(&format!("Author ({default_author}):"));
+    let duration = input.prompt("Time Spent (e.g. 1h 30m):");
+    let note = input.prompt("Note:");
+
+    let author = if author.is_empty() {
+        default_author
+    } else {
+        author
+    };
+    match parse_duration(&duration) {
+        Ok(duration) => Some(WorkLog {
+            author,
+            started: now(),
+            duration,
+            note,
+        }),
+        Err(error) => {
+            print_error(input, &error);
+            None
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::io_utils::ScriptedInput;
+
+    fn prompts(lines: &[&str]) -> (Prompts, Rc<ScriptedInput>) {
+        let input = Rc::new(ScriptedInput::new(lines));
+        (
+            Prompts::with_input(input.clone(), Palette::default()),
+            input,
+        )
+    }
+
     #[test]
-    fn dummy_test() {
-        todo!("make tests to overtake the keyboard");
+    fn create_prompts_should_be_cancelled_without_an_answer() {
+        let (prompts, input) = prompts(&["Checkout"]);
+
+        assert_eq!((prompts.create_epic)(), None);
+        assert_eq!((prompts.create_story)(), None);
+        // the description isn't asked for when the name is cancelled
+        assert_eq!(
+            *input.messages.borrow(),
+            vec![
+                "Epic Name       :".to_owned(),
+                "Epic Description:".to_owned(),
+                "Story Name       :".to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn create_epic_should_read_name_and_description() {
+        let (prompts, input) = prompts(&["Checkout", "Payment and order confirmation"]);
+
+        let epic = (prompts.create_epic)().unwrap();
+        assert_eq!(epic.name, "Checkout".to_owned());
+        assert_eq!(
+            epic.description,
+            "Payment and order confirmation".to_owned()
+        );
+        assert_eq!(
+            *input.messages.borrow(),
+            vec![
+                "Epic Name       :".to_owned(),
+                "Epic Description:".to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn delete_story_should_only_confirm_with_y() {
+        let (prompts, _) = prompts(&["Y", "n", "y"]);
+
+        assert_eq!((prompts.delete_story)(), true);
+        assert_eq!((prompts.delete_story)(), false);
+        assert_eq!((prompts.delete_story)(), false);
+        // the end of the input is no confirmation either
+        assert_eq!((prompts.delete_story)(), false);
+    }
+
+    #[test]
+    fn update_status_should_map_choices() {
+        let (prompts, _) = prompts(&["2", "4", "5"]);
+
+        assert_eq!((prompts.update_status)(), Some(Status::InProgress));
+        assert_eq!((prompts.update_status)(), Some(Status::Closed));
+        assert_eq!((prompts.update_status)(), None);
+    }
+
+    #[test]
+    fn update_estimate_should_default_remaining_to_original() {
+        let (prompts, _) = prompts(&["1h 30m", "", "2h", "45m", "-", "soon", ""]);
+
+        assert_eq!((prompts.update_estimate)(), Some((Some(90), Some(90))));
+        assert_eq!((prompts.update_estimate)(), Some((Some(120), Some(45))));
+        assert_eq!((prompts.update_estimate)(), Some((None, None)));
+        assert_eq!((prompts.update_estimate)(), None);
+    }
+
+    #[test]
+    fn log_work_should_read_author_duration_and_note() {
+        let (prompts, input) = prompts(&["alice", "2h", "card form", "", "lots", ""]);
+
+        let work_log = (prompts.log_work)().unwrap();
+        assert_eq!(work_log.author, "alice".to_owned());
+        assert_eq!(work_log.duration, 120);
+        assert_eq!(work_log.note, "card form".to_owned());
+        // an invalid duration
+        assert_eq!((prompts.log_work)().is_none(), true);
+        assert_eq!(input.errors.borrow().len(), 1);
+        assert_eq!(
+            input.errors.borrow()[0],
+            "Invalid duration 'lots'!".to_owned()
+        );
     }
 }
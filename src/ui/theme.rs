@@ -0,0 +1,404 @@
+/// This module contains the colour themes of the interactive app and the detection of the
+/// colours supported by the terminal.
+///
+/// A `Theme` describes the styles of the app in truecolor, a `Palette` holds the escape codes
+/// of a theme for the colours the terminal supports. Pages and prompts only use the palette.
+use std::env;
+use std::io::{self, IsTerminal};
+
+/// The colours a terminal can show
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// no escape codes at all, e.g. when the output is piped
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Detects the colours of the terminal from the environment, see `from_env`.
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var("NO_COLOR").ok().as_deref(),
+            io::stdout().is_terminal(),
+            env::var("TERM").ok().as_deref(),
+            env::var("COLORTERM").ok().as_deref(),
+        )
+    }
+
+    /// Colours are turned off when `NO_COLOR` is set (https://no-color.org) or the output
+    /// is not a terminal. Otherwise `COLORTERM` and `TERM` tell what the terminal supports.
+    pub fn from_env(
+        no_color: Option<&str>,
+        is_terminal: bool,
+        term: Option<&str>,
+        colorterm: Option<&str>,
+    ) -> Self {
+        if no_color.is_some_and(|value| !value.is_empty()) || !is_terminal {
+            return ColorSupport::None;
+        }
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorSupport::TrueColor;
+        }
+        match term {
+            Some("dumb") => ColorSupport::None,
+            Some(term) if term.contains("256color") => ColorSupport::Ansi256,
+            _ => ColorSupport::Ansi16,
+        }
+    }
+}
+
+/// A truecolor colour, converted to the nearest colour of the palette for other terminals
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+/// The 16 ANSI colours as shown by xterm, black to white followed by the bright colours
+const ANSI_16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+impl Color {
+    /// The nearest colour of the 6x6x6 colour cube of the 256 colour palette
+    fn to_ansi_256(self) -> u8 {
+        // the levels of the cube are 0, 95, 135, 175, 215 and 255
+        let level = |value: u8| match value {
+            0..=47 => 0,
+            48..=114 => 1,
+            value => (value - 35) / 40,
+        };
+        16 + 36 * level(self.0) + 6 * level(self.1) + level(self.2)
+    }
+
+    /// The index of the nearest of the 16 ANSI colours
+    fn to_ansi_16(self) -> u8 {
+        let distance = |(r, g, b): (u8, u8, u8)| {
+            [(r, self.0), (g, self.1), (b, self.2)]
+                .iter()
+                .map(|&(a, b)| (i32::from(a) - i32::from(b)).pow(2))
+                .sum::<i32>()
+        };
+        (0..ANSI_16.len())
+            .min_by_key(|&index| distance(ANSI_16[index]))
+            .unwrap_or(0) as u8
+    }
+
+    /// The SGR parameter setting this colour as foreground
+    fn sgr(self, support: ColorSupport) -> Option<String> {
+        match support {
+            ColorSupport::None => None,
+            ColorSupport::Ansi16 => match self.to_ansi_16() {
+                index @ 0..=7 => Some(format!("{}", 30 + index)),
+                index => Some(format!("{}", 90 + index - 8)),
+            },
+            ColorSupport::Ansi256 => Some(format!("38;5;{}", self.to_ansi_256())),
+            ColorSupport::TrueColor => Some(format!("38;2;{};{};{}", self.0, self.1, self.2)),
+        }
+    }
+}
+
+/// The look of a kind of text, e.g. the table headers
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    const fn color(r: u8, g: u8, b: u8) -> Self {
+        Style {
+            color: Some(Color(r, g, b)),
+            bold: false,
+            underline: false,
+        }
+    }
+
+    const fn bold(self) -> Self {
+        Style { bold: true, ..self }
+    }
+
+    const fn underline(self) -> Self {
+        Style {
+            underline: true,
+            ..self
+        }
+    }
+
+    /// The escape code of the style. It starts with a reset, so a style never inherits
+    /// the attributes of the text before it.
+    fn escape(self, support: ColorSupport) -> String {
+        if support == ColorSupport::None {
+            return String::new();
+        }
+
+        let mut parameters = vec!["0".to_owned()];
+        if self.bold {
+            parameters.push("1".to_owned());
+        }
+        if self.underline {
+            parameters.push("4".to_owned());
+        }
+        parameters.extend(self.color.and_then(|color| color.sgr(support)));
+        format!("\x1B[{}m", parameters.join(";"))
+    }
+}
+
+/// The styles of the app
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Theme {
+    pub name: &'static str,
+    /// table headers and separators
+    pub header: Style,
+    /// the keys in the help lines and the prompts
+    pub query: Style,
+    /// rows of overdue items
+    pub overdue: Style,
+    /// error messages
+    pub error: Style,
+}
+
+const NO_STYLE: Style = Style {
+    color: None,
+    bold: false,
+    underline: false,
+};
+
+/// The built-in themes, the first one is the default
+pub const THEMES: [Theme; 4] = [
+    Theme {
+        name: "default",
+        header: Style::color(20, 198, 13),
+        query: Style::color(255, 0, 0),
+        overdue: Style::color(255, 0, 0),
+        error: Style::color(255, 0, 0),
+    },
+    // darker colours which are readable on a white background
+    Theme {
+        name: "light",
+        header: Style::color(0, 110, 0),
+        query: Style::color(175, 0, 0),
+        overdue: Style::color(175, 0, 0),
+        error: Style::color(175, 0, 0),
+    },
+    Theme {
+        name: "high-contrast",
+        header: Style::color(0, 255, 255).bold(),
+        query: Style::color(255, 255, 0).bold(),
+        overdue: Style::color(255, 0, 255).bold().underline(),
+        error: Style::color(255, 0, 0).bold(),
+    },
+    // only text attributes, for terminals with a custom colour scheme
+    Theme {
+        name: "monochrome",
+        header: NO_STYLE.bold(),
+        query: NO_STYLE.bold(),
+        overdue: NO_STYLE.underline(),
+        error: NO_STYLE.bold(),
+    },
+];
+
+impl Default for Theme {
+    fn default() -> Self {
+        THEMES[0]
+    }
+}
+
+impl Theme {
+    /// The built-in theme with the given name, case-insensitive
+    pub fn by_name(name: &str) -> Option<Theme> {
+        THEMES
+            .into_iter()
+            .find(|theme| theme.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The names of the built-in themes, e.g. for the usage
+    pub fn names() -> Vec<&'static str> {
+        THEMES.iter().map(|theme| theme.name).collect()
+    }
+
+    /// The escape codes of the theme for the colours of the terminal
+    pub fn palette(&self, support: ColorSupport) -> Palette {
+        let escape = |code: &str| match support {
+            ColorSupport::None => String::new(),
+            _ => code.to_owned(),
+        };
+
+        Palette {
+            header: self.header.escape(support),
+            query: self.query.escape(support),
+            overdue: self.overdue.escape(support),
+            error: self.error.escape(support),
+            selected: escape("\x1B[7m"),
+            reset: escape("\x1B[0m"),
+        }
+    }
+}
+
+/// The escape codes the pages and prompts are drawn with, all empty without colours
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Palette {
+    pub header: String,
+    pub query: String,
+    pub overdue: String,
+    pub error: String,
+    /// added to the colour of the selected row in the TUI
+    pub selected: String,
+    pub reset: String,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Theme::default().palette(ColorSupport::TrueColor)
+    }
+}
+
+impl Palette {
+    pub fn has_colors(&self) -> bool {
+        !self.reset.is_empty()
+    }
+
+    /// Highlights the keys in brackets, e.g. `[q] quit`
+    pub fn highlight_keys(&self, text: &str) -> String {
+        text.replace('[', &format!("[{}", self.query))
+            .replace(']', &format!("{}]", self.reset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_env_should_turn_off_colors() {
+        let truecolor = Some("truecolor");
+        assert_eq!(
+            ColorSupport::from_env(Some("1"), true, Some("xterm"), truecolor),
+            ColorSupport::None
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, false, Some("xterm"), truecolor),
+            ColorSupport::None
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, true, Some("dumb"), None),
+            ColorSupport::None
+        );
+        // an empty NO_COLOR is ignored
+        assert_eq!(
+            ColorSupport::from_env(Some(""), true, Some("xterm"), truecolor),
+            ColorSupport::TrueColor
+        );
+    }
+
+    #[test]
+    fn from_env_should_detect_colors() {
+        assert_eq!(
+            ColorSupport::from_env(None, true, Some("xterm-256color"), Some("24bit")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, true, Some("xterm-256color"), None),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, true, Some("xterm"), None),
+            ColorSupport::Ansi16
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, true, None, None),
+            ColorSupport::Ansi16
+        );
+    }
+
+    #[test]
+    fn color_should_fall_back_to_nearest_color() {
+        assert_eq!(Color(20, 198, 13).to_ansi_256(), 40);
+        assert_eq!(Color(255, 0, 0).to_ansi_256(), 196);
+        assert_eq!(Color(0, 0, 0).to_ansi_256(), 16);
+        assert_eq!(Color(255, 255, 255).to_ansi_256(), 231);
+
+        assert_eq!(Color(20, 198, 13).to_ansi_16(), 2);
+        assert_eq!(Color(255, 0, 0).to_ansi_16(), 9);
+        assert_eq!(Color(175, 0, 0).to_ansi_16(), 1);
+    }
+
+    #[test]
+    fn palette_should_use_escapes_of_color_support() {
+        let theme = Theme::default();
+
+        assert_eq!(
+            theme.palette(ColorSupport::TrueColor).header,
+            "\x1B[0;38;2;20;198;13m".to_owned()
+        );
+        assert_eq!(
+            theme.palette(ColorSupport::Ansi256).header,
+            "\x1B[0;38;5;40m".to_owned()
+        );
+        assert_eq!(
+            theme.palette(ColorSupport::Ansi16).overdue,
+            "\x1B[0;91m".to_owned()
+        );
+        assert_eq!(
+            theme.palette(ColorSupport::None),
+            Palette {
+                header: "".to_owned(),
+                query: "".to_owned(),
+                overdue: "".to_owned(),
+                error: "".to_owned(),
+                selected: "".to_owned(),
+                reset: "".to_owned(),
+            }
+        );
+        assert_eq!(theme.palette(ColorSupport::None).has_colors(), false);
+    }
+
+    #[test]
+    fn monochrome_should_only_use_attributes() {
+        let palette = Theme::by_name("Monochrome")
+            .unwrap()
+            .palette(ColorSupport::TrueColor);
+
+        assert_eq!(palette.header, "\x1B[0;1m".to_owned());
+        assert_eq!(palette.overdue, "\x1B[0;4m".to_owned());
+        assert_eq!(
+            Theme::by_name("high-contrast")
+                .unwrap()
+                .palette(ColorSupport::Ansi16)
+                .overdue,
+            "\x1B[0;1;4;95m".to_owned()
+        );
+        assert_eq!(Theme::by_name("sepia").is_none(), true);
+    }
+
+    #[test]
+    fn highlight_keys_should_color_brackets() {
+        let palette = Theme::default().palette(ColorSupport::Ansi16);
+
+        assert_eq!(
+            palette.highlight_keys("[q] quit"),
+            "[\x1B[0;91mq\x1B[0m] quit".to_owned()
+        );
+        assert_eq!(
+            Palette::default()
+                .highlight_keys("[q] quit")
+                .contains("38;2;255;0;0"),
+            true
+        );
+    }
+}